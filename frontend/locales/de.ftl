@@ -0,0 +1,348 @@
+# German translation of en.ftl.
+
+## Common
+
+close = Schließen
+cancel = Abbrechen
+loading = Wird geladen...
+
+## Settings
+
+settings-language = Sprache
+settings-language-browser = Wie im Browser
+settings-tab-width = Tabulatorbreite
+settings-theme = Farbschema
+settings-colorblind = Farbenblind-freundliche Diagnosen
+settings-keybindings = Tastenbelegung
+settings-font-size = Schriftgröße
+settings-font = Schriftart
+settings-whitespace = Leerzeichen anzeigen
+settings-word-wrap = Lange Zeilen umbrechen
+settings-minimap = Minimap
+settings-autorun-shared = Geteilten Code beim Öffnen ausführen
+settings-live = Nach dem Tippen ausführen
+settings-live-off = Aus
+settings-live-delay = nach { $ms } ms
+settings-collab-name = Dein Name
+settings-split = Editor und Ausgabe
+settings-swap-panes = Ausgabe zuerst
+theme-system = System
+theme-light = Hell
+theme-dark = Dunkel
+theme-high-contrast = Hoher Kontrast
+keybindings-default = Standard
+whitespace-none = Nie
+whitespace-selection = In der Auswahl
+whitespace-boundary = Außer zwischen Wörtern
+whitespace-all = Immer
+font-default = Standard
+font-system-monospace = Monospace des Systems
+split-side-by-side = Nebeneinander
+split-stacked = Übereinander
+
+## Build options
+
+build-options = Build-Optionen
+build-options-yew = Yew-Version
+build-options-toolchain = Toolchain
+build-options-edition = Edition
+build-options-profile = Profil
+build-options-optimization = Optimierung
+build-options-compare = Vergleichen mit
+build-options-compare-off = Aus
+build-options-benchmark = Benchmark
+build-options-benchmark-off = Aus
+build-options-benchmark-renders = { $count } Re-Renders
+compare-version = Yew { $version }
+toolchain-stable = Stable
+toolchain-beta = Beta
+toolchain-nightly = Nightly
+profile-release = Release
+profile-debug = Debug
+profile-min-size = Kleinste Größe
+optimization-none = Keine
+optimization-codegen-units = Eine Codegen-Unit
+optimization-simd = SIMD
+
+## Status bar
+
+status-cursor = Z. { $line }, Sp. { $column }
+status-selected = { $count } ausgewählt
+status-change-options = Build-Optionen ändern
+status-edition = Edition { $edition }
+status-yew = Yew { $version }
+
+## Clippy
+
+clippy = Clippy
+clippy-running = Clippy läuft...
+clippy-clean = Keine Lints, der Code sieht gut aus.
+clippy-close = Clippy-Bereich schließen
+clippy-errors = Fehler ({ $count })
+clippy-warnings = Warnungen ({ $count })
+clippy-notes = Hinweise ({ $count })
+clippy-help = Hilfe ({ $count })
+
+## Console
+
+console = Konsole
+console-levels = Konsolenstufen
+console-errors = Fehler
+console-warnings = Warnungen
+console-info = Info
+console-logs = Logs
+console-debug = Debug
+console-clear = Leeren
+console-clear-label = Konsole leeren
+console-compile-error = Kompilierung fehlgeschlagen
+console-uncaught = Nicht abgefangen: { $message }
+console-input = JavaScript in der Vorschau auswerten, ihre Exporte liegen auf `wasm`
+network-empty = Die Vorschau hat keine Anfragen gestellt.
+network-pending = Ausstehend
+network-failed = Fehlgeschlagen
+network-method = Methode
+network-url = URL
+network-status = Status
+network-time = Dauer
+network-clear-label = Anfragen leeren
+inspector-empty = Führe den Code aus, um die Elemente der Vorschau zu sehen.
+inspector-refresh = Aktualisieren
+inspector-truncated = Die Seite hat mehr Elemente als angezeigt werden.
+
+## Keyboard shortcuts
+
+shortcuts = Tastenkürzel
+shortcut-run = Ausführen
+shortcut-share = Teilen
+shortcut-format = Formatieren
+shortcut-show-shortcuts = Tastenkürzel anzeigen
+shortcut-command-palette = Befehlspalette öffnen
+shortcut-find = In der Datei suchen, auch mit regulären Ausdrücken
+shortcut-find-keys = Strg/⌘ + F
+shortcut-replace = In der Datei ersetzen
+shortcut-replace-keys = Strg + H, unter macOS ⌘ + ⌥ + F
+shortcut-tab-focus = Tab den Fokus aus dem Editor bewegen lassen
+shortcut-tab-focus-keys = Strg + M
+key-ctrl = Strg/⌘
+key-shift = Umschalt
+
+## Command palette
+
+command-palette = Befehlspalette
+command-search = Befehl eingeben
+command-no-match = Kein Befehl gefunden.
+command-run = Code ausführen
+command-share = Teilen
+command-format = Code formatieren
+command-show-shortcuts = Tastenkürzel anzeigen
+command-clippy = Mit Clippy prüfen
+command-expand = Makros expandieren
+command-download = Als Cargo-Projekt herunterladen
+command-export-image = Die Vorschau als Bild exportieren
+command-fork = In ein eigenes Snippet forken
+command-copy-markdown = Als Markdown kopieren
+command-collaborate = Code gemeinsam mit anderen bearbeiten
+command-show-changes = Mit dem geteilten Code vergleichen
+command-compare-versions = Yew-Versionen vergleichen
+command-toggle-benchmark = Benchmark-Modus umschalten
+command-toggle-theme = Farbschema wechseln
+command-toggle-split = Zwischen nebeneinander und übereinander wechseln
+command-swap-panes = Editor und Ausgabe tauschen
+command-open-examples = Beispiele öffnen
+command-search-snippets = Geteilte Snippets durchsuchen
+command-open-settings = Einstellungen öffnen
+command-open-build-options = Build-Optionen öffnen
+command-open-dependencies = Abhängigkeiten öffnen
+command-open-history = Verlauf öffnen
+command-open-revisions = Revisionen öffnen
+
+## Dependencies
+
+dependencies = Abhängigkeiten
+dependencies-search = Crates suchen
+dependencies-no-match = Kein Crate gefunden.
+dependencies-no-releases = { $name } hat keine stabilen Releases
+dependencies-lookup-failed = { $name } konnte nicht nachgeschlagen werden
+dependencies-version = Version
+dependencies-features = Features
+dependencies-add = Hinzufügen
+dependencies-remove = { $name } entfernen
+dependencies-empty = Noch nichts hinzugefügt, Yew und was es braucht sind immer dabei.
+
+## History
+
+history = Verlauf
+history-empty = Noch keine früheren Versionen.
+history-restore = Diese Version wiederherstellen
+
+## File tabs
+
+files = Dateien
+files-new = Neue Datei
+files-new-name = Name der neuen Datei
+files-new-style = Stylesheet für die Vorschau hinzufügen
+files-style = + CSS
+files-new-head = Markup für den Kopf der Vorschau hinzufügen
+files-head = + HTML
+files-rename-name = Neuer Name der Datei
+files-rename-hint = Doppelklick oder F2 zum Umbenennen
+files-exists = { $name } gibt es schon
+files-invalid-name = Dateinamen müssen Modulnamen in snake_case sein
+files-reserved-name = main.rs und lib.rs sind reserviert
+files-delete = Löschen
+files-delete-named = { $name } löschen
+files-delete-confirm = { $name } löschen?
+files-changed = Seit dem letzten Ausführen geändert
+files-close-named = { $name } schließen
+files-expanded = main.rs (expandiert)
+
+## Output
+
+expansion-running = Makros werden expandiert...
+glue-unavailable = Nur erfolgreiche Durchläufe haben Glue-Code.
+glue-loading = Glue-Code wird geladen...
+glue-failed = Der Glue-Code konnte nicht geladen werden.
+page-source-unavailable = Die Vorschau wurde noch nicht geladen.
+diff = Änderungen am geteilten Code
+build-stats-wasm = wasm: { $size }
+build-stats-gzipped = gzip: { $size }
+build-stats-js = js: { $size }
+build-stats-time = gebaut in { $seconds } s
+benchmark-mount = Mount: { $time }
+benchmark-median = Re-Render: { $time } Median aus { $count }
+benchmark-range = schnellster { $min }, langsamster { $max }
+build-progress = Build-Fortschritt
+stage-queued = In der Warteschlange
+stage-compiling-dependencies = Abhängigkeiten werden kompiliert
+stage-compiling-snippet = Snippet wird kompiliert
+stage-bindgen = Bindings werden generiert
+stage-optimizing = Optimierung
+run-failed = Der Code konnte nicht ausgeführt werden
+run-cancelled = Ausführung abgebrochen
+fullscreen = Vollbild
+fullscreen-exit = Vollbild beenden (Esc)
+export-image = Als Bild exportieren
+export-image-running = Wird exportiert...
+export-image-failed = Die Vorschau konnte nicht als Bild exportiert werden
+
+## Sharing
+
+share = Teilen
+share-title = Titel
+share-description = Beschreibung
+share-tags = Tags
+share-tags-placeholder = Bis zu { $count }, durch Kommas getrennt
+share-failed = Der Code konnte nicht geteilt werden
+share-copied = Link in die Zwischenablage kopiert
+share-copy-failed = Geteilt, kopiere den Link aus der Adressleiste
+fork = Forken
+fork-title = In ein eigenes Snippet forken
+fork-failed = Das Snippet konnte nicht geforkt werden
+forked = Geforkt, der Link zeigt jetzt auf deine Kopie
+forked-from = Geforkt von { $id }
+markdown = Als Markdown kopieren
+markdown-title = Ein Badge mit Link zum Snippet als Markdown kopieren
+markdown-embed = Eine Einbettung des Playgrounds hinzufügen
+markdown-copy = Kopieren
+markdown-copied = Markdown in die Zwischenablage kopiert
+markdown-copy-failed = Das Markdown konnte nicht kopiert werden
+
+## Collaboration
+
+collab = Gemeinsam bearbeiten
+collab-title = Den Code gemeinsam mit anderen bearbeiten
+collab-copy = Link zum Raum kopieren
+collab-leave = Raum verlassen
+collab-connecting = Verbinde...
+collab-connected = Live
+collab-disconnected = Verbinde neu...
+collab-copied = Link zum Raum kopiert, wer ihn öffnet, bearbeitet den Code mit dir
+collab-copy-failed = Raum geöffnet, kopiere den Link aus der Adressleiste
+collab-guest = Gast
+collab-participants = Andere im Raum: { $count }
+
+## Revisions
+
+revisions = Revisionen
+revisions-original = Wie geteilt
+revisions-copy = Link zu dieser Revision kopieren
+revisions-copy-short = Link kopieren
+revisions-copy-failed = Kopieren fehlgeschlagen, öffne die Revision und kopiere den Link aus der Adressleiste
+revisions-name = Name der Revision
+revisions-save = Speichern
+revisions-saved = Revision gespeichert
+revisions-save-failed = Die Revision konnte nicht gespeichert werden, versuche es noch einmal.
+revisions-not-owned = Nur wer das Snippet geteilt hat, kann Revisionen davon speichern.
+
+## Examples
+
+examples = Beispiele
+examples-failed = Die Beispiele konnten nicht geladen werden.
+examples-back = Zurück zum Editor
+search = Suche
+search-text = Titel oder Beschreibung
+search-tag = Tag
+search-hint = Durchsucht die 1000 zuletzt geteilten Snippets, die neuesten zuerst.
+search-empty = Keine Snippets gefunden.
+search-failed = Die Snippets konnten nicht durchsucht werden.
+search-untitled = Snippet ohne Titel
+recent = Zuletzt
+recent-empty = Snippets, die du öffnest oder bearbeitest, erscheinen hier.
+recent-shared = Geteiltes Snippet { $id }
+recent-gist = Gist { $id }
+recent-example = Beispiel { $id }
+recent-link = Snippet aus einem Link
+
+## Dropping files
+
+drop-files = .rs-Dateien hier ablegen, um sie zu öffnen
+drop-not-rust = Nur .rs-Dateien können geöffnet werden
+drop-skipped = { $name } übersprungen, { $reason }
+drop-read-failed = { $name } konnte nicht gelesen werden
+drop-overwrite-confirm = Deine Änderungen an { $names } durch die abgelegten Dateien ersetzen?
+
+## Editor and output
+
+run = Ausführen
+format = Formatieren
+format-unchanged = Schon formatiert
+format-not-rust = Nur Rust-Code wird formatiert
+format-parse-error = Der Code lässt sich nicht parsen und daher nicht formatieren
+format-failed = Der Code konnte nicht formatiert werden
+clippy-title = Mit Clippy prüfen
+clippy-failed = Clippy konnte nicht ausgeführt werden
+expand = Expandieren
+expand-title = Makros expandieren
+expansion-failed = Die Makros konnten nicht expandiert werden
+download-title = Als Cargo-Projekt herunterladen
+download-failed = Das Projekt konnte nicht heruntergeladen werden
+changes = Änderungen
+changes-title = Mit dem geteilten Code vergleichen
+toggle-theme = Farbschema wechseln
+settings = Einstellungen
+open-playground = Im Playground öffnen ↗
+skip-to-editor = Zum Editor springen
+skip-to-output = Zur Ausgabe springen
+skip-links = Sprunglinks
+restore-session = Den Code der letzten Sitzung wiederherstellen?
+restore = Wiederherstellen
+discard = Verwerfen
+editor = Editor
+output = Ausgabe
+output-preview = Vorschau
+output-glue = JS-Glue
+output-html = HTML
+output-network = Netzwerk
+output-elements = Elemente
+panes = Bereiche
+panes-code = Code
+panes-both = Beides
+autorun-loading = Der Code wird zum Ausführen geladen...
+announce-building = Wird gebaut
+announce-build-failed-one = Build mit 1 Fehler fehlgeschlagen
+announce-build-failed-other = Build mit { $count } Fehlern fehlgeschlagen
+announce-build-finished = Build nach { $seconds } Sekunden fertig
+connection-offline = Du bist offline. Der Editor funktioniert weiter, zum Ausführen braucht es eine Verbindung.
+connection-unreachable = Der Server des Playgrounds ist nicht erreichbar, neuer Versuch im Hintergrund.
+connection-unavailable = Der Compiler ist gerade nicht verfügbar, neuer Versuch im Hintergrund.
+present-exit = Präsentation beenden
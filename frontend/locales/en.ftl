@@ -0,0 +1,351 @@
+# The UI strings of the playground. A translation copies this file under its locale's
+# tag and replaces the values, ids are left as they are. `{ $name }` is replaced with
+# the value called `name`.
+
+## Common
+
+close = Close
+cancel = Cancel
+loading = Loading...
+
+## Settings
+
+settings-language = Language
+settings-language-browser = Browser default
+settings-tab-width = Tab width
+settings-theme = Theme
+settings-colorblind = Colourblind-safe diagnostics
+settings-keybindings = Key bindings
+settings-font-size = Font size
+settings-font = Font
+settings-whitespace = Show whitespace
+settings-word-wrap = Wrap long lines
+settings-minimap = Minimap
+settings-autorun-shared = Run shared code on opening
+settings-live = Run when typing stops
+settings-live-off = Off
+settings-live-delay = after { $ms } ms
+settings-collab-name = Your name
+settings-split = Editor and output
+settings-swap-panes = Output first
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+theme-high-contrast = High contrast
+keybindings-default = Default
+whitespace-none = Never
+whitespace-selection = In the selection
+whitespace-boundary = Except between words
+whitespace-all = Always
+font-default = Default
+font-system-monospace = System monospace
+split-side-by-side = Side by side
+split-stacked = Stacked
+
+## Build options
+
+build-options = Build options
+build-options-yew = Yew version
+build-options-toolchain = Toolchain
+build-options-edition = Edition
+build-options-profile = Profile
+build-options-optimization = Optimization
+build-options-compare = Compare with
+build-options-compare-off = Off
+build-options-benchmark = Benchmark
+build-options-benchmark-off = Off
+build-options-benchmark-renders = { $count } re-renders
+compare-version = Yew { $version }
+toolchain-stable = Stable
+toolchain-beta = Beta
+toolchain-nightly = Nightly
+profile-release = Release
+profile-debug = Debug
+profile-min-size = Smallest size
+optimization-none = None
+optimization-codegen-units = Single codegen unit
+optimization-simd = SIMD
+
+## Status bar
+
+status-cursor = Ln { $line }, Col { $column }
+status-selected = { $count } selected
+status-change-options = Change the build options
+status-edition = Edition { $edition }
+status-yew = Yew { $version }
+
+## Clippy
+
+clippy = Clippy
+clippy-running = Running clippy...
+clippy-clean = No lints, the code looks good.
+clippy-close = Close the clippy panel
+clippy-errors = Errors ({ $count })
+clippy-warnings = Warnings ({ $count })
+clippy-notes = Notes ({ $count })
+clippy-help = Help ({ $count })
+
+## Console
+
+console = Console
+console-levels = Console levels
+console-errors = Errors
+console-warnings = Warnings
+console-info = Info
+console-logs = Logs
+console-debug = Debug
+console-clear = Clear
+console-clear-label = Clear the console
+console-compile-error = Compilation failed
+console-uncaught = Uncaught: { $message }
+console-input = Evaluate JavaScript in the preview, its exports are on `wasm`
+network-empty = The preview made no requests.
+network-pending = Pending
+network-failed = Failed
+network-method = Method
+network-url = URL
+network-status = Status
+network-time = Time
+network-clear-label = Clear the requests
+inspector-empty = Run the code to see the preview's elements.
+inspector-refresh = Refresh
+inspector-truncated = The page has more elements than are shown.
+
+## Keyboard shortcuts
+
+shortcuts = Keyboard shortcuts
+shortcut-run = Run
+shortcut-share = Share
+shortcut-format = Format
+shortcut-show-shortcuts = Show keyboard shortcuts
+shortcut-command-palette = Open the command palette
+shortcut-find = Find in the file, regular expressions included
+shortcut-find-keys = Ctrl/⌘ + F
+shortcut-replace = Replace in the file
+shortcut-replace-keys = Ctrl + H, ⌘ + ⌥ + F on macOS
+shortcut-tab-focus = Toggle Tab moving the focus out of the editor
+shortcut-tab-focus-keys = Ctrl + M
+# Ctrl stands for Cmd on macOS as well
+key-ctrl = Ctrl/⌘
+key-shift = Shift
+
+## Command palette
+
+command-palette = Command palette
+command-search = Type a command
+command-no-match = No command matches.
+command-run = Run the code
+command-share = Share
+command-format = Format the code
+command-show-shortcuts = Show keyboard shortcuts
+command-clippy = Lint with clippy
+command-expand = Expand macros
+command-download = Download as a cargo project
+command-export-image = Export the preview as an image
+command-fork = Fork into a snippet of your own
+command-copy-markdown = Copy as Markdown
+command-collaborate = Edit the code together with others
+command-show-changes = Compare with the shared code
+command-compare-versions = Compare Yew versions
+command-toggle-benchmark = Toggle benchmark mode
+command-toggle-theme = Toggle the theme
+command-toggle-split = Toggle side by side and stacked panes
+command-swap-panes = Swap the editor and the output
+command-open-examples = Open the examples
+command-search-snippets = Search the shared snippets
+command-open-settings = Open the settings
+command-open-build-options = Open the build options
+command-open-dependencies = Open the dependencies
+command-open-history = Open the history
+command-open-revisions = Open the revisions
+
+## Dependencies
+
+dependencies = Dependencies
+dependencies-search = Search crates
+dependencies-no-match = No crate matches.
+dependencies-no-releases = { $name } has no stable releases
+dependencies-lookup-failed = Failed to look up { $name }
+dependencies-version = Version
+dependencies-features = Features
+dependencies-add = Add
+dependencies-remove = Remove { $name }
+dependencies-empty = Nothing added yet, Yew and what it needs are always there.
+
+## History
+
+history = History
+history-empty = No earlier versions yet.
+history-restore = Restore this version
+
+## File tabs
+
+files = Files
+files-new = New file
+files-new-name = Name of the new file
+files-new-style = Add a style sheet for the preview
+files-style = + CSS
+files-new-head = Add markup to the head of the preview
+files-head = + HTML
+files-rename-name = New name of the file
+files-rename-hint = Double-click or press F2 to rename
+files-exists = { $name } already exists
+files-invalid-name = file names must be snake_case module names
+files-reserved-name = main.rs and lib.rs are reserved
+files-delete = Delete
+files-delete-named = Delete { $name }
+files-delete-confirm = Delete { $name }?
+files-changed = Changed since the last run
+files-close-named = Close { $name }
+files-expanded = main.rs (expanded)
+
+## Output
+
+expansion-running = Expanding macros...
+glue-unavailable = Only successful runs have glue code to show.
+glue-loading = Loading the glue code...
+glue-failed = Failed to load the glue code.
+page-source-unavailable = The preview hasn't been loaded yet.
+diff = Changes to the shared code
+build-stats-wasm = wasm: { $size }
+build-stats-gzipped = gzipped: { $size }
+build-stats-js = js: { $size }
+build-stats-time = built in { $seconds } s
+benchmark-mount = mount: { $time }
+benchmark-median = re-render: { $time } median of { $count }
+benchmark-range = fastest { $min }, slowest { $max }
+build-progress = Build progress
+stage-queued = Queued
+stage-compiling-dependencies = Compiling dependencies
+stage-compiling-snippet = Compiling the snippet
+stage-bindgen = Generating bindings
+stage-optimizing = Optimizing
+run-failed = Failed to run the code
+run-cancelled = Run cancelled
+fullscreen = Fullscreen
+fullscreen-exit = Exit fullscreen (Esc)
+export-image = Export image
+export-image-running = Exporting...
+export-image-failed = Failed to export the preview as an image
+
+## Sharing
+
+share = Share
+share-title = Title
+share-description = Description
+share-tags = Tags
+share-tags-placeholder = Up to { $count }, separated by commas
+share-failed = Failed to share the code
+share-copied = Link copied to clipboard
+share-copy-failed = Shared, copy the link from the address bar
+fork = Fork
+fork-title = Fork into a snippet of your own
+fork-failed = Failed to fork the snippet
+forked = Forked, the link now points to your copy
+forked-from = Forked from { $id }
+markdown = Copy as Markdown
+markdown-title = Copy a badge linking to the snippet as Markdown
+markdown-embed = Include an embed of the playground
+markdown-copy = Copy
+markdown-copied = Markdown copied to clipboard
+markdown-copy-failed = Failed to copy the Markdown
+
+## Collaboration
+
+collab = Collaborate
+collab-title = Edit the code together with others
+collab-copy = Copy the link to the room
+collab-leave = Leave the room
+collab-connecting = Connecting...
+collab-connected = Live
+collab-disconnected = Reconnecting...
+collab-copied = Link to the room copied, whoever opens it edits the code with you
+collab-copy-failed = Room opened, copy the link from the address bar
+collab-guest = Guest
+collab-participants = Others in the room: { $count }
+
+## Revisions
+
+revisions = Revisions
+revisions-original = As shared
+revisions-copy = Copy the link to this revision
+revisions-copy-short = Copy link
+revisions-copy-failed = Copying the link failed, open the revision and copy it from the address bar
+revisions-name = Name of the revision
+revisions-save = Save
+revisions-saved = Revision saved
+revisions-save-failed = The revision couldn't be saved, try again.
+revisions-not-owned = Only whoever shared the snippet can save revisions of it.
+
+## Examples
+
+examples = Examples
+examples-failed = Failed to load the examples.
+examples-back = Back to the editor
+search = Search
+search-text = Title or description
+search-tag = Tag
+search-hint = Looks through the 1000 snippets shared last, newest first.
+search-empty = No snippets found.
+search-failed = Failed to search the snippets.
+search-untitled = Untitled snippet
+recent = Recent
+recent-empty = Snippets you open or edit show up here.
+recent-shared = Shared snippet { $id }
+recent-gist = Gist { $id }
+recent-example = Example { $id }
+recent-link = Snippet from a link
+
+## Dropping files
+
+drop-files = Drop .rs files to open them
+drop-not-rust = Only .rs files can be opened
+drop-skipped = Skipped { $name }, { $reason }
+drop-read-failed = Failed to read { $name }
+drop-overwrite-confirm = Replace your changes to { $names } with the dropped files?
+
+## Editor and output
+
+run = Run
+format = Format
+format-unchanged = Already formatted
+format-not-rust = Only Rust code is formatted
+format-parse-error = Couldn't format the code, it doesn't parse
+format-failed = Failed to format the code
+clippy-title = Lint with clippy
+clippy-failed = Failed to run clippy
+expand = Expand
+expand-title = Expand macros
+expansion-failed = Failed to expand the macros
+download-title = Download as a cargo project
+download-failed = Failed to download the project
+changes = Changes
+changes-title = Compare with the shared code
+toggle-theme = Toggle theme
+settings = Settings
+open-playground = Open in the playground ↗
+skip-to-editor = Skip to the editor
+skip-to-output = Skip to the output
+skip-links = Skip links
+restore-session = Restore the code from your previous session?
+restore = Restore
+discard = Discard
+editor = Editor
+output = Output
+output-preview = Preview
+output-glue = JS glue
+output-html = HTML
+output-network = Network
+output-elements = Elements
+panes = Panes
+panes-code = Code
+panes-both = Both
+autorun-loading = Loading the code to run it...
+announce-building = Building
+announce-build-failed-one = Build failed with 1 error
+announce-build-failed-other = Build failed with { $count } errors
+announce-build-finished = Build finished in { $seconds } seconds
+connection-offline = You're offline. The editor keeps working, running the code needs a connection.
+connection-unreachable = Can't reach the playground's server, retrying in the background.
+connection-unavailable = The compiler is unavailable right now, retrying in the background.
+present-exit = Exit presentation
@@ -0,0 +1,77 @@
+use gloo::events::EventListener;
+use js_sys::{ArrayBuffer, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{BinaryType, MessageEvent, WebSocket};
+use yew::Callback;
+
+use super::BACKEND_URL;
+
+/// A fresh id to open a room under, unguessable so only those with the link get in.
+pub fn new_room_id() -> String {
+    gloo::utils::window()
+        .crypto()
+        .expect("the page has no crypto")
+        .random_uuid()
+}
+
+pub enum RoomEvent {
+    /// A Yjs update, the first one is the room's whole document.
+    Update(Vec<u8>),
+    /// The presence of someone in the room as JSON, as the backend passes it on.
+    Presence(String),
+    /// The connection was lost or couldn't be made, nothing is sent or received anymore.
+    Closed,
+}
+
+/// A connection to a room of the backend, closed when dropped.
+pub struct Room {
+    socket: WebSocket,
+    _listeners: [EventListener; 2],
+}
+
+impl Room {
+    /// Passes the update of a local edit on to the others in the room.
+    pub fn send(&self, update: &[u8]) {
+        if self.socket.ready_state() == WebSocket::OPEN {
+            let _ = self.socket.send_with_u8_array(update);
+        }
+    }
+
+    /// Tells the others in the room who's here and where in the code, as JSON.
+    pub fn send_presence(&self, presence: &str) {
+        if self.socket.ready_state() == WebSocket::OPEN {
+            let _ = self.socket.send_with_str(presence);
+        }
+    }
+}
+
+impl Drop for Room {
+    fn drop(&mut self) {
+        let _ = self.socket.close();
+    }
+}
+
+/// Joins the room with the given id, opening it if nobody's in it.
+pub fn join(id: &str, onevent: Callback<RoomEvent>) -> Result<Room, JsValue> {
+    // `http` becomes `ws` and `https` becomes `wss`
+    let url = format!("{}/collab/{}", BACKEND_URL.replacen("http", "ws", 1), id);
+    let socket = WebSocket::new(&url)?;
+    socket.set_binary_type(BinaryType::Arraybuffer);
+    let message = {
+        let onevent = onevent.clone();
+        EventListener::new(&socket, "message", move |e| {
+            let data = e.unchecked_ref::<MessageEvent>().data();
+            if let Some(buffer) = data.dyn_ref::<ArrayBuffer>() {
+                onevent.emit(RoomEvent::Update(Uint8Array::new(buffer).to_vec()));
+            } else if let Some(presence) = data.as_string() {
+                onevent.emit(RoomEvent::Presence(presence));
+            }
+        })
+    };
+    // an error is always followed by `close`
+    let close = EventListener::new(&socket, "close", move |_| onevent.emit(RoomEvent::Closed));
+    Ok(Room {
+        socket,
+        _listeners: [message, close],
+    })
+}
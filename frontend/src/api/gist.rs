@@ -0,0 +1,54 @@
+use anyhow::{anyhow, Result};
+use gloo_net::http::Request;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const GISTS_URL: &str = "https://api.github.com/gists";
+
+#[derive(Debug, Deserialize)]
+struct GistFile {
+    filename: String,
+    content: String,
+    /// Set when the API only returned the start of a large file.
+    #[serde(default)]
+    truncated: bool,
+    raw_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Gist {
+    files: BTreeMap<String, GistFile>,
+}
+
+/// Fetches the Rust code of a GitHub gist. A gist with several Rust files is
+/// expected to name the snippet `main.rs`, otherwise the first one is used.
+pub async fn get(id: &str) -> Result<String> {
+    let resp = Request::get(&format!("{}/{}", GISTS_URL, id))
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !resp.ok() {
+        return Err(anyhow!(
+            "failed to fetch gist {}: {}",
+            id,
+            resp.status_text()
+        ));
+    }
+    let gist = resp.json::<Gist>().await?;
+
+    let mut files = gist
+        .files
+        .into_values()
+        .filter(|it| it.filename.ends_with(".rs"))
+        .collect::<Vec<_>>();
+    if files.is_empty() {
+        return Err(anyhow!("gist {} has no Rust files", id));
+    }
+    let main = files.iter().position(|it| it.filename == "main.rs");
+    let file = files.swap_remove(main.unwrap_or(0));
+
+    if file.truncated {
+        return Ok(Request::get(&file.raw_url).send().await?.text().await?);
+    }
+    Ok(file.content)
+}
@@ -1,5 +1,10 @@
+pub mod collab;
+pub mod gist;
+pub mod progress;
 pub mod share;
 
+use common::client::{GlooTransport, PlaygroundClient};
+
 pub const BACKEND_URL: &str = match option_env!("BACKEND_URL") {
     Some(v) => v,
     None => {
@@ -12,3 +17,8 @@ pub const BACKEND_URL: &str = match option_env!("BACKEND_URL") {
         DEFAULT
     }
 };
+
+/// The backend's API, besides the streams of progress and of collaborative editing.
+pub fn client() -> PlaygroundClient<GlooTransport> {
+    PlaygroundClient::new(BACKEND_URL, GlooTransport::default())
+}
@@ -0,0 +1,69 @@
+use gloo::events::EventListener;
+use serde::Deserialize;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{EventSource, MessageEvent};
+use yew::Callback;
+
+use super::BACKEND_URL;
+
+/// What a build is busy with. Stages only move forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildStage {
+    Queued,
+    CompilingDependencies,
+    CompilingSnippet,
+    Bindgen,
+    Optimizing,
+    Done,
+}
+
+impl BuildStage {
+    /// The stages a build goes through, as shown while it runs, with the message id of
+    /// their label.
+    pub const STEPS: &'static [(Self, &'static str)] = &[
+        (Self::Queued, "stage-queued"),
+        (Self::CompilingDependencies, "stage-compiling-dependencies"),
+        (Self::CompilingSnippet, "stage-compiling-snippet"),
+        (Self::Bindgen, "stage-bindgen"),
+        (Self::Optimizing, "stage-optimizing"),
+    ];
+}
+
+/// A fresh id to follow a run's progress under.
+pub fn new_id() -> String {
+    let random = (js_sys::Math::random() * u32::MAX as f64) as u32;
+    format!("{:x}-{:08x}", js_sys::Date::now() as u64, random)
+}
+
+/// Reports the stages of the build run under an id, until dropped.
+pub struct Subscription {
+    source: EventSource,
+    _listener: EventListener,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.source.close();
+    }
+}
+
+/// Follows the build run under `id`, subscribe before starting the run so no stage
+/// is missed.
+pub fn follow(id: &str, onstage: Callback<BuildStage>) -> Result<Subscription, JsValue> {
+    let source = EventSource::new(&format!("{}/progress/{}", BACKEND_URL, id))?;
+    let listener = EventListener::new(&source, "message", move |e| {
+        let stage = e
+            .unchecked_ref::<MessageEvent>()
+            .data()
+            .as_string()
+            .and_then(|it| serde_json::from_str(&it).ok());
+        if let Some(stage) = stage {
+            onstage.emit(stage);
+        }
+    });
+    Ok(Subscription {
+        source,
+        _listener: listener,
+    })
+}
@@ -1,7 +1,9 @@
+use crate::utils::query::Metadata;
 use anyhow::{anyhow, Context, Result};
+use common::{NewSnippet, SharedSnippet};
 use gloo_net::http::{Request, Response};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 
@@ -39,10 +41,38 @@ pub struct PasteFields {
     content: HashMap<String, String>,
     #[serde(rename = "createdBy")]
     created_by: HashMap<String, Option<String>>,
+    /// Missing from pastes shared without them.
+    #[serde(default)]
+    title: HashMap<String, String>,
+    #[serde(default)]
+    description: HashMap<String, String>,
+    #[serde(default)]
+    tags: ArrayValue,
+    /// ID of the paste this one is a fork of.
+    #[serde(default, rename = "forkedFrom")]
+    forked_from: HashMap<String, String>,
+    /// The style sheet of the preview, missing from pastes without one.
+    #[serde(default)]
+    style: HashMap<String, String>,
+    /// Markup of the head of the preview, missing from pastes without it.
+    #[serde(default)]
+    head: HashMap<String, String>,
+}
+
+/// Firestore's arrays, of strings here. Empty ones come without any values.
+#[derive(Default, Serialize, Deserialize)]
+struct ArrayValue {
+    #[serde(default, rename = "arrayValue")]
+    array_value: Values,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Values {
+    #[serde(default)]
+    values: Vec<HashMap<String, String>>,
 }
 
 const STRING_VALUE: &str = "stringValue";
-const NULL_VALUE: &str = "nullValue";
 const ERROR: &str = "error";
 
 impl PasteFields {
@@ -54,6 +84,31 @@ impl PasteFields {
         self.content.remove(STRING_VALUE).unwrap()
     }
 
+    pub fn metadata(&self) -> Metadata {
+        Metadata {
+            title: self.title.get(STRING_VALUE).cloned(),
+            description: self.description.get(STRING_VALUE).cloned(),
+            tags: self
+                .tags
+                .array_value
+                .values
+                .iter()
+                .filter_map(|it| it.get(STRING_VALUE).cloned())
+                .collect(),
+        }
+    }
+
+    pub fn forked_from(&self) -> Option<&str> {
+        self.forked_from.get(STRING_VALUE).map(String::as_str)
+    }
+
+    pub fn page(&self) -> PageFiles {
+        PageFiles {
+            style: self.style.get(STRING_VALUE).cloned(),
+            head: self.head.get(STRING_VALUE).cloned(),
+        }
+    }
+
     pub fn created_by(&self) -> Option<&str> {
         self.created_by
             .get(STRING_VALUE)
@@ -66,26 +121,43 @@ impl fmt::Debug for PasteFields {
         f.debug_struct("Fields")
             .field("content", &self.content())
             .field("created_by", &format!("{:?}", self.created_by()))
+            .field("metadata", &self.metadata())
+            .field("forked_from", &self.forked_from())
+            .field("page", &self.page())
             .finish()
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PasteDocument {
-    #[serde(skip_serializing)]
-    name: String,
-    pub fields: PasteFields,
+/// The files of the preview's page a paste keeps next to the code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageFiles {
+    pub style: Option<String>,
+    pub head: Option<String>,
 }
 
-impl PasteDocument {
-    pub fn id(&self) -> String {
-        self.name.replace(
-            "projects/pastify-app/databases/(default)/documents/pastes/",
-            "",
-        )
+/// What's known about an opened paste besides its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteDetails {
+    pub metadata: Metadata,
+    pub forked_from: Option<String>,
+    pub page: PageFiles,
+}
+
+impl From<&PasteFields> for PasteDetails {
+    fn from(fields: &PasteFields) -> Self {
+        Self {
+            metadata: fields.metadata(),
+            forked_from: fields.forked_from().map(str::to_string),
+            page: fields.page(),
+        }
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasteDocument {
+    pub fields: PasteFields,
+}
+
 pub async fn get(id: &str) -> Result<PasteDocument> {
     let resp = Request::get(&format!("{}/{}", FIRESTORE_URL, id))
         .send()
@@ -97,24 +169,21 @@ pub async fn get(id: &str) -> Result<PasteDocument> {
     }
 }
 
-pub async fn create(content: &str) -> Result<PasteDocument> {
-    let doc = json!({
-        "fields": {
-            "content": {
-                STRING_VALUE: content
-            },
-            "createdBy": {
-                NULL_VALUE: Option::<String>::None
-            }
-        }
-    });
-
-    let resp = Request::post(FIRESTORE_URL).json(&doc)?.send().await?;
-
-    if (200..300).contains(&resp.status()) {
-        let paste = resp.json::<PasteDocument>().await?;
-        Ok(paste)
-    } else {
-        FirestoreError::anyhow_from_response(&resp).await
-    }
+/// Shares the snippet through the backend, which stores it as a paste.
+pub async fn create(
+    content: &str,
+    page: &PageFiles,
+    metadata: &Metadata,
+    forked_from: Option<&str>,
+) -> Result<SharedSnippet> {
+    let snippet = NewSnippet {
+        content: content.to_string(),
+        title: metadata.title.clone(),
+        description: metadata.description.clone(),
+        tags: metadata.tags.clone(),
+        forked_from: forked_from.map(str::to_string),
+        style: page.style.clone(),
+        head: page.head.clone(),
+    };
+    super::client().share(&snippet).await
 }
@@ -1,107 +1,1739 @@
-use crate::components::editor::Editor;
-use crate::components::output::OutputContainer;
-use crate::utils::query::Query;
-use crate::{icon, ActionButtonState, ActionButtonStateContext};
+use crate::api::collab;
+use crate::api::share::{PageFiles, PasteDetails};
+use crate::collab::{CollabState, CollabStatus};
+use crate::components::badge::BadgeModal;
+use crate::components::benchmark::{Benchmark, BenchmarkBar, DEFAULT_RENDERS};
+use crate::components::build_options::BuildOptionsPanel;
+use crate::components::build_stats::BuildStatsBar;
+use crate::components::clippy::{ClippyPanel, Lints};
+use crate::components::command_palette::CommandPalette;
+use crate::components::compare::{self, ComparePane};
+use crate::components::compile_error::CompileErrorPanel;
+use crate::components::console::{ConsoleAction, ConsoleLog, ConsolePanel};
+use crate::components::dependencies::DependenciesPanel;
+use crate::components::diff::DiffView;
+use crate::components::editor::{Cursor, Editor, Markers, Replacement, Reveal, BASE_CONTENT};
+use crate::components::expansion::{Expansion, ExpansionView};
+use crate::components::glue::GlueView;
+use crate::components::history::HistoryPanel;
+use crate::components::inspector::{ElementTree, InspectorPanel};
+use crate::components::network::{NetworkAction, NetworkLog, NetworkPanel};
+use crate::components::output::{Evaluation, OutputContainer, PreviewMessage};
+use crate::components::page_source::PageSourceView;
+use crate::components::recent::RecentMenu;
+use crate::components::revisions::RevisionsPanel;
+use crate::components::search;
+use crate::components::settings::SettingsPanel;
+use crate::components::share::ShareModal;
+use crate::components::shortcuts::ShortcutsModal;
+use crate::components::status_bar::StatusBar;
+use crate::components::tabs::FileTabs;
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::export;
+use crate::files::{self, FileContents, RunRequest, HEAD, MAIN, STYLE};
+use crate::history;
+use crate::i18n::I18n;
+use crate::keymap::{use_keymap, Action};
+use crate::owned;
+use crate::recent;
+use crate::session;
+use crate::settings::{SettingsAction, SettingsContext, SplitOrientation, ThemePreference};
+use crate::theme::Theme;
+use crate::utils::connection::{use_connection, Connection};
+use crate::utils::fragment::{self, use_fragment};
+use crate::utils::media::use_media_query;
+use crate::utils::query::{use_query, Embed, Metadata, Query};
+use crate::utils::{copy_to_clipboard, download, replace_address};
+use crate::{icon, ActionButtonState, ActionButtonStateContext, Route};
+use common::client::BuildStats;
+use common::{
+    BuildOptions, ExpandResponse, FileDiagnostic, FormatResponse, Level, Span, YewVersion,
+};
+use gloo::dialogs::confirm;
+use gloo::file::futures::read_as_text;
 use gloo::history::{BrowserHistory, History};
+use gloo::timers::callback::Timeout;
+use js_sys::{Array, Function};
 use split_yew::{Direction, Split};
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::HtmlElement;
 use yew::prelude::*;
 use yew::suspense::Suspense;
+use yew_router::prelude::*;
+
+/// The drawers open in the same place, only one is open at a time.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Drawer {
+    Settings,
+    BuildOptions,
+    Dependencies,
+    History,
+    Revisions,
+}
+
+impl Drawer {
+    /// Of the element holding the drawer, which its button controls.
+    fn id(self) -> &'static str {
+        match self {
+            Drawer::Settings => "settings",
+            Drawer::BuildOptions => "build-options",
+            Drawer::Dependencies => "dependencies",
+            Drawer::History => "history",
+            Drawer::Revisions => "revisions",
+        }
+    }
+
+    fn label(self, i18n: I18n) -> String {
+        i18n.t(match self {
+            Drawer::Settings => "settings",
+            Drawer::BuildOptions => "build-options",
+            Drawer::Dependencies => "dependencies",
+            Drawer::History => "history",
+            Drawer::Revisions => "revisions",
+        })
+    }
+}
+
+/// What the output pane shows above the console.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputTab {
+    Preview,
+    /// The JS wasm-bindgen generated for the last run.
+    Glue,
+    /// The HTML document the preview was loaded with.
+    Html,
+    /// The requests the preview made.
+    Network,
+    /// The preview's element tree.
+    Elements,
+}
+
+impl OutputTab {
+    const ALL: [Self; 5] = [
+        Self::Preview,
+        Self::Glue,
+        Self::Html,
+        Self::Network,
+        Self::Elements,
+    ];
+
+    fn label(self, i18n: I18n) -> String {
+        i18n.t(match self {
+            OutputTab::Preview => "output-preview",
+            OutputTab::Glue => "output-glue",
+            OutputTab::Html => "output-html",
+            OutputTab::Network => "output-network",
+            OutputTab::Elements => "output-elements",
+        })
+    }
+}
+
+/// Pane sizes for showing only one of the editor and the preview.
+const CODE_ONLY: [f64; 2] = [100.0, 0.0];
+const PREVIEW_ONLY: [f64; 2] = [0.0, 100.0];
+
+/// The build's diagnostics as markers per file. Files deleted since the run are left
+/// out, the editor would put their markers on `main.rs`.
+fn compile_markers(diagnostics: Vec<FileDiagnostic>, paths: &[String]) -> Vec<Markers> {
+    let mut by_path = BTreeMap::<String, Vec<_>>::new();
+    for FileDiagnostic { path, diagnostic } in diagnostics {
+        if paths.contains(&path) {
+            by_path.entry(path).or_default().push(diagnostic);
+        }
+    }
+    by_path
+        .into_iter()
+        .map(|(path, diagnostics)| Markers {
+            path,
+            source: "rustc",
+            diagnostics: Rc::new(diagnostics),
+        })
+        .collect()
+}
 
 #[function_component]
 pub fn App() -> Html {
-    let editor_contents = use_mut_ref(String::new);
+    let i18n = use_context::<I18n>().unwrap();
+    let contents = (*use_memo(|_| FileContents::default(), ())).clone();
+    let paths = use_state(|| vec![MAIN.to_string()]);
+    let active = use_state(|| MAIN.to_string());
+    // what the preview is running, to tell which files changed since
+    let last_run = use_mut_ref(BTreeMap::new);
+    let dirty = use_state_eq(BTreeSet::new);
     let data = use_state(|| None);
+    let query = use_query();
+    // shared links carry the options they were built with
+    let build_options = use_state(|| {
+        query
+            .as_ref()
+            .map(|it| it.options.clone())
+            .unwrap_or_default()
+    });
+    let embed = query.as_ref().map(|it| it.embed).unwrap_or_default();
+    // ID of the paste the link opened
+    let shared = query.as_ref().and_then(|it| it.shared.clone());
+    // ID of the revision of it the link opened
+    let revision = query.as_ref().and_then(|it| it.revision.clone());
+    // the paste it's a fork of, once the opened one is fetched
+    let forked_from = use_state_eq(|| None::<String>);
+    // from the link, or from the paste it opened once that's fetched
+    let metadata = use_state_eq(|| {
+        query
+            .as_ref()
+            .map(|it| it.metadata.clone())
+            .unwrap_or_default()
+    });
+    use_effect_with_deps(
+        |metadata| {
+            let document = gloo::utils::document();
+            let title = metadata.page_title();
+            document.set_title(&title);
+            // for link previews rendered from the page as it is
+            let description = metadata.page_description();
+            let tags = [
+                ("meta[name=description]", description),
+                ("meta[property='og:title']", &title),
+                ("meta[property='og:description']", description),
+            ];
+            for (selector, content) in tags {
+                if let Ok(Some(tag)) = document.query_selector(selector) {
+                    let _ = tag.set_attribute("content", content);
+                }
+            }
+        },
+        (*metadata).clone(),
+    );
+    let console = use_reducer(ConsoleLog::default);
+    let network = use_reducer(NetworkLog::default);
+    let compile_error = use_state(|| None);
+    // stay until the next run, edits move them along with the code
+    let compile_markers = use_state_eq(Vec::new);
+    // the last run's stats, along with the ones of the successful run before it
+    let build_stats = use_state_eq(|| None::<(BuildStats, Option<BuildStats>)>);
+    let last_stats = use_mut_ref(|| None::<BuildStats>);
+    // re-renders runs benchmark the snippet with, if they do
+    let bench = use_state_eq(|| None::<u32>);
+    let benchmark = use_state(|| None::<(Benchmark, Option<Benchmark>)>);
+    let last_benchmark = use_mut_ref(|| None::<Benchmark>);
+    let build_id = use_state_eq(|| None::<AttrValue>);
+    let output_tab = use_state_eq(|| OutputTab::Preview);
+    let page = use_state_eq(|| None::<AttrValue>);
+    // read out by screen readers, the preview changing isn't
+    let announcement = use_state(AttrValue::default);
 
     let action_button_state = use_context::<ActionButtonStateContext>().unwrap();
+    let connection = use_connection();
+    // rather than letting runs fail with fetch errors
+    let run_disabled = action_button_state.disabled() || connection != Connection::Online;
+    // why, when it's the connection
+    let run_title = connection.problem().map(|it| i18n.t(it));
 
+    let settings = use_context::<SettingsContext>().unwrap();
     let split_sizes = use_state(|| vec![100.0, 0.0]);
     let output_collapsed = use_state(|| true);
+    // side by side when there's room for it and the user didn't pick stacked, stacked
+    // otherwise
+    let wide = use_media_query("(min-width: 768px)", true);
+    let side_by_side = wide && settings.split == SplitOrientation::SideBySide;
+    // the split's sizes are in the panes' order on the page, `split_sizes` has the
+    // editor's first either way
+    let swapped = use_mut_ref(|| false);
+    *swapped.borrow_mut() = settings.swap_panes;
+    let on_drag_end = {
+        let settings = settings.clone();
+        let split_sizes = split_sizes.clone();
+        let swapped = swapped.clone();
+        use_memo(
+            move |_| {
+                Closure::<dyn Fn(Array)>::new(move |sizes: Array| {
+                    let mut sizes = sizes
+                        .iter()
+                        .filter_map(|it| it.as_f64())
+                        .collect::<Vec<_>>();
+                    if *swapped.borrow() {
+                        sizes.reverse();
+                    }
+                    if let Some(editor_size) = sizes.first() {
+                        settings.dispatch(SettingsAction::EditorSize(*editor_size));
+                    }
+                    split_sizes.set(sizes);
+                })
+                .into_js_value()
+                .unchecked_into::<Function>()
+            },
+            (),
+        )
+    };
 
-    let on_run_click = {
+    let run = {
         let action_button_state = action_button_state.clone();
-        let editor_contents = editor_contents.clone();
+        let contents = contents.clone();
+        let last_run = last_run.clone();
+        let dirty = dirty.clone();
+        let build_options = build_options.clone();
         let split_sizes = split_sizes.clone();
         let output_collapsed = output_collapsed.clone();
+        let editor_size = settings.editor_size;
         let data = data.clone();
-        move |_| {
-            data.set(Some(Rc::from(editor_contents.as_ref().borrow().as_str())));
-            if *output_collapsed {
+        let (console, network) = (console.clone(), network.clone());
+        let compile_error = compile_error.clone();
+        let compile_markers = compile_markers.clone();
+        let build_stats = build_stats.clone();
+        let (bench, benchmark) = (bench.clone(), benchmark.clone());
+        let announcement = announcement.clone();
+        Callback::from(move |_| {
+            announcement.set(AttrValue::from(i18n.t("announce-building")));
+            console.dispatch(ConsoleAction::Clear);
+            network.dispatch(NetworkAction::Clear);
+            compile_error.set(None);
+            compile_markers.set(Vec::new());
+            build_stats.set(None);
+            benchmark.set(None);
+            let files = contents.snapshot();
+            *last_run.borrow_mut() = files.clone();
+            dirty.set(BTreeSet::new());
+            data.set(Some(RunRequest {
+                files: Rc::new(files),
+                options: (*build_options).clone(),
+                bench: *bench,
+            }));
+            if !wide {
+                // stacked panes are too short to share the screen, show the preview and let
+                // the pane switcher bring the code back
+                output_collapsed.set(false);
+                split_sizes.set(PREVIEW_ONLY.to_vec());
+            } else if *output_collapsed {
                 output_collapsed.set(false);
-                split_sizes.set(vec![50.0, 50.0]);
+                split_sizes.set(vec![editor_size, 100.0 - editor_size]);
             }
             action_button_state.dispatch(ActionButtonState::Disabled);
-        }
+        })
     };
 
-    let on_share_click = {
-        let editor_contents = editor_contents.clone();
+    let toasts = use_context::<ToastContext>().unwrap();
+    let share = {
+        let contents = contents.clone();
+        let build_options = build_options.clone();
         let action_button_state = action_button_state.clone();
-        move |_| {
+        let toasts = toasts.clone();
+        let shown_metadata = metadata.clone();
+        let forked_from = forked_from.clone();
+        Callback::from(move |metadata: Metadata| {
+            shown_metadata.set(metadata.clone());
+            // a new snippet, even if it started out as a fork
+            forked_from.set(None);
             let action_button_state = action_button_state.clone();
-            let editor_contents = editor_contents.clone();
+            // shared links hold a single file, besides the files of the preview's page
+            let content = contents.get(MAIN).unwrap_or_default();
+            let page = PageFiles {
+                style: contents.get(STYLE),
+                head: contents.get(HEAD),
+            };
+            let options = (*build_options).clone();
+            let toasts = toasts.clone();
 
-            // https://tenor.com/view/this-is-fine-fire-house-burning-okay-gif-5263684
-            // this is fine because no one can do anuthing with the refcell while we holding it
-            // alternate is clone, which is expensive
-            #[allow(clippy::await_holding_refcell_ref)]
             wasm_bindgen_futures::spawn_local(async move {
-                action_button_state.dispatch(ActionButtonState::Disabled);
                 let history = BrowserHistory::new();
 
-                let content = editor_contents.as_ref().borrow();
-                let content = content.as_str();
-                let paste = crate::api::share::create(content).await.expect("fucked up");
-                let id = paste.id();
+                // small snippets fit into the link itself and aren't stored anywhere,
+                // such links hold only the code, and can't be searched for by tag
+                let encoded = fragment::encode(&content);
+                let inline = page == PageFiles::default() && metadata.tags.is_empty();
+                // links to stored pastes go through the backend's page for them, which
+                // chat apps and forums unfurl
+                let mut unfurled = None;
+                if inline && encoded.len() <= fragment::MAX_SHARED_LEN {
+                    let query = Query {
+                        shared: None,
+                        revision: None,
+                        code: None,
+                        gist: None,
+                        example: None,
+                        room: None,
+                        options,
+                        embed: Embed::default(),
+                        metadata,
+                    };
+                    let query = serde_urlencoded::to_string(query).expect("query serializes");
+                    let separator = if query.is_empty() { "" } else { "?" };
+                    history.push(format!("/{}{}{}", separator, query, encoded));
+                } else {
+                    action_button_state.dispatch(ActionButtonState::Disabled);
+                    let paste = crate::api::share::create(&content, &page, &metadata, None).await;
+                    action_button_state.dispatch(ActionButtonState::Enabled);
+                    let paste = match paste {
+                        Ok(paste) => paste,
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to share code");
+                            toast::show(&toasts, ToastKind::Error, i18n.t("share-failed"));
+                            return;
+                        }
+                    };
+                    owned::add(&paste.id, &paste.owner_token);
+                    let id = paste.id;
+                    let options_query =
+                        serde_urlencoded::to_string(&options).expect("options serialize");
+                    let separator = if options_query.is_empty() { "" } else { "?" };
+                    unfurled = Some(format!(
+                        "{}/s/{}{}{}",
+                        gloo::utils::window()
+                            .location()
+                            .origin()
+                            .expect("failed to get origin"),
+                        id,
+                        separator,
+                        options_query
+                    ));
+                    let query = Query {
+                        shared: Some(id),
+                        revision: None,
+                        code: None,
+                        gist: None,
+                        example: None,
+                        room: None,
+                        options,
+                        embed: Embed::default(),
+                        // the paste holds it
+                        metadata: Metadata::default(),
+                    };
+                    history
+                        .push_with_query("/", query)
+                        .expect("failed to navigate");
+                }
+
+                let url = unfurled.unwrap_or_else(|| {
+                    gloo::utils::window()
+                        .location()
+                        .href()
+                        .expect("failed to get url")
+                });
+                match copy_to_clipboard(&url).await {
+                    Ok(()) => toast::show(&toasts, ToastKind::Success, i18n.t("share-copied")),
+                    Err(e) => {
+                        // e.g. when the page lost focus while sharing
+                        tracing::warn!(?e, "failed to copy link");
+                        toast::show(&toasts, ToastKind::Info, i18n.t("share-copy-failed"));
+                    }
+                }
+            })
+        })
+    };
+
+    // copies of shared pastes are pastes of their own, pointing back at the original
+    let fork = {
+        let contents = contents.clone();
+        let build_options = build_options.clone();
+        let metadata = metadata.clone();
+        let action_button_state = action_button_state.clone();
+        let toasts = toasts.clone();
+        let shared = shared.clone();
+        Callback::from(move |_| {
+            let Some(original) = shared.clone() else {
+                return;
+            };
+            let content = contents.get(MAIN).unwrap_or_default();
+            let page = PageFiles {
+                style: contents.get(STYLE),
+                head: contents.get(HEAD),
+            };
+            let options = (*build_options).clone();
+            let metadata = (*metadata).clone();
+            let action_button_state = action_button_state.clone();
+            let toasts = toasts.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                action_button_state.dispatch(ActionButtonState::Disabled);
+                let paste =
+                    crate::api::share::create(&content, &page, &metadata, Some(&original)).await;
+                action_button_state.dispatch(ActionButtonState::Enabled);
+                let paste = match paste {
+                    Ok(paste) => paste,
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to fork snippet");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("fork-failed"));
+                        return;
+                    }
+                };
+                owned::add(&paste.id, &paste.owner_token);
                 let query = Query {
-                    shared: Some(id),
+                    shared: Some(paste.id),
+                    revision: None,
                     code: None,
+                    gist: None,
+                    example: None,
+                    room: None,
+                    options,
+                    embed: Embed::default(),
+                    metadata: Metadata::default(),
                 };
-                history
+                BrowserHistory::new()
                     .push_with_query("/", query)
                     .expect("failed to navigate");
-                action_button_state.dispatch(ActionButtonState::Enabled);
+                toast::show(&toasts, ToastKind::Success, i18n.t("forked"));
+            });
+        })
+    };
+
+    let replacements = use_state(Vec::new);
+    // rustfmt's parse errors, until the code is edited
+    let format_markers = use_state_eq(|| None);
+    let format = {
+        let contents = contents.clone();
+        let active = active.clone();
+        let replacements = replacements.clone();
+        let format_markers = format_markers.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            let path = (*active).clone();
+            if files::is_page_file(&path) {
+                toast::show(&toasts, ToastKind::Info, i18n.t("format-not-rust"));
+                return;
+            }
+            let code = contents.get(&path).unwrap_or_default();
+            let replacements = replacements.clone();
+            let format_markers = format_markers.clone();
+            let toasts = toasts.clone();
+            format_markers.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match crate::api::client().format(&code).await {
+                    Ok(FormatResponse::Formatted(formatted)) if formatted == code => {
+                        toast::show(&toasts, ToastKind::Info, i18n.t("format-unchanged"))
+                    }
+                    Ok(FormatResponse::Formatted(formatted)) => {
+                        replacements.set(vec![Replacement {
+                            path,
+                            code: Rc::from(formatted),
+                        }])
+                    }
+                    Ok(FormatResponse::ParseError {
+                        rendered,
+                        diagnostics,
+                    }) => {
+                        tracing::warn!(rendered, "failed to format code");
+                        format_markers.set(Some(Markers {
+                            path,
+                            source: "rustfmt",
+                            diagnostics: Rc::new(diagnostics),
+                        }));
+                        toast::show(&toasts, ToastKind::Error, i18n.t("format-parse-error"));
+                    }
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to format code");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("format-failed"));
+                    }
+                }
             })
+        })
+    };
+
+    // clippy lints the main file only, like the format and share actions
+    let lints = use_state(|| None);
+    let clippy = {
+        let contents = contents.clone();
+        let build_options = build_options.clone();
+        let lints = lints.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            let code = contents.get(MAIN).unwrap_or_default();
+            let options = (*build_options).clone();
+            let lints = lints.clone();
+            let toasts = toasts.clone();
+            lints.set(Some(Lints::Running));
+            wasm_bindgen_futures::spawn_local(async move {
+                match crate::api::client().clippy(&code, &options).await {
+                    Ok(response) => lints.set(Some(Lints::Done(Rc::new(response.diagnostics)))),
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to run clippy");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("clippy-failed"));
+                        lints.set(None);
+                    }
+                }
+            })
+        })
+    };
+    // the main file with its macros expanded, shown in a read-only tab while open
+    let expansion = use_state(|| None);
+    let expand = {
+        let contents = contents.clone();
+        let build_options = build_options.clone();
+        let expansion = expansion.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            let code = contents.get(MAIN).unwrap_or_default();
+            let options = (*build_options).clone();
+            let expansion = expansion.clone();
+            let toasts = toasts.clone();
+            expansion.set(Some(Expansion::Running));
+            wasm_bindgen_futures::spawn_local(async move {
+                match crate::api::client().expand(&code, &options).await {
+                    Ok(ExpandResponse::Expanded(code)) => {
+                        expansion.set(Some(Expansion::Expanded(AttrValue::from(code))))
+                    }
+                    Ok(ExpandResponse::CompileError(message)) => {
+                        expansion.set(Some(Expansion::CompileError(AttrValue::from(message))))
+                    }
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to expand macros");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("expansion-failed"));
+                        expansion.set(None);
+                    }
+                }
+            })
+        })
+    };
+    let on_expansion_close = {
+        let expansion = expansion.clone();
+        Callback::from(move |_| expansion.set(None))
+    };
+    let reveal = use_state(|| None);
+    let on_lint_select = {
+        let active = active.clone();
+        let reveal = reveal.clone();
+        Callback::from(move |span| {
+            active.set(MAIN.to_string());
+            reveal.set(Some(Reveal {
+                path: MAIN.to_string(),
+                span: Rc::new(span),
+            }));
+        })
+    };
+    let on_error_select = {
+        let active = active.clone();
+        let reveal = reveal.clone();
+        Callback::from(move |(path, span): (String, Span)| {
+            active.set(path.clone());
+            reveal.set(Some(Reveal {
+                path,
+                span: Rc::new(span),
+            }));
+        })
+    };
+    let on_lints_close = {
+        let lints = lints.clone();
+        Callback::from(move |_| lints.set(None))
+    };
+
+    let shortcuts_open = use_state(|| false);
+    let share_open = use_state(|| false);
+    let on_run_click = run.reform(|_: MouseEvent| ());
+    let on_format_click = format.reform(|_: MouseEvent| ());
+    let on_clippy_click = clippy.reform(|_: MouseEvent| ());
+    let on_expand_click = expand.reform(|_: MouseEvent| ());
+    let on_fork_click = fork.reform(|_: MouseEvent| ());
+    let on_share_click = {
+        let share_open = share_open.clone();
+        move |_| share_open.set(true)
+    };
+    let on_share = {
+        let share_open = share_open.clone();
+        Callback::from(move |metadata| {
+            share_open.set(false);
+            share.emit(metadata);
+        })
+    };
+    let on_share_close = {
+        let share_open = share_open.clone();
+        Callback::from(move |_| share_open.set(false))
+    };
+    let markdown_open = use_state(|| false);
+    let on_markdown_click = {
+        let markdown_open = markdown_open.clone();
+        move |_| markdown_open.set(true)
+    };
+    let on_markdown_close = {
+        let markdown_open = markdown_open.clone();
+        Callback::from(move |_| markdown_open.set(false))
+    };
+    // the shared snippet as it's built now, for READMEs and forum posts to link to
+    let markdown_query = Query {
+        shared: shared.clone(),
+        revision: revision.clone(),
+        code: None,
+        gist: None,
+        example: None,
+        room: None,
+        options: (*build_options).clone(),
+        embed: Embed::default(),
+        metadata: Metadata::default(),
+    };
+    // the address holds the room while in one, to be passed on, without the page
+    // following the address and reloading the code
+    let room = use_state(|| {
+        query
+            .as_ref()
+            .and_then(|it| it.room.clone())
+            .filter(|_| !embed.chromeless())
+    });
+    let collab_state = use_state_eq(|| None::<CollabState>);
+    let collaborate = {
+        let room = room.clone();
+        let build_options = build_options.clone();
+        let metadata = metadata.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            if room.is_none() {
+                let id = collab::new_room_id();
+                let query = Query {
+                    shared: None,
+                    revision: None,
+                    code: None,
+                    gist: None,
+                    example: None,
+                    room: Some(id.clone()),
+                    options: (*build_options).clone(),
+                    embed: Embed::default(),
+                    metadata: (*metadata).clone(),
+                };
+                let query = serde_urlencoded::to_string(query).expect("query serializes");
+                replace_address(&format!("/?{}", query));
+                room.set(Some(id));
+            }
+            let toasts = toasts.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let url = gloo::utils::window()
+                    .location()
+                    .href()
+                    .expect("failed to get url");
+                match copy_to_clipboard(&url).await {
+                    Ok(()) => toast::show(&toasts, ToastKind::Success, i18n.t("collab-copied")),
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to copy link");
+                        toast::show(&toasts, ToastKind::Info, i18n.t("collab-copy-failed"));
+                    }
+                }
+            });
+        })
+    };
+    let on_collab_click = collaborate.reform(|_: MouseEvent| ());
+    let on_leave_click = {
+        let (room, collab_state) = (room.clone(), collab_state.clone());
+        let query = query.clone();
+        move |_| {
+            let query = query.clone().map(|it| Query { room: None, ..it });
+            let query = query
+                .map(|it| serde_urlencoded::to_string(it).expect("query serializes"))
+                .unwrap_or_default();
+            let separator = if query.is_empty() { "" } else { "?" };
+            replace_address(&format!("/{}{}", separator, query));
+            room.set(None);
+            collab_state.set(None);
         }
     };
+    let on_collab_change = {
+        let collab_state = collab_state.clone();
+        Callback::from(move |state| collab_state.set(Some(state)))
+    };
+    let download_project = {
+        let contents = contents.clone();
+        let build_options = build_options.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            let zip = export::project_zip(&contents.snapshot(), &build_options);
+            let downloaded = zip.and_then(|zip| {
+                download(&zip, "yew-app.zip", "application/zip")
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))
+            });
+            if let Err(e) = downloaded {
+                tracing::warn!(?e, "failed to download project");
+                toast::show(&toasts, ToastKind::Error, i18n.t("download-failed"));
+            }
+        })
+    };
+    let on_download_click = download_project.reform(|_: MouseEvent| ());
+    let on_shortcuts_click = {
+        let shortcuts_open = shortcuts_open.clone();
+        move |_| shortcuts_open.set(true)
+    };
+    let on_shortcuts_close = {
+        let shortcuts_open = shortcuts_open.clone();
+        Callback::from(move |_| shortcuts_open.set(false))
+    };
+
+    let onmessage = {
+        let console = console.clone();
+        let compile_error = compile_error.clone();
+        let compile_markers = compile_markers.clone();
+        let announcement = announcement.clone();
+        let paths = paths.clone();
+        Callback::from(move |message| {
+            if let PreviewMessage::CompileError {
+                message,
+                diagnostics,
+            } = &message
+            {
+                let errors = diagnostics
+                    .iter()
+                    .filter(|it| it.diagnostic.level == Level::Error)
+                    .count();
+                announcement.set(AttrValue::from(match errors {
+                    1 => i18n.t("announce-build-failed-one"),
+                    errors => i18n.t_args("announce-build-failed-other", &[("count", &errors)]),
+                }));
+                compile_error.set(Some(AttrValue::from(message.clone())));
+                compile_markers.set(self::compile_markers(diagnostics.clone(), &paths));
+            }
+            console.dispatch(ConsoleAction::Push(message))
+        })
+    };
+
+    // asks the preview for its element tree each time it changes
+    let inspection = use_state_eq(|| 0);
+    let tree = use_state_eq(|| None::<Rc<ElementTree>>);
+    let on_inspect = {
+        let inspection = inspection.clone();
+        Callback::from(move |_| inspection.set(*inspection + 1))
+    };
+    let on_tree = {
+        let tree = tree.clone();
+        Callback::from(move |it| tree.set(Some(Rc::new(it))))
+    };
+    let on_network = {
+        let network = network.clone();
+        Callback::from(move |action| network.dispatch(action))
+    };
+    // bumped to export the preview as an image
+    let capture = use_state_eq(|| 0);
+    // code entered into the console, for the preview to evaluate
+    let evaluation = use_state_eq(|| None::<Evaluation>);
+    let on_evaluate = {
+        let evaluation = evaluation.clone();
+        Callback::from(move |it| evaluation.set(Some(it)))
+    };
+
+    let on_stats = {
+        let build_stats = build_stats.clone();
+        let announcement = announcement.clone();
+        Callback::from(move |stats: Option<BuildStats>| {
+            if let Some(stats) = stats {
+                let seconds = format!("{:.1}", stats.build_ms as f64 / 1000.0);
+                announcement.set(AttrValue::from(
+                    i18n.t_args("announce-build-finished", &[("seconds", &seconds)]),
+                ));
+            }
+            let previous = stats.and_then(|it| last_stats.borrow_mut().replace(it));
+            build_stats.set(stats.map(|it| (it, previous)));
+        })
+    };
+
+    let on_benchmark = {
+        let benchmark = benchmark.clone();
+        Callback::from(move |it: Benchmark| {
+            let previous = last_benchmark.borrow_mut().replace(it.clone());
+            benchmark.set(Some((it, previous)));
+        })
+    };
+
+    let on_build = {
+        let build_id = build_id.clone();
+        Callback::from(move |id| build_id.set(id))
+    };
+    let on_page = {
+        let page = page.clone();
+        Callback::from(move |it| page.set(Some(it)))
+    };
+    let output_tabs = html! {
+        <div role="tablist" aria-label={i18n.t("output")} class="flex text-sm bg-gray-100 dark:bg-gray-800">
+            { for OutputTab::ALL.into_iter().map(|tab| {
+                let selected = *output_tab == tab;
+                let onclick = {
+                    let output_tab = output_tab.clone();
+                    let on_inspect = on_inspect.clone();
+                    move |_| {
+                        output_tab.set(tab);
+                        if tab == OutputTab::Elements {
+                            on_inspect.emit(());
+                        }
+                    }
+                };
+                let classes = classes!(
+                    "px-3",
+                    "py-1",
+                    selected.then_some("bg-white dark:bg-gray-900")
+                );
+                html! {
+                    <button role="tab" aria-selected={selected.to_string()} {onclick} class={classes}>{tab.label(i18n)}</button>
+                }
+            }) }
+        </div>
+    };
 
+    let fragment = use_fragment();
+    // embeds and presentations link to the full playground, with the same code and options
+    let playground_href = embed.chromeless().then(|| {
+        let query = query.clone().map(|it| Query {
+            embed: Embed::default(),
+            ..it
+        });
+        let query = query
+            .map(|it| serde_urlencoded::to_string(it).expect("query serializes"))
+            .unwrap_or_default();
+        let separator = if query.is_empty() { "" } else { "?" };
+        format!("/{}{}{}", separator, query, fragment)
+    });
+    // autosaves also go into the snippet's history, read through a ref like `restore_pending`
+    let history_key = use_mut_ref(String::new);
+    *history_key.borrow_mut() = history::snippet_key(query.as_ref(), &fragment);
+    // the opened snippet is the most recent one, touched again once its title is known
+    {
+        let chromeless = embed.chromeless();
+        use_effect_with_deps(
+            move |(snippet, title)| {
+                if !chromeless {
+                    recent::remember(snippet.clone(), title.clone());
+                }
+            },
+            (history_key.borrow().clone(), metadata.title.clone()),
+        );
+    }
+    // offer the code of the last visit back, unless the link opened something else
+    let opens_code = fragment::holds_code(&fragment)
+        || query.is_some_and(|it| {
+            it.shared.is_some()
+                || it.code.is_some()
+                || it.gist.is_some()
+                || it.example.is_some()
+                || it.room.is_some()
+        });
+    // embeds neither offer the last session nor replace it
+    let previous_session =
+        use_state(|| session::load().filter(|_| !opens_code && !embed.chromeless()));
+    // the link asks for it, or the user does for all links opening code
+    let autorun = embed.autorun || (opens_code && settings.autorun_shared);
+    let autosave = use_mut_ref(|| None::<Timeout>);
+    // the code the link opened, to show what the user changed about it
+    let opened = use_mut_ref(|| None::<Rc<str>>);
+    let edited = use_state_eq(|| false);
+    // whether the editor reported the code it opened with, for `autorun`
+    let loaded = use_state_eq(|| false);
+    let diff_open = use_state(|| false);
+    // Yew version runs are also built with, side by side with the build options' one
+    let compare = use_state_eq(|| None::<YewVersion>);
+    let on_opened = {
+        let opened = opened.clone();
+        Callback::from(move |code| *opened.borrow_mut() = Some(code))
+    };
+    let on_paste = {
+        let (metadata, forked_from) = (metadata.clone(), forked_from.clone());
+        let (contents, paths, replacements) =
+            (contents.clone(), paths.clone(), replacements.clone());
+        Callback::from(move |paste: PasteDetails| {
+            metadata.set(paste.metadata);
+            forked_from.set(paste.forked_from);
+            let page = [(STYLE, paste.page.style), (HEAD, paste.page.head)];
+            let mut new_paths = (*paths).clone();
+            let mut replaced = Vec::new();
+            for (path, code) in page {
+                let Some(code) = code else {
+                    continue;
+                };
+                if new_paths.iter().any(|it| it == path) {
+                    replaced.push(Replacement {
+                        path: path.to_string(),
+                        code: Rc::from(code),
+                    });
+                } else {
+                    // new files are created with what's in `contents`
+                    contents.set(path, code);
+                    new_paths.push(path.to_string());
+                }
+            }
+            paths.set(new_paths);
+            replacements.set(replaced);
+        })
+    };
+    let on_diff_click = {
+        let diff_open = diff_open.clone();
+        move |_| diff_open.set(!*diff_open)
+    };
+    let on_diff_close = {
+        let diff_open = diff_open.clone();
+        Callback::from(move |_| diff_open.set(false))
+    };
+    // the editor holds on to the first `oninput`, so it reads these through refs
+    let restore_pending = use_mut_ref(|| false);
+    *restore_pending.borrow_mut() = previous_session.is_some();
+    // live mode reruns the code once the typing stops, after the user ran it themselves
+    let live_delay = use_mut_ref(|| None::<u32>);
+    *live_delay.borrow_mut() = settings.live_delay_ms.filter(|_| !embed.chromeless());
+    let live_run = use_mut_ref(|| None::<Callback<()>>);
+    // stacked panes would switch from the code to the preview while typing
+    let live = wide && connection == Connection::Online;
+    *live_run.borrow_mut() = live.then(|| run.clone());
+    let live_timeout = use_mut_ref(|| None::<Timeout>);
     let oninput = {
-        move |v| {
-            *editor_contents.as_ref().borrow_mut() = v;
+        let contents = contents.clone();
+        let last_run = last_run.clone();
+        let dirty = dirty.clone();
+        let format_markers = format_markers.clone();
+        let (opened, edited) = (opened.clone(), edited.clone());
+        let loaded = loaded.clone();
+        let history_key = history_key.clone();
+        move |(path, code): (String, String)| {
+            format_markers.set(None);
+            if path == MAIN {
+                edited.set(opened.borrow().as_deref().is_some_and(|it| *it != code));
+                loaded.set(true);
+            }
+            // keep the previous session around until the user decided what to do with it
+            if path == MAIN && !*restore_pending.borrow() && !embed.chromeless() {
+                let code = code.clone();
+                let snippet = history_key.borrow().clone();
+                let timeout = Timeout::new(session::AUTOSAVE_DELAY_MS, move || {
+                    session::save(&code);
+                    recent::remember(snippet.clone(), None);
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Err(e) = history::save(&snippet, &code).await {
+                            tracing::warn!(?e, "failed to save revision");
+                        }
+                    });
+                });
+                *autosave.borrow_mut() = Some(timeout);
+            }
+            contents.set(&path, code);
+            dirty.set(files::changed_since(&last_run.borrow(), &contents));
+            if let Some(delay) = *live_delay.borrow() {
+                let (contents, last_run) = (contents.clone(), last_run.clone());
+                let live_run = live_run.clone();
+                // replacing the timeout cancels the one of the previous edit
+                let timeout = Timeout::new(delay, move || {
+                    // nothing counts as changed before the first run
+                    let changed = !files::changed_since(&last_run.borrow(), &contents).is_empty();
+                    // a newer run cancels the one in progress, the compiler queues the rest
+                    if let (true, Some(run)) = (changed, &*live_run.borrow()) {
+                        run.emit(());
+                    }
+                });
+                *live_timeout.borrow_mut() = Some(timeout);
+            }
+        }
+    };
+    {
+        let run = run.clone();
+        use_effect_with_deps(
+            move |loaded| {
+                if *loaded && autorun {
+                    run.emit(());
+                }
+            },
+            *loaded,
+        );
+    }
+    let on_restore_click = {
+        let previous_session = previous_session.clone();
+        let replacements = replacements.clone();
+        move |_| {
+            if let Some(code) = &*previous_session {
+                replacements.set(vec![Replacement {
+                    path: MAIN.to_string(),
+                    code: Rc::from(code.as_str()),
+                }]);
+            }
+            previous_session.set(None);
+        }
+    };
+    let on_discard_click = {
+        let previous_session = previous_session.clone();
+        move |_| {
+            session::clear();
+            previous_session.set(None);
         }
     };
 
+    let on_select = {
+        let active = active.clone();
+        let expansion = expansion.clone();
+        Callback::from(move |path| {
+            expansion.set(None);
+            active.set(path);
+        })
+    };
+    let on_create = {
+        let (contents, paths, active) = (contents.clone(), paths.clone(), active.clone());
+        let (last_run, dirty) = (last_run.clone(), dirty.clone());
+        Callback::from(move |path: String| {
+            contents.set(&path, String::new());
+            let mut new_paths = (*paths).clone();
+            new_paths.push(path.clone());
+            paths.set(new_paths);
+            active.set(path);
+            dirty.set(files::changed_since(&last_run.borrow(), &contents));
+        })
+    };
+    let on_rename = {
+        let (contents, paths, active) = (contents.clone(), paths.clone(), active.clone());
+        let (last_run, dirty) = (last_run.clone(), dirty.clone());
+        Callback::from(move |(old, new): (String, String)| {
+            let code = contents.remove(&old).unwrap_or_default();
+            contents.set(&new, code);
+            let new_paths = paths
+                .iter()
+                .map(|it| if *it == old { new.clone() } else { it.clone() })
+                .collect();
+            paths.set(new_paths);
+            if *active == old {
+                active.set(new);
+            }
+            dirty.set(files::changed_since(&last_run.borrow(), &contents));
+        })
+    };
+    let on_delete = {
+        let (contents, paths, active) = (contents.clone(), paths.clone(), active.clone());
+        let (last_run, dirty) = (last_run.clone(), dirty.clone());
+        Callback::from(move |path: String| {
+            contents.remove(&path);
+            paths.set(paths.iter().filter(|it| **it != path).cloned().collect());
+            if *active == path {
+                active.set(MAIN.to_string());
+            }
+            dirty.set(files::changed_since(&last_run.borrow(), &contents));
+        })
+    };
+    // `.rs` files dragged onto the editor. A single one is loaded into the open file,
+    // several are loaded by their names, `main.rs` into the crate root and the rest
+    // into module files.
+    let droppable = !embed.chromeless() && !embed.read_only();
+    let dragging = use_state_eq(|| false);
+    let on_drag_enter = {
+        let dragging = dragging.clone();
+        move |e: DragEvent| {
+            // text dragged around within the editor carries no files
+            let files = e
+                .data_transfer()
+                .is_some_and(|it| it.types().includes(&JsValue::from_str("Files"), 0));
+            if droppable && files {
+                dragging.set(true);
+            }
+        }
+    };
+    let on_drag_over = |e: DragEvent| e.prevent_default();
+    let on_drag_leave = {
+        let dragging = dragging.clone();
+        move |_: DragEvent| dragging.set(false)
+    };
+    let on_load_files = {
+        let (contents, paths, active) = (contents.clone(), paths.clone(), active.clone());
+        let (last_run, dirty) = (last_run.clone(), dirty.clone());
+        let replacements = replacements.clone();
+        let expansion = expansion.clone();
+        let opened = opened.clone();
+        Callback::from(move |loaded: Vec<(String, String)>| {
+            // the code the page opened with is still around, it's not worth a question
+            let pristine = |path: &str, code: &str| {
+                path == MAIN && opened.borrow().as_deref().unwrap_or(BASE_CONTENT) == code
+            };
+            let overwritten = loaded
+                .iter()
+                .filter(|(path, code)| {
+                    contents.get(path).is_some_and(|old| {
+                        !old.trim().is_empty() && old != *code && !pristine(path, &old)
+                    })
+                })
+                .map(|(path, _)| files::file_name(path))
+                .collect::<Vec<_>>();
+            if !overwritten.is_empty() {
+                let names = overwritten.join(", ");
+                if !confirm(&i18n.t_args("drop-overwrite-confirm", &[("names", &names)])) {
+                    return;
+                }
+            }
+
+            let Some(first) = loaded.first().map(|(path, _)| path.clone()) else {
+                return;
+            };
+            let mut new_paths = (*paths).clone();
+            let mut replaced = Vec::new();
+            for (path, code) in loaded {
+                if new_paths.contains(&path) {
+                    replaced.push(Replacement {
+                        path,
+                        code: Rc::from(code),
+                    });
+                } else {
+                    // new files are created with what's in `contents`
+                    contents.set(&path, code);
+                    new_paths.push(path);
+                }
+            }
+            paths.set(new_paths);
+            replacements.set(replaced);
+            expansion.set(None);
+            active.set(first);
+            dirty.set(files::changed_since(&last_run.borrow(), &contents));
+        })
+    };
+    let on_drop = {
+        let dragging = dragging.clone();
+        let active = active.clone();
+        let toasts = toasts.clone();
+        move |e: DragEvent| {
+            // otherwise the browser navigates to the file
+            e.prevent_default();
+            dragging.set(false);
+            let Some(list) = e.data_transfer().and_then(|it| it.files()) else {
+                return;
+            };
+            let dropped = (0..list.length())
+                .filter_map(|i| list.get(i))
+                .filter(|it| it.name().ends_with(".rs"))
+                .collect::<Vec<_>>();
+            if dropped.is_empty() {
+                toast::show(&toasts, ToastKind::Info, i18n.t("drop-not-rust"));
+                return;
+            }
+
+            let single = dropped.len() == 1;
+            let active = (*active).clone();
+            let on_load_files = on_load_files.clone();
+            let toasts = toasts.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut loaded = BTreeMap::new();
+                for file in dropped {
+                    let name = file.name();
+                    let path = match name.as_str() {
+                        "main.rs" => Ok(MAIN.to_string()),
+                        _ if single => Ok(active.clone()),
+                        _ => files::module_path(&name),
+                    };
+                    let path = match path {
+                        Ok(path) => path,
+                        Err(reason) => {
+                            let reason = i18n.t(reason);
+                            let message = i18n
+                                .t_args("drop-skipped", &[("name", &name), ("reason", &reason)]);
+                            toast::show(&toasts, ToastKind::Error, message);
+                            continue;
+                        }
+                    };
+                    match read_as_text(&gloo::file::File::from(file)).await {
+                        Ok(code) => {
+                            loaded.insert(path, code);
+                        }
+                        Err(e) => {
+                            tracing::warn!(?e, name, "failed to read a dropped file");
+                            let message = i18n.t_args("drop-read-failed", &[("name", &name)]);
+                            toast::show(&toasts, ToastKind::Error, message);
+                        }
+                    }
+                }
+                on_load_files.emit(loaded.into_iter().collect());
+            });
+        }
+    };
+
+    // at least 44px square so they're easy to hit on touch screens
     let mut classes = Classes::from(
-        "p-3 text-center shadow-lg bg-gray-800 rounded-md flex gap-2 \
-    transition duration-200 ease-in-out disabled:cursor-not-allowed disabled:bg-gray-700",
+        "p-3 min-h-[44px] min-w-[44px] items-center justify-center touch-manipulation \
+    text-center shadow-lg bg-gray-300 dark:bg-gray-800 rounded-md flex gap-2 \
+    transition duration-200 ease-in-out disabled:cursor-not-allowed disabled:bg-gray-200 \
+    dark:disabled:bg-gray-700",
     );
     if !action_button_state.disabled() {
-        classes.push("hover:bg-gray-900")
+        classes.push("hover:bg-gray-400 dark:hover:bg-gray-900")
+    }
+
+    let drawer = use_state(|| None);
+    let toggle_drawer = |which: Drawer| {
+        let drawer = drawer.clone();
+        move |_| drawer.set((*drawer != Some(which)).then_some(which))
+    };
+    let drawer_ref = use_node_ref();
+    // opened drawers take the focus, they come after the rest of the header
+    {
+        let drawer_ref = drawer_ref.clone();
+        use_effect_with_deps(
+            move |drawer| {
+                if drawer.is_some() {
+                    if let Some(element) = drawer_ref.cast::<HtmlElement>() {
+                        let _ = element.focus();
+                    }
+                }
+            },
+            *drawer,
+        );
+    }
+    let on_drawer_keydown = {
+        let drawer = drawer.clone();
+        move |e: KeyboardEvent| {
+            let Some(open) = *drawer else {
+                return;
+            };
+            if e.key() == "Escape" {
+                // back to the button that opened it
+                let button = gloo::utils::document()
+                    .query_selector(&format!("[aria-controls=\"{}\"]", open.id()))
+                    .ok()
+                    .flatten()
+                    .and_then(|it| it.dyn_into::<HtmlElement>().ok());
+                if let Some(button) = button {
+                    let _ = button.focus();
+                }
+                drawer.set(None);
+            }
+        }
+    };
+    let drawer_expanded = |which: Drawer| (*drawer == Some(which)).to_string();
+    let on_settings_click = toggle_drawer(Drawer::Settings);
+    let on_build_options_click = toggle_drawer(Drawer::BuildOptions);
+    let on_status_options_click = {
+        let drawer = drawer.clone();
+        Callback::from(move |_| drawer.set(Some(Drawer::BuildOptions)))
+    };
+    let cursor = use_state_eq(Cursor::default);
+    let on_cursor = {
+        let cursor = cursor.clone();
+        Callback::from(move |it| cursor.set(it))
+    };
+    let on_dependencies_click = toggle_drawer(Drawer::Dependencies);
+    let on_history_click = toggle_drawer(Drawer::History);
+    let on_revisions_click = toggle_drawer(Drawer::Revisions);
+    let on_history_restore = {
+        let replacements = replacements.clone();
+        let drawer = drawer.clone();
+        Callback::from(move |code: String| {
+            replacements.set(vec![Replacement {
+                path: MAIN.to_string(),
+                code: Rc::from(code),
+            }]);
+            drawer.set(None);
+        })
+    };
+    let on_build_options_change = {
+        let build_options = build_options.clone();
+        Callback::from(move |options| build_options.set(options))
+    };
+    let on_bench_change = {
+        let bench = bench.clone();
+        Callback::from(move |renders| bench.set(renders))
+    };
+    let on_compare_change = {
+        let compare = compare.clone();
+        Callback::from(move |version| compare.set(version))
+    };
+    // against the first other version, the build options pick a particular one
+    let toggle_compare = {
+        let compare = compare.clone();
+        let build_options = build_options.clone();
+        Callback::from(move |()| {
+            let current = build_options.yew;
+            compare.set(match *compare {
+                Some(_) => None,
+                None => YewVersion::ALL.into_iter().find(|it| *it != current),
+            });
+        })
+    };
+    let on_dependencies_change = {
+        let build_options = build_options.clone();
+        Callback::from(move |dependencies| {
+            build_options.set(BuildOptions {
+                dependencies,
+                ..(*build_options).clone()
+            })
+        })
+    };
+
+    let editor_size = settings.editor_size;
+    let theme = use_context::<Theme>().unwrap();
+    let toggle_theme = {
+        let settings = settings.clone();
+        Callback::from(move |_| {
+            let preference = match theme.toggled() {
+                Theme::Light => ThemePreference::Light,
+                Theme::Dark | Theme::HighContrast => ThemePreference::Dark,
+            };
+            settings.dispatch(SettingsAction::Theme(preference));
+        })
+    };
+    let on_theme_click = toggle_theme.reform(|_: MouseEvent| ());
+    let collab_label = i18n.t(match collab_state.as_ref().map(|it| it.status) {
+        Some(CollabStatus::Connected) => "collab-connected",
+        Some(CollabStatus::Disconnected) => "collab-disconnected",
+        Some(CollabStatus::Connecting) | None => "collab-connecting",
+    });
+    let participants = collab_state
+        .as_ref()
+        .map(|it| it.participants.clone())
+        .unwrap_or_default();
+    let theme_icon = match theme {
+        Theme::Light => icon!("dark_mode", classes!("fill-gray-800", "dark:fill-gray-200")),
+        Theme::Dark | Theme::HighContrast => icon!(
+            "light_mode",
+            classes!("fill-gray-800", "dark:fill-gray-200")
+        ),
+    };
+
+    // what the keyboard shortcuts and the command palette can do right now, the same as
+    // the buttons on offer
+    let available = {
+        let busy = action_button_state.disabled();
+        let (chromeless, forkable, edited) = (embed.chromeless(), shared.is_some(), *edited);
+        let previewing = data.is_some();
+        move |action: Action| match action {
+            Action::Run => !run_disabled,
+            // embeds only offer running the code
+            _ if chromeless => false,
+            Action::Share => !busy,
+            Action::Fork => !busy && forkable,
+            Action::ShowChanges => edited,
+            Action::OpenRevisions | Action::CopyMarkdown => forkable,
+            // narrow screens stack the panes either way
+            Action::ToggleSplit => wide,
+            Action::ExportImage => previewing,
+            _ => true,
+        }
+    };
+    let palette_open = use_state(|| false);
+    let navigator = use_navigator().unwrap();
+    let on_action = {
+        let (shortcuts_open, share_open) = (shortcuts_open.clone(), share_open.clone());
+        let markdown_open = markdown_open.clone();
+        let palette_open = palette_open.clone();
+        let drawer = drawer.clone();
+        let diff_open = diff_open.clone();
+        let bench = bench.clone();
+        let settings = settings.clone();
+        let capture = capture.clone();
+        Callback::from(move |action| {
+            if !available(action) {
+                return;
+            }
+            match action {
+                Action::Run => run.emit(()),
+                Action::Share => share_open.set(true),
+                Action::Format => format.emit(()),
+                Action::ShowShortcuts => shortcuts_open.set(true),
+                Action::CommandPalette => palette_open.set(!*palette_open),
+                Action::Clippy => clippy.emit(()),
+                Action::Expand => expand.emit(()),
+                Action::Download => download_project.emit(()),
+                Action::Collaborate => collaborate.emit(()),
+                Action::Fork => fork.emit(()),
+                Action::CopyMarkdown => markdown_open.set(true),
+                Action::ShowChanges => diff_open.set(!*diff_open),
+                Action::CompareVersions => toggle_compare.emit(()),
+                Action::ToggleBenchmark => bench.set(match *bench {
+                    Some(_) => None,
+                    None => Some(DEFAULT_RENDERS),
+                }),
+                Action::ToggleTheme => toggle_theme.emit(()),
+                Action::ExportImage => capture.set(*capture + 1),
+                Action::ToggleSplit => {
+                    settings.dispatch(SettingsAction::Split(settings.split.toggled()))
+                }
+                Action::SwapPanes => {
+                    settings.dispatch(SettingsAction::SwapPanes(!settings.swap_panes))
+                }
+                Action::OpenExamples => navigator.push(&Route::Examples),
+                Action::SearchSnippets => navigator.push(&Route::Search),
+                Action::OpenSettings => drawer.set(Some(Drawer::Settings)),
+                Action::OpenBuildOptions => drawer.set(Some(Drawer::BuildOptions)),
+                Action::OpenDependencies => drawer.set(Some(Drawer::Dependencies)),
+                Action::OpenHistory => drawer.set(Some(Drawer::History)),
+                Action::OpenRevisions => drawer.set(Some(Drawer::Revisions)),
+            }
+        })
+    };
+    use_keymap(on_action.clone());
+    let palette_actions = Action::ALL
+        .iter()
+        .copied()
+        .filter(|it| available(*it))
+        .collect::<Vec<_>>();
+    let on_palette_select = {
+        let palette_open = palette_open.clone();
+        Callback::from(move |action| {
+            palette_open.set(false);
+            on_action.emit(action);
+        })
+    };
+    let on_palette_close = {
+        let palette_open = palette_open.clone();
+        Callback::from(move |_| palette_open.set(false))
+    };
+
+    // on narrow screens the stacked panes can be switched between, or shown together
+    let pane_button = |label: String, sizes: Vec<f64>| {
+        let pressed = *split_sizes == sizes;
+        let onclick = {
+            let split_sizes = split_sizes.clone();
+            let sizes = sizes.clone();
+            move |_| split_sizes.set(sizes.clone())
+        };
+        let classes = classes!(
+            "flex-1",
+            "min-h-[44px]",
+            "touch-manipulation",
+            pressed.then_some("bg-gray-300 dark:bg-gray-600")
+        );
+        html! {
+            <button {onclick} aria-pressed={pressed.to_string()} class={classes}>{label}</button>
+        }
+    };
+    let pane_switcher = html! {
+        <nav aria-label={i18n.t("panes")} class="flex border-b border-gray-300 dark:border-gray-600 bg-gray-100 dark:bg-gray-800">
+            {pane_button(i18n.t("panes-code"), CODE_ONLY.to_vec())}
+            {pane_button(i18n.t("panes-both"), vec![editor_size, 100.0 - editor_size])}
+            {pane_button(i18n.t("output-preview"), PREVIEW_ONLY.to_vec())}
+        </nav>
+    };
+
+    let diff = match (*diff_open, &*opened.borrow()) {
+        (true, Some(original)) => Some((original.clone(), contents.get(MAIN).unwrap_or_default())),
+        _ => None,
+    };
+
+    let swap = settings.swap_panes;
+    let collapsed_split = output_collapsed.then_some(if swap { 0 } else { 1 });
+    let mut min_sizes = if wide {
+        vec![100.0, 0.0]
+    } else {
+        vec![0.0, 0.0]
+    };
+    let mut sizes = (*split_sizes).clone();
+    if swap {
+        min_sizes.reverse();
+        sizes.reverse();
     }
+    let (direction, split_classes) = if side_by_side {
+        (
+            Direction::Horizontal,
+            "flex flex-row flex-grow overflow-hidden",
+        )
+    } else {
+        (
+            Direction::Vertical,
+            "flex flex-col flex-grow overflow-hidden",
+        )
+    };
 
-    let collapsed_split = if *output_collapsed { Some(1) } else { None };
+    let markers = format_markers
+        .iter()
+        .chain(compile_markers.iter())
+        .cloned()
+        .collect::<Vec<_>>();
 
-    html! {
-        <div class="flex flex-col h-screen">
-            <header class="bg-gray-700 p-3 flex justify-between">
-                <button onclick={on_run_click} disabled={action_button_state.disabled()} class={classes.clone()}>{icon!("play_arrow", classes!("fill-gray-200"))} {"Run"}</button>
+    let editor_pane = html! {
+        <section id="editor" aria-label={i18n.t("editor")} tabindex="-1" class="flex flex-col min-h-0">
+            if !embed.chromeless() {
+                <FileTabs paths={(*paths).clone()} active={(*active).clone()} dirty={(*dirty).clone()} onselect={on_select} oncreate={on_create} onrename={on_rename} ondelete={on_delete} viewer={expansion.is_some().then(|| AttrValue::from(i18n.t("files-expanded")))} onviewerclose={on_expansion_close} />
+            }
+            <div class="flex-grow min-h-0 relative" ondragenter={on_drag_enter}>
+                <Suspense fallback={{html! {i18n.t("loading")}}}>
+                    <Editor paths={(*paths).clone()} active={(*active).clone()} contents={contents.clone()} {oninput} replacements={(*replacements).clone()} {markers} reveal={(*reveal).clone()} onopened={on_opened} onpaste={on_paste} oncursor={on_cursor} read_only={embed.read_only()} presenting={embed.present} room={(*room).clone().map(AttrValue::from)} oncollab={on_collab_change} />
+                </Suspense>
+                if let Some(expansion) = &*expansion {
+                    <div class="absolute inset-0 z-10">
+                        <ExpansionView expansion={expansion.clone()} />
+                    </div>
+                }
+                if let Some((original, modified)) = diff {
+                    <div class="absolute inset-0 z-10">
+                        <DiffView {original} {modified} onclose={on_diff_close} />
+                    </div>
+                }
+                if *dragging {
+                    <div class="absolute inset-0 z-20 flex items-center justify-center border-4 border-dashed border-blue-500 bg-gray-200/80 dark:bg-gray-700/80 text-lg" ondragover={on_drag_over} ondragleave={on_drag_leave} ondrop={on_drop}>
+                        <span class="pointer-events-none">{i18n.t("drop-files")}</span>
+                    </div>
+                }
+            </div>
+            if let Some(lints) = &*lints {
+                <ClippyPanel lints={lints.clone()} onselect={on_lint_select} onclose={on_lints_close} />
+            }
+            if !embed.chromeless() {
+                <StatusBar cursor={*cursor} path={AttrValue::from((*active).clone())} options={(*build_options).clone()} onoptionsclick={on_status_options_click} />
+            }
+        </section>
+    };
+    let output_pane = html! {
+        <section id="output" aria-label={i18n.t("output")} tabindex="-1" class="w-full min-h-0 flex flex-col">
+            if let Some(ref data) = *data {
+                if !embed.chromeless() {
+                    {output_tabs}
+                }
+                <div class="flex-grow min-h-0 flex divide-x divide-gray-300 dark:divide-gray-600">
+                    <div class="min-h-0 min-w-0 flex-1 flex flex-col">
+                        if compare.is_some() {
+                            {compare::version_label(data.options.yew, i18n)}
+                        }
+                        <div class="flex-grow min-h-0 relative">
+                            <OutputContainer request={data.clone()} {onmessage} onstats={on_stats} onbuild={on_build} onpage={on_page} onbenchmark={on_benchmark} onnetwork={on_network} inspection={*inspection} ontree={on_tree} evaluation={(*evaluation).clone()} capture={*capture} />
+                            if let Some(message) = &*compile_error {
+                                <div class="absolute inset-0">
+                                    <CompileErrorPanel message={message.clone()} paths={(*paths).clone()} onselect={on_error_select.clone()} />
+                                </div>
+                            }
+                            // over the preview rather than instead of it, which would reload it
+                            if *output_tab != OutputTab::Preview {
+                                <div role="tabpanel" aria-label={output_tab.label(i18n)} class="absolute inset-0 z-10">
+                                    {match *output_tab {
+                                        OutputTab::Glue => html! { <GlueView build_id={(*build_id).clone()} /> },
+                                        OutputTab::Network => html! { <NetworkPanel log={network.clone()} /> },
+                                        OutputTab::Elements => html! { <InspectorPanel tree={(*tree).clone()} onrefresh={on_inspect.clone()} /> },
+                                        OutputTab::Html | OutputTab::Preview => html! { <PageSourceView page={(*page).clone()} /> },
+                                    }}
+                                </div>
+                            }
+                        </div>
+                        // presentations keep to the code and what it renders
+                        if !embed.present {
+                            if let Some((stats, previous)) = *build_stats {
+                                <BuildStatsBar {stats} {previous} />
+                            }
+                            if let Some((benchmark, previous)) = (*benchmark).clone() {
+                                <BenchmarkBar {benchmark} {previous} />
+                            }
+                            <ConsolePanel log={console} onevaluate={on_evaluate} />
+                        }
+                    </div>
+                    if let Some(version) = *compare {
+                        <ComparePane request={data.clone()} {version} paths={(*paths).clone()} onselect={on_error_select} />
+                    }
+                </div>
+            } else if autorun {
+                // until the code is loaded and the run starts
+                <p role="status" class="m-auto p-4 text-gray-600 dark:text-gray-400">{i18n.t("autorun-loading")}</p>
+            }
+        </section>
+    };
+    let panes = if swap {
+        [output_pane, editor_pane]
+    } else {
+        [editor_pane, output_pane]
+    };
 
-                <div>
-                    <button onclick={on_share_click} disabled={action_button_state.disabled()} class={classes}>{icon!("share", classes!("fill-gray-200"))} {"Share"}</button>
+    html! {
+        // `dvh` follows the on-screen keyboard, `vh` would leave the editor under it
+        <div class="flex flex-col h-screen supports-[height:100dvh]:h-[100dvh]">
+            <nav aria-label={i18n.t("skip-links")} class="contents">
+                <a href="#editor" class="sr-only focus:not-sr-only focus:p-2">{i18n.t("skip-to-editor")}</a>
+                if data.is_some() {
+                    <a href="#output" class="sr-only focus:not-sr-only focus:p-2">{i18n.t("skip-to-output")}</a>
+                }
+            </nav>
+            <div aria-live="polite" class="sr-only">{(*announcement).clone()}</div>
+            if let Some(href) = playground_href.clone().filter(|_| embed.present) {
+                <div class="bg-gray-100 dark:bg-gray-700 px-2 py-1 flex gap-4 items-center">
+                    <button onclick={on_run_click} disabled={run_disabled} title={run_title.clone()} class={classes}>{icon!("play_arrow", classes!("fill-gray-800", "dark:fill-gray-200"))} {i18n.t("run")}</button>
+                    if let Some(title) = &metadata.title {
+                        <h1 class="min-w-0 truncate text-2xl">{title}</h1>
+                    }
+                    <a {href} class="ml-auto text-sm hover:underline">{i18n.t("present-exit")}</a>
                 </div>
-            </header>
-            <Split min_sizes={vec![100.0, 0.0]} sizes={(*split_sizes).clone()} collapsed={collapsed_split} direction={Direction::Vertical} class="flex flex-col flex-grow overflow-hidden">
-                <div>
-                    <Suspense fallback={{html! {"loading..."}}}>
-                        <Editor {oninput} />
-                    </Suspense>
+            } else if let Some(href) = playground_href {
+                <div class="bg-gray-100 dark:bg-gray-700 px-2 py-1 flex gap-2 items-center justify-between text-sm">
+                    <button onclick={on_run_click} disabled={run_disabled} title={run_title.clone()} class={classes}>{icon!("play_arrow", classes!("fill-gray-800", "dark:fill-gray-200"))} {i18n.t("run")}</button>
+                    <a {href} target="_blank" rel="noopener" class="hover:underline">{i18n.t("open-playground")}</a>
                 </div>
-                <div class="w-full min-h-0">
-                    if let Some(ref data) = *data {
-                        <OutputContainer value={data} />
+            } else {
+                <header class="bg-gray-100 dark:bg-gray-700 p-2 sm:p-3 flex flex-wrap gap-2 justify-between">
+                    <button onclick={on_run_click} disabled={run_disabled} title={run_title.clone()} class={classes.clone()}>{icon!("play_arrow", classes!("fill-gray-800", "dark:fill-gray-200"))} {i18n.t("run")}</button>
+                    if let Some(title) = &metadata.title {
+                        <h1 title={metadata.description.clone()} class="self-center min-w-0 truncate text-lg">{title}</h1>
                     }
+                    if !metadata.tags.is_empty() {
+                        <div class="self-center">{search::tag_links(&metadata.tags)}</div>
+                    }
+                    if let Some(original) = &*forked_from {
+                        <a href={format!("/?shared={}", original)} class="self-center text-sm hover:underline">{i18n.t_args("forked-from", &[("id", original)])}</a>
+                    }
+
+                    <div class="flex flex-wrap gap-2">
+                        <Link<Route> to={Route::Examples} classes={classes.clone()}>{i18n.t("examples")}</Link<Route>>
+                        <Link<Route> to={Route::Search} classes={classes.clone()}>{i18n.t("search")}</Link<Route>>
+                        <RecentMenu classes={classes.clone()} />
+                        if *edited {
+                            <button onclick={on_diff_click} title={i18n.t("changes-title")} aria-label={i18n.t("changes-title")} aria-pressed={diff_open.to_string()} class={classes.clone()}>{icon!("difference", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("changes")}</span></button>
+                        }
+                        <button onclick={on_format_click} title={i18n.t("format")} aria-label={i18n.t("format")} class={classes.clone()}>{icon!("format_align_left", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("format")}</span></button>
+                        <button onclick={on_clippy_click} title={i18n.t("clippy-title")} aria-label={i18n.t("clippy-title")} class={classes.clone()}>{icon!("rule", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("clippy")}</span></button>
+                        <button onclick={on_expand_click} title={i18n.t("expand-title")} aria-label={i18n.t("expand-title")} class={classes.clone()}>{icon!("unfold_more", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("expand")}</span></button>
+                        <button onclick={on_download_click} title={i18n.t("download-title")} aria-label={i18n.t("download-title")} class={classes.clone()}>{icon!("download", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        if shared.is_some() {
+                            <button onclick={on_fork_click} title={i18n.t("fork-title")} aria-label={i18n.t("fork")} disabled={action_button_state.disabled()} class={classes.clone()}>{icon!("fork_right", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("fork")}</span></button>
+                            <button onclick={on_markdown_click} title={i18n.t("markdown-title")} aria-label={i18n.t("markdown-title")} class={classes.clone()}>{icon!("code", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        }
+                        if room.is_some() {
+                            if !participants.is_empty() {
+                                <ul aria-label={i18n.t_args("collab-participants", &[("count", &participants.len().to_string())])} class="flex -space-x-1">
+                                    { for participants.iter().map(|participant| html! {
+                                        <li title={participant.name.clone()} style={format!("background-color: {}", participant.color)} class="w-6 h-6 rounded-full ring-2 ring-gray-100 dark:ring-gray-700 text-white text-xs flex items-center justify-center select-none">
+                                            {participant.name.chars().next().map(|it| it.to_uppercase().to_string()).unwrap_or_default()}
+                                        </li>
+                                    }) }
+                                </ul>
+                            }
+                            <button onclick={on_collab_click} title={i18n.t("collab-copy")} aria-label={i18n.t("collab-copy")} class={classes.clone()}>{icon!("group", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{collab_label}</span></button>
+                            <button onclick={on_leave_click} title={i18n.t("collab-leave")} aria-label={i18n.t("collab-leave")} class={classes.clone()}>{icon!("logout", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        } else {
+                            <button onclick={on_collab_click} title={i18n.t("collab-title")} aria-label={i18n.t("collab-title")} class={classes.clone()}>{icon!("group", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("collab")}</span></button>
+                        }
+                        <button onclick={on_share_click} title={i18n.t("share")} aria-label={i18n.t("share")} disabled={action_button_state.disabled()} class={classes.clone()}>{icon!("share", classes!("fill-gray-800", "dark:fill-gray-200"))} <span class="hidden sm:inline">{i18n.t("share")}</span></button>
+                        <button onclick={on_theme_click} title={i18n.t("toggle-theme")} aria-label={i18n.t("toggle-theme")} class={classes.clone()}>{theme_icon}</button>
+                        <button onclick={on_shortcuts_click} title={i18n.t("shortcuts")} aria-label={i18n.t("shortcuts")} class={classes.clone()}>{icon!("keyboard", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        <button onclick={on_dependencies_click} title={i18n.t("dependencies")} aria-label={i18n.t("dependencies")} aria-controls={Drawer::Dependencies.id()} aria-expanded={drawer_expanded(Drawer::Dependencies)} class={classes.clone()}>{icon!("extension", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        <button onclick={on_history_click} title={i18n.t("history")} aria-label={i18n.t("history")} aria-controls={Drawer::History.id()} aria-expanded={drawer_expanded(Drawer::History)} class={classes.clone()}>{icon!("history", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        if shared.is_some() {
+                            <button onclick={on_revisions_click} title={i18n.t("revisions")} aria-label={i18n.t("revisions")} aria-controls={Drawer::Revisions.id()} aria-expanded={drawer_expanded(Drawer::Revisions)} class={classes.clone()}>{icon!("bookmarks", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        }
+                        <button onclick={on_build_options_click} title={i18n.t("build-options")} aria-label={i18n.t("build-options")} aria-controls={Drawer::BuildOptions.id()} aria-expanded={drawer_expanded(Drawer::BuildOptions)} class={classes.clone()}>{icon!("tune", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                        <button onclick={on_settings_click} title={i18n.t("settings")} aria-label={i18n.t("settings")} aria-controls={Drawer::Settings.id()} aria-expanded={drawer_expanded(Drawer::Settings)} class={classes}>{icon!("settings", classes!("fill-gray-800", "dark:fill-gray-200"))}</button>
+                    </div>
+                </header>
+            }
+            if let Some(problem) = connection.problem() {
+                <div role="alert" class="p-2 text-center bg-yellow-100 dark:bg-yellow-900">{i18n.t(problem)}</div>
+            }
+            if previous_session.is_some() {
+                <div role="alert" class="p-2 flex gap-3 items-center justify-center bg-blue-100 dark:bg-blue-900">
+                    {i18n.t("restore-session")}
+                    <button onclick={on_restore_click} class="px-2 rounded bg-blue-200 dark:bg-blue-700 hover:bg-blue-300 dark:hover:bg-blue-600">{i18n.t("restore")}</button>
+                    <button onclick={on_discard_click} class="px-2 rounded hover:bg-blue-200 dark:hover:bg-blue-800">{i18n.t("discard")}</button>
+                </div>
+            }
+            if let Some(open) = *drawer {
+                <div ref={drawer_ref} id={open.id()} role="region" aria-label={open.label(i18n)} tabindex="-1" onkeydown={on_drawer_keydown}>
+                    {match open {
+                        Drawer::Settings => html! { <SettingsPanel /> },
+                        Drawer::BuildOptions => html! {
+                            <BuildOptionsPanel options={(*build_options).clone()} onchange={on_build_options_change} compare={*compare} oncompare={on_compare_change} bench={*bench} onbench={on_bench_change} />
+                        },
+                        Drawer::Dependencies => html! {
+                            <DependenciesPanel dependencies={build_options.dependencies.clone()} onchange={on_dependencies_change} />
+                        },
+                        Drawer::History => html! {
+                            <HistoryPanel snippet={history_key.borrow().clone()} onrestore={on_history_restore} />
+                        },
+                        Drawer::Revisions => html! {
+                            <RevisionsPanel paste={shared.clone().unwrap_or_default()} owner_token={shared.as_deref().and_then(owned::token)} contents={contents.clone()} />
+                        },
+                    }}
                 </div>
-            </Split>
+            }
+            if *shortcuts_open {
+                <ShortcutsModal onclose={on_shortcuts_close} />
+            }
+            if *palette_open {
+                <CommandPalette actions={palette_actions} onselect={on_palette_select} onclose={on_palette_close} />
+            }
+            if *share_open {
+                <ShareModal metadata={(*metadata).clone()} onshare={on_share} onclose={on_share_close} />
+            }
+            if *markdown_open {
+                <BadgeModal query={markdown_query} title={metadata.title.clone()} onclose={on_markdown_close} />
+            }
+            if !wide && data.is_some() {
+                {pane_switcher}
+            }
+            <main class="contents">
+                // Split.js takes the panes in their order on the page when it's created,
+                // swapping them creates it anew
+                <Split key={swap.to_string()} {min_sizes} {sizes} collapsed={collapsed_split} {direction} on_drag_end={(*on_drag_end).clone()} class={split_classes}>
+                    { for panes }
+                </Split>
+            </main>
         </div>
     }
 }
@@ -0,0 +1,532 @@
+//! Editing the main file together with others, in a room of the backend. The code is
+//! a Yjs text shared through the room: edits made in the editor go into the text and
+//! out to the room, edits coming in from the room are played back into the editor.
+//!
+//! Everyone in the room also tells the others their name, color and selection, the
+//! selections of the others are shown in the editor with their names at their cursors.
+
+use crate::api::collab::{self, Room, RoomEvent};
+use gloo::timers::callback::Timeout;
+use js_sys::{Array, Object};
+use monaco::api::{DisposableClosure, TextModel};
+use monaco::sys::editor::{
+    ICodeEditor, IIdentifiedSingleEditOperation, IModelContentChange, IModelContentChangedEvent,
+    IModelDecorationOptions, IModelDeltaDecoration, ITextModel, TrackedRangeStickiness,
+};
+use monaco::sys::{IDisposable, IPosition, Position, Range};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::{Rc, Weak};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Element;
+use yew::Callback;
+use yrs::types::text::TextEvent;
+use yrs::types::Delta;
+use yrs::updates::decoder::Decode;
+use yrs::{
+    Assoc, Doc, GetString, IndexedSequence, Observable, OffsetKind, Options, ReadTxn, StateVector,
+    StickyIndex, Subscription, Text, TextRef, Transact, TransactionMut, Update,
+};
+
+/// How long to wait before trying again after the connection to the room was lost.
+const RECONNECT_DELAY_MS: u32 = 2000;
+
+/// Everyone in a room gets one of these, picked by their client id.
+const COLORS: [&str; 8] = [
+    "#e11d48", "#2563eb", "#16a34a", "#d97706", "#9333ea", "#0891b2", "#db2777", "#65a30d",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollabStatus {
+    Connecting,
+    Connected,
+    /// Edits are kept and sent once the connection is back.
+    Disconnected,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub name: String,
+    /// A CSS color, the one their selection is shown in.
+    pub color: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollabState {
+    pub status: CollabStatus,
+    /// The others in the room, in the order they joined.
+    pub participants: Vec<Participant>,
+}
+
+/// What someone in the room tells the others about themselves.
+#[derive(Serialize, Deserialize)]
+struct Presence {
+    name: String,
+    /// Index into [`COLORS`].
+    color: usize,
+    /// In `main.rs`, `None` while another file is open.
+    selection: Option<Selection>,
+}
+
+impl Presence {
+    fn color(&self) -> &'static str {
+        COLORS[self.color % COLORS.len()]
+    }
+}
+
+/// Positions in the text rather than offsets, they stay put as others edit around them.
+#[derive(Serialize, Deserialize)]
+struct Selection {
+    anchor: StickyIndex,
+    /// Where the cursor is.
+    head: StickyIndex,
+}
+
+/// The presence of another peer as the backend passes it on, `None` once they left.
+#[derive(Deserialize)]
+struct PeerPresence {
+    peer: u64,
+    presence: Option<Presence>,
+}
+
+struct State {
+    id: String,
+    doc: Doc,
+    text: TextRef,
+    model: TextModel,
+    editor: ICodeEditor,
+    name: RefCell<String>,
+    /// Index into [`COLORS`].
+    color: usize,
+    room: RefCell<Option<Room>>,
+    reconnect: RefCell<Option<Timeout>>,
+    /// Whether the room sent its document over the current connection yet.
+    joined: Cell<bool>,
+    /// Whether the text was synced with the room once, from then on the editor and
+    /// the text are kept the same.
+    synced: Cell<bool>,
+    /// Set while edits from the room are played back into the editor, so they aren't
+    /// taken for the user's own.
+    remote: Cell<bool>,
+    status: Cell<CollabStatus>,
+    /// The others in the room by the id the backend gave them, which goes up as they join.
+    peers: RefCell<BTreeMap<u64, Presence>>,
+    /// Of their selections, as last returned by Monaco.
+    decorations: RefCell<Array>,
+    /// Holds the colors and names of the others, see [`State::render`].
+    style: Element,
+    onchange: Callback<CollabState>,
+}
+
+impl State {
+    fn report(&self, status: CollabStatus) {
+        self.status.set(status);
+        let participants = self
+            .peers
+            .borrow()
+            .values()
+            .map(|presence| Participant {
+                name: presence.name.clone(),
+                color: presence.color(),
+            })
+            .collect();
+        self.onchange.emit(CollabState {
+            status,
+            participants,
+        });
+    }
+
+    fn connect(self: &Rc<Self>) {
+        self.report(CollabStatus::Connecting);
+        self.joined.set(false);
+        let state = Rc::downgrade(self);
+        let onevent = Callback::from(move |event| {
+            if let Some(state) = Weak::upgrade(&state) {
+                state.on_event(event);
+            }
+        });
+        match collab::join(&self.id, onevent) {
+            Ok(room) => *self.room.borrow_mut() = Some(room),
+            Err(e) => {
+                tracing::warn!(?e, "failed to join the room");
+                self.on_event(RoomEvent::Closed);
+            }
+        }
+    }
+
+    fn on_event(self: &Rc<Self>, event: RoomEvent) {
+        match event {
+            RoomEvent::Update(update) => {
+                let Ok(update) = Update::decode_v1(&update) else {
+                    tracing::warn!("the room sent an update that doesn't decode");
+                    return;
+                };
+                self.remote.set(self.synced.get());
+                let applied = self.doc.transact_mut().apply_update(update);
+                self.remote.set(false);
+                if let Err(e) = applied {
+                    tracing::warn!(?e, "failed to apply an update from the room");
+                }
+                // the first update is the room's document
+                if !self.joined.replace(true) {
+                    self.on_joined();
+                }
+                self.render();
+            }
+            RoomEvent::Presence(presence) => {
+                let presence = match serde_json::from_str::<PeerPresence>(&presence) {
+                    Ok(presence) => presence,
+                    Err(e) => {
+                        tracing::warn!(?e, "the room sent a presence that doesn't parse");
+                        return;
+                    }
+                };
+                let mut peers = self.peers.borrow_mut();
+                match presence.presence {
+                    Some(it) => peers.insert(presence.peer, it),
+                    None => peers.remove(&presence.peer),
+                };
+                drop(peers);
+                self.render();
+                self.report(self.status.get());
+            }
+            RoomEvent::Closed => {
+                self.room.borrow_mut().take();
+                // they're all sent again after reconnecting
+                self.peers.borrow_mut().clear();
+                self.render();
+                self.report(CollabStatus::Disconnected);
+                let state = Rc::downgrade(self);
+                let timeout = Timeout::new(RECONNECT_DELAY_MS, move || {
+                    if let Some(state) = Weak::upgrade(&state) {
+                        state.connect();
+                    }
+                });
+                *self.reconnect.borrow_mut() = Some(timeout);
+            }
+        }
+    }
+
+    fn on_joined(&self) {
+        if !self.synced.get() {
+            let fresh = self.doc.transact().state_vector() == StateVector::default();
+            if fresh {
+                // the room was just opened, with the code in the editor
+                let mut txn = self.doc.transact_mut();
+                self.text.insert(&mut txn, 0, &self.model.get_value());
+            } else {
+                let code = self.text.get_string(&self.doc.transact());
+                self.remote.set(true);
+                replace_all(self.model.as_ref(), &code);
+                self.remote.set(false);
+            }
+            self.synced.set(true);
+        }
+        // whatever the room missed, edits made while disconnected or the code it opens with
+        let state = self
+            .doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+        if let Some(room) = &*self.room.borrow() {
+            room.send(&state);
+        }
+        self.send_presence();
+        self.report(CollabStatus::Connected);
+    }
+
+    /// The selection in the editor, if it has `main.rs` open.
+    fn selection(&self) -> Option<Selection> {
+        let model: &ITextModel = self.model.as_ref();
+        let open = self.editor.get_model()?;
+        if AsRef::<JsValue>::as_ref(&open) != AsRef::<JsValue>::as_ref(model) {
+            return None;
+        }
+        let selection = self.editor.get_selection()?;
+        let offset = |line, column| {
+            let position = Position::new(line, column);
+            model.get_offset_at(position.unchecked_ref()) as u32
+        };
+        let anchor = offset(
+            selection.selection_start_line_number(),
+            selection.selection_start_column(),
+        );
+        let head = offset(
+            selection.position_line_number(),
+            selection.position_column(),
+        );
+        let mut txn = self.doc.try_transact_mut().ok()?;
+        // there's no character after the end of the text to stick to
+        let mut sticky = |offset| {
+            self.text
+                .sticky_index(&mut txn, offset, Assoc::After)
+                .or_else(|| self.text.sticky_index(&mut txn, offset, Assoc::Before))
+        };
+        Some(Selection {
+            anchor: sticky(anchor)?,
+            head: sticky(head)?,
+        })
+    }
+
+    fn send_presence(&self) {
+        // edits from the room move the cursor, but not to anywhere new in the text
+        if self.remote.get() || !self.synced.get() {
+            return;
+        }
+        let presence = Presence {
+            name: self.name.borrow().clone(),
+            color: self.color,
+            selection: self.selection(),
+        };
+        match serde_json::to_string(&presence) {
+            Ok(presence) => {
+                if let Some(room) = &*self.room.borrow() {
+                    room.send_presence(&presence);
+                }
+            }
+            Err(e) => tracing::warn!(?e, "failed to serialize the presence"),
+        }
+    }
+
+    /// Shows the selections of the others in the editor, with their names at their
+    /// cursors. Their colors and names go into the style sheet as custom properties,
+    /// the rest of the looks are in `globals.scss`.
+    fn render(&self) {
+        let model: &ITextModel = self.model.as_ref();
+        let txn = self.doc.transact();
+        let mut styles = String::new();
+        let decorations = Array::new();
+        for (peer, presence) in &*self.peers.borrow() {
+            let class = format!("collab-peer-{}", peer);
+            styles += &format!(
+                ".{} {{ --collab-color: {}; --collab-name: \"{}\"; }}\n",
+                class,
+                presence.color(),
+                css_escape(&presence.name)
+            );
+            let Some(selection) = &presence.selection else {
+                continue;
+            };
+            let (Some(anchor), Some(head)) = (
+                selection.anchor.get_offset(&txn),
+                selection.head.get_offset(&txn),
+            ) else {
+                continue;
+            };
+            let (anchor, head) = (anchor.index, head.index);
+            let options: IModelDecorationOptions = Object::new().unchecked_into();
+            options.set_class_name(Some(&format!("collab-selection {}", class)));
+            let cursor = format!("collab-cursor {}", class);
+            if head < anchor {
+                options.set_before_content_class_name(Some(&cursor));
+            } else {
+                options.set_after_content_class_name(Some(&cursor));
+            }
+            options.set_stickiness(Some(TrackedRangeStickiness::Nevergrowswhentypingatedges));
+            let start = model.get_position_at(anchor.min(head) as f64);
+            let end = model.get_position_at(anchor.max(head) as f64);
+            let range = Range::from_positions(
+                start.unchecked_ref::<IPosition>(),
+                end.unchecked_ref::<IPosition>(),
+            );
+            let decoration: IModelDeltaDecoration = Object::new().unchecked_into();
+            decoration.set_range(range.unchecked_ref());
+            decoration.set_options(&options);
+            decorations.push(&decoration);
+        }
+        self.style.set_text_content(Some(&styles));
+        let old = self.decorations.replace(Array::new());
+        *self.decorations.borrow_mut() = model.delta_decorations(&old, &decorations, None);
+    }
+
+    /// Takes the changes of an edit in the editor into the text.
+    fn on_local_changes(&self, changes: Array) {
+        if self.remote.get() || !self.synced.get() {
+            return;
+        }
+        let mut changes = changes
+            .iter()
+            .map(|it| it.unchecked_into::<IModelContentChange>())
+            .collect::<Vec<_>>();
+        // the offsets are into the code as it was before, so the last change goes first
+        changes.sort_by(|a, b| b.range_offset().total_cmp(&a.range_offset()));
+        let update = {
+            let mut txn = self.doc.transact_mut();
+            for change in changes {
+                let offset = change.range_offset() as u32;
+                let len = change.range_length() as u32;
+                if len > 0 {
+                    self.text.remove_range(&mut txn, offset, len);
+                }
+                let inserted = change.text();
+                if !inserted.is_empty() {
+                    self.text.insert(&mut txn, offset, &inserted);
+                }
+            }
+            txn.encode_update_v1()
+        };
+        if let Some(room) = &*self.room.borrow() {
+            room.send(&update);
+        }
+    }
+
+    /// Plays the changes of an update from the room back into the editor.
+    fn on_remote_changes(&self, txn: &TransactionMut, event: &TextEvent) {
+        if !self.remote.get() {
+            return;
+        }
+        let model: &ITextModel = self.model.as_ref();
+        let mut offset = 0;
+        for delta in event.delta(txn) {
+            match delta {
+                Delta::Retain(len, _) => offset += len,
+                Delta::Inserted(inserted, _) => {
+                    let inserted = inserted.to_string();
+                    edit(model, offset, offset, &inserted);
+                    offset += inserted.encode_utf16().count() as u32;
+                }
+                Delta::Deleted(len) => edit(model, offset, offset + len, ""),
+            }
+        }
+    }
+}
+
+/// Replaces the UTF-16 range from `start` to `end` with `text`, outside of the undo
+/// stack, the user can't undo what someone else typed.
+fn edit(model: &ITextModel, start: u32, end: u32, text: &str) {
+    let start = model.get_position_at(start as f64);
+    let end = model.get_position_at(end as f64);
+    let range = Range::from_positions(
+        start.unchecked_ref::<IPosition>(),
+        end.unchecked_ref::<IPosition>(),
+    );
+    let edit: IIdentifiedSingleEditOperation = Object::new().unchecked_into();
+    edit.set_range(&range);
+    edit.set_text(Some(text));
+    model.apply_edits(&Array::of1(&edit));
+}
+
+/// Escapes `text` for a CSS string, names can have any characters.
+fn css_escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == ' ' => c.to_string(),
+            c => format!("\\{:x} ", c as u32),
+        })
+        .collect()
+}
+
+fn replace_all(model: &ITextModel, text: &str) {
+    let edit: IIdentifiedSingleEditOperation = Object::new().unchecked_into();
+    edit.set_range(&model.get_full_model_range());
+    edit.set_text(Some(text));
+    model.apply_edits(&Array::of1(&edit));
+}
+
+/// Shares the code of `model` through a room until dropped.
+pub struct Collab {
+    state: Rc<State>,
+    _change: DisposableClosure<dyn FnMut(IModelContentChangedEvent)>,
+    _observer: Subscription,
+    selection: IDisposable,
+    _on_selection: Closure<dyn Fn(JsValue)>,
+}
+
+impl Collab {
+    /// Joins the room with the given id. A room nobody's in yet is opened with the
+    /// code in `model`, otherwise the code in `model` is replaced with the room's. The
+    /// others see the selection of `editor` while it has `model` open.
+    pub fn join(
+        id: &str,
+        model: TextModel,
+        editor: ICodeEditor,
+        name: String,
+        onchange: Callback<CollabState>,
+    ) -> Self {
+        let doc = Doc::with_options(Options {
+            // Yjs keeps client ids to 53 bits, the largest integers JavaScript holds
+            client_id: (js_sys::Math::random() * (1u64 << 53) as f64) as u64,
+            // what Monaco counts offsets in
+            offset_kind: OffsetKind::Utf16,
+            ..Options::default()
+        });
+        let text = doc.get_or_insert_text("code");
+        let color = (doc.client_id() % COLORS.len() as u64) as usize;
+        let style = gloo::utils::document()
+            .create_element("style")
+            .expect("failed to create a style element");
+        let _ = gloo::utils::head().append_child(&style);
+        let state = Rc::new(State {
+            id: id.to_string(),
+            doc,
+            text: text.clone(),
+            model: model.clone(),
+            editor: editor.clone().unchecked_into(),
+            name: RefCell::new(name),
+            color,
+            room: RefCell::new(None),
+            reconnect: RefCell::new(None),
+            joined: Cell::new(false),
+            synced: Cell::new(false),
+            remote: Cell::new(false),
+            status: Cell::new(CollabStatus::Connecting),
+            peers: RefCell::new(BTreeMap::new()),
+            decorations: RefCell::new(Array::new()),
+            style,
+            onchange,
+        });
+        let change = {
+            let state = Rc::downgrade(&state);
+            model.on_did_change_content(move |event| {
+                if let Some(state) = Weak::upgrade(&state) {
+                    state.on_local_changes(event.changes());
+                }
+            })
+        };
+        let observer = {
+            let state = Rc::downgrade(&state);
+            text.observe(move |txn, event| {
+                if let Some(state) = Weak::upgrade(&state) {
+                    state.on_remote_changes(txn, event);
+                }
+            })
+        };
+        let on_selection = {
+            let state = Rc::downgrade(&state);
+            Closure::<dyn Fn(JsValue)>::new(move |_| {
+                if let Some(state) = Weak::upgrade(&state) {
+                    state.send_presence();
+                }
+            })
+        };
+        let selection =
+            editor.on_did_change_cursor_selection(on_selection.as_ref().unchecked_ref());
+        state.connect();
+        Self {
+            state,
+            _change: change,
+            _observer: observer,
+            selection,
+            _on_selection: on_selection,
+        }
+    }
+
+    /// Changes the name the others see.
+    pub fn set_name(&self, name: String) {
+        if self.state.name.replace(name) != *self.state.name.borrow() {
+            self.state.send_presence();
+        }
+    }
+}
+
+impl Drop for Collab {
+    fn drop(&mut self) {
+        self.selection.dispose();
+        self.state.reconnect.borrow_mut().take();
+        self.state.room.borrow_mut().take();
+        self.state.peers.borrow_mut().clear();
+        self.state.render();
+        self.state.style.remove();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::i18n::I18n;
+use crate::utils::copy_to_clipboard;
+use crate::utils::query::{Embed, Query};
+use web_sys::{HtmlElement, HtmlInputElement};
+use yew::prelude::*;
+
+/// The badge the Markdown shows, linking to the snippet.
+const BADGE_URL: &str = "https://img.shields.io/badge/Yew-Playground-009a5b?logo=rust";
+const BADGE_ALT: &str = "Open in the Yew Playground";
+/// Of the embed, in pixels, it takes up the width of the page.
+const EMBED_HEIGHT: u32 = 480;
+
+/// Escapes text for an attribute of HTML, the embed's `src` and `title`.
+fn escape_attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The playground's link to the snippet of `query`.
+fn link(query: &Query, embed: Embed) -> String {
+    let origin = gloo::utils::window()
+        .location()
+        .origin()
+        .expect("failed to get origin");
+    let query = Query {
+        embed,
+        ..query.clone()
+    };
+    let query = serde_urlencoded::to_string(query).expect("query serializes");
+    format!("{}/?{}", origin, query)
+}
+
+/// A badge linking to the snippet, with its title when it has one, and optionally an
+/// iframe embedding the playground with it after it.
+fn markdown(query: &Query, title: Option<&str>, with_embed: bool) -> String {
+    let href = link(query, Embed::default());
+    let mut markdown = match title {
+        Some(title) => format!(
+            "[![{}]({})]({} \"{}\")",
+            BADGE_ALT,
+            BADGE_URL,
+            href,
+            title.replace('"', "\\\"")
+        ),
+        None => format!("[![{}]({})]({})", BADGE_ALT, BADGE_URL, href),
+    };
+    if with_embed {
+        let src = link(
+            query,
+            Embed {
+                embed: true,
+                ..Embed::default()
+            },
+        );
+        markdown.push_str(&format!(
+            "\n\n<iframe src=\"{}\" title=\"{}\" width=\"100%\" height=\"{}\" style=\"border: 0\" loading=\"lazy\"></iframe>",
+            escape_attribute(&src),
+            escape_attribute(title.unwrap_or(BADGE_ALT)),
+            EMBED_HEIGHT
+        ));
+    }
+    markdown
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BadgeModalProps {
+    /// Of the shared snippet, linked to with its build options.
+    pub query: Query,
+    pub title: Option<String>,
+    pub onclose: Callback<()>,
+}
+
+/// Markdown for READMEs and forum posts linking to the shared snippet, with a badge and
+/// optionally an embed of the playground, shown before it's copied.
+#[function_component]
+pub fn BadgeModal(props: &BadgeModalProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let toasts = use_context::<ToastContext>().unwrap();
+    let with_embed = use_state_eq(|| false);
+    let copy_ref = use_node_ref();
+    {
+        let copy_ref = copy_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(button) = copy_ref.cast::<HtmlElement>() {
+                    let _ = button.focus();
+                }
+            },
+            (),
+        );
+    }
+
+    let text = markdown(&props.query, props.title.as_deref(), *with_embed);
+    let onclose = props.onclose.reform(|_: MouseEvent| ());
+    let onkeydown = {
+        let onclose = props.onclose.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                onclose.emit(());
+            }
+        }
+    };
+    let onchange = {
+        let with_embed = with_embed.clone();
+        move |e: Event| with_embed.set(e.target_unchecked_into::<HtmlInputElement>().checked())
+    };
+    let oncopy = {
+        let text = text.clone();
+        let onclose = props.onclose.clone();
+        move |_| {
+            let (text, toasts, onclose) = (text.clone(), toasts.clone(), onclose.clone());
+            wasm_bindgen_futures::spawn_local(async move {
+                match copy_to_clipboard(&text).await {
+                    Ok(()) => {
+                        toast::show(&toasts, ToastKind::Success, i18n.t("markdown-copied"));
+                        onclose.emit(());
+                    }
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to copy markdown");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("markdown-copy-failed"));
+                    }
+                }
+            });
+        }
+    };
+
+    html! {
+        <div onclick={onclose.clone()} {onkeydown} class="fixed inset-0 z-20 bg-black/50 flex items-center justify-center">
+            <div onclick={|e: MouseEvent| e.stop_propagation()} role="dialog" aria-modal="true" aria-labelledby="markdown-heading" class="w-[32rem] max-w-full p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+                <h2 id="markdown-heading" class="text-lg">{i18n.t("markdown")}</h2>
+                <textarea readonly=true rows="6" value={text} aria-label={i18n.t("markdown")} class="bg-gray-200 dark:bg-gray-700 rounded p-1 font-mono text-sm" />
+                <label class="flex gap-2 items-center">
+                    <input type="checkbox" checked={*with_embed} {onchange} />
+                    {i18n.t("markdown-embed")}
+                </label>
+                <div class="flex gap-2 justify-end">
+                    <button type="button" onclick={onclose} class="p-2 rounded-md hover:bg-gray-200 dark:hover:bg-gray-700">{i18n.t("cancel")}</button>
+                    <button ref={copy_ref} type="button" onclick={oncopy} class="p-2 bg-gray-200 dark:bg-gray-700 rounded-md hover:bg-gray-300 dark:hover:bg-gray-900">{i18n.t("markdown-copy")}</button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,95 @@
+use crate::i18n::I18n;
+use serde::Deserialize;
+use yew::prelude::*;
+
+/// Re-renders a benchmark can be asked for, as the build options offer them.
+pub const RENDERS: &[&str] = &["10", "100", "1000"];
+/// What the command palette turns benchmarks on with.
+pub const DEFAULT_RENDERS: u32 = 100;
+
+/// Timings the benchmark the compiler builds for `bench` runs reports from the
+/// preview, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Benchmark {
+    /// Of mounting the snippet's `App`, its effects included.
+    pub mount_ms: f64,
+    /// Of each re-render of it, in the order they ran.
+    pub render_ms: Vec<f64>,
+}
+
+impl Benchmark {
+    /// The re-render times sorted, the harness always reports at least one.
+    fn sorted(&self) -> Vec<f64> {
+        let mut sorted = self.render_ms.clone();
+        sorted.sort_by(f64::total_cmp);
+        sorted
+    }
+
+    pub fn median_ms(&self) -> f64 {
+        let sorted = self.sorted();
+        match sorted.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+            len => sorted[len / 2],
+        }
+    }
+}
+
+fn format_ms(ms: f64) -> String {
+    if ms < 10.0 {
+        format!("{:.2} ms", ms)
+    } else {
+        format!("{:.1} ms", ms)
+    }
+}
+
+/// How much a time changed since the last benchmark, nothing if it barely did.
+fn time_change(ms: f64, previous: Option<f64>) -> Html {
+    let previous = match previous {
+        Some(previous) if (previous - ms).abs() >= 0.005 => previous,
+        _ => return html! {},
+    };
+    let (sign, classes) = if ms > previous {
+        ("+", "text-red-700 dark:text-red-400")
+    } else {
+        ("−", "text-green-700 dark:text-green-400")
+    };
+    html! {
+        <span class={classes}>{format!(" ({}{})", sign, format_ms((ms - previous).abs()))}</span>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BenchmarkBarProps {
+    pub benchmark: Benchmark,
+    /// Of the last benchmark, to compare the times with.
+    pub previous: Option<Benchmark>,
+}
+
+/// Status bar under the preview with the timings of a benchmark run.
+#[function_component]
+pub fn BenchmarkBar(props: &BenchmarkBarProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let benchmark = &props.benchmark;
+    let previous = props.previous.as_ref();
+    let sorted = benchmark.sorted();
+    let (min, max) = match (sorted.first(), sorted.last()) {
+        (Some(min), Some(max)) => (*min, *max),
+        _ => (0.0, 0.0),
+    };
+    let median = benchmark.median_ms();
+
+    html! {
+        <div role="status" class="px-3 py-1 flex flex-wrap gap-x-4 text-xs bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300">
+            <span>
+                {i18n.t_args("benchmark-mount", &[("time", &format_ms(benchmark.mount_ms))])}
+                {time_change(benchmark.mount_ms, previous.map(|it| it.mount_ms))}
+            </span>
+            <span>
+                {i18n.t_args("benchmark-median", &[("time", &format_ms(median)), ("count", &sorted.len())])}
+                {time_change(median, previous.map(Benchmark::median_ms))}
+            </span>
+            <span>{i18n.t_args("benchmark-range", &[("min", &format_ms(min)), ("max", &format_ms(max))])}</span>
+        </div>
+    }
+}
@@ -0,0 +1,149 @@
+use crate::components::benchmark::RENDERS;
+use crate::i18n::I18n;
+use common::{BuildOptions, Edition, Profile, Toolchain, YewVersion};
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// Values are `RUSTFLAGS` presets of the compiler, labels message ids.
+const OPTIMIZATIONS: &[(&str, &str)] = &[
+    ("", "optimization-none"),
+    ("codegen-units=1", "optimization-codegen-units"),
+    ("target-feature=+simd128", "optimization-simd"),
+];
+
+fn toolchain_label(toolchain: Toolchain) -> &'static str {
+    match toolchain {
+        Toolchain::Stable => "toolchain-stable",
+        Toolchain::Beta => "toolchain-beta",
+        Toolchain::Nightly => "toolchain-nightly",
+    }
+}
+
+fn profile_label(profile: Profile) -> &'static str {
+    match profile {
+        Profile::Release => "profile-release",
+        Profile::Debug => "profile-debug",
+        Profile::MinSize => "profile-min-size",
+    }
+}
+
+/// Select for one option, its choices as value and label. The first one is the
+/// compiler's default.
+fn option_select<T: Clone + PartialEq + 'static>(
+    label: String,
+    choices: Vec<(T, String)>,
+    value: &T,
+    onchange: Callback<T>,
+) -> Html {
+    let values = choices
+        .iter()
+        .map(|(value, _)| value.clone())
+        .collect::<Vec<_>>();
+    let onchange = move |e: Event| {
+        let select: HtmlSelectElement = e.target_unchecked_into();
+        // the values of the select's options are the indices of the choices
+        let index = select.value().parse::<usize>().ok();
+        if let Some(value) = index.and_then(|it| values.get(it)) {
+            onchange.emit(value.clone());
+        }
+    };
+
+    html! {
+        <label class="flex justify-between items-center">
+            {label}
+            <select {onchange} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                { for choices.into_iter().enumerate().map(|(index, (choice, label))| html! {
+                    <option value={index.to_string()} selected={&choice == value}>{label}</option>
+                }) }
+            </select>
+        </label>
+    }
+}
+
+/// Emits the options with one of them changed by `set`.
+fn update<T: 'static>(
+    props: &BuildOptionsPanelProps,
+    set: fn(&mut BuildOptions, T),
+) -> Callback<T> {
+    let options = props.options.clone();
+    let onchange = props.onchange.clone();
+    Callback::from(move |value| {
+        let mut options = options.clone();
+        set(&mut options, value);
+        onchange.emit(options);
+    })
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BuildOptionsPanelProps {
+    pub options: BuildOptions,
+    pub onchange: Callback<BuildOptions>,
+    /// Yew version the runs are also built with to compare against, if any.
+    #[prop_or_default]
+    pub compare: Option<YewVersion>,
+    #[prop_or_default]
+    pub oncompare: Callback<Option<YewVersion>>,
+    /// Re-renders the runs benchmark the snippet's `App` with, if they do.
+    #[prop_or_default]
+    pub bench: Option<u32>,
+    #[prop_or_default]
+    pub onbench: Callback<Option<u32>>,
+}
+
+/// Drawer with the options the next runs are built with, opened from the header.
+#[function_component]
+pub fn BuildOptionsPanel(props: &BuildOptionsPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let options = &props.options;
+    let versions = YewVersion::ALL
+        .into_iter()
+        .map(|it| (it, it.as_str().to_string()))
+        .collect::<Vec<_>>();
+    let toolchains = Toolchain::ALL
+        .into_iter()
+        .map(|it| (it, i18n.t(toolchain_label(it))))
+        .collect();
+    let editions = Edition::ALL
+        .into_iter()
+        .map(|it| (it, it.to_string()))
+        .collect();
+    let profiles = Profile::ALL
+        .into_iter()
+        .map(|it| (it, i18n.t(profile_label(it))))
+        .collect();
+    let optimizations = OPTIMIZATIONS
+        .iter()
+        .map(|(value, id)| {
+            (
+                Some(value.to_string()).filter(|it| !it.is_empty()),
+                i18n.t(id),
+            )
+        })
+        .collect();
+    let compare_choices = std::iter::once((None, i18n.t("build-options-compare-off")))
+        .chain(
+            versions
+                .iter()
+                .map(|(version, label)| (Some(*version), label.clone())),
+        )
+        .collect();
+    let bench_choices = std::iter::once((None, i18n.t("build-options-benchmark-off")))
+        .chain(RENDERS.iter().map(|renders| {
+            let label = i18n.t_args("build-options-benchmark-renders", &[("count", renders)]);
+            (renders.parse().ok(), label)
+        }))
+        .collect();
+
+    html! {
+        <div class="absolute left-3 right-3 sm:left-auto top-20 z-10 sm:w-72 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+            <h2 class="text-lg">{i18n.t("build-options")}</h2>
+            {option_select(i18n.t("build-options-yew"), versions, &options.yew, update(props, |it, value| it.yew = value))}
+            {option_select(i18n.t("build-options-toolchain"), toolchains, &options.toolchain, update(props, |it, value| it.toolchain = value))}
+            {option_select(i18n.t("build-options-edition"), editions, &options.edition, update(props, |it, value| it.edition = value))}
+            {option_select(i18n.t("build-options-profile"), profiles, &options.profile, update(props, |it, value| it.profile = value))}
+            {option_select(i18n.t("build-options-optimization"), optimizations, &options.rustflags, update(props, |it, value| it.rustflags = value))}
+            {option_select(i18n.t("build-options-compare"), compare_choices, &props.compare, props.oncompare.clone())}
+            {option_select(i18n.t("build-options-benchmark"), bench_choices, &props.bench, props.onbench.clone())}
+        </div>
+    }
+}
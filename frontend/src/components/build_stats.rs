@@ -0,0 +1,59 @@
+use crate::i18n::I18n;
+use common::client::BuildStats;
+use yew::prelude::*;
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.2} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// How much a size changed since the last run, nothing if it didn't.
+fn size_change(size: u64, previous: Option<u64>) -> Html {
+    let previous = match previous {
+        Some(previous) if previous != size => previous,
+        _ => return html! {},
+    };
+    let (sign, change, classes) = if size > previous {
+        ("+", size - previous, "text-red-700 dark:text-red-400")
+    } else {
+        ("−", previous - size, "text-green-700 dark:text-green-400")
+    };
+    html! {
+        <span class={classes}>{format!(" ({}{})", sign, format_size(change))}</span>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BuildStatsBarProps {
+    pub stats: BuildStats,
+    /// Of the last successful run, to compare the sizes with.
+    pub previous: Option<BuildStats>,
+}
+
+/// Status bar under the preview with the sizes of the build and how long it took.
+#[function_component]
+pub fn BuildStatsBar(props: &BuildStatsBarProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let stats = &props.stats;
+    let previous = props.previous.as_ref();
+    let entry = |id: &'static str, size: u64, previous: Option<u64>| {
+        html! {
+            <span>
+                {i18n.t_args(id, &[("size", &format_size(size))])}
+                {size_change(size, previous)}
+            </span>
+        }
+    };
+
+    html! {
+        <div role="status" class="px-3 py-1 flex flex-wrap gap-x-4 text-xs bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300">
+            {entry("build-stats-wasm", stats.wasm_size, previous.map(|it| it.wasm_size))}
+            {entry("build-stats-gzipped", stats.wasm_gzip_size, previous.map(|it| it.wasm_gzip_size))}
+            {entry("build-stats-js", stats.js_size, previous.map(|it| it.js_size))}
+            <span>{i18n.t_args("build-stats-time", &[("seconds", &format!("{:.1}", stats.build_ms as f64 / 1000.0))])}</span>
+        </div>
+    }
+}
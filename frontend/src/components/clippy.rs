@@ -0,0 +1,110 @@
+use crate::i18n::I18n;
+use common::{Diagnostic, Level, Span};
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Groups in the order they're listed in, with the message id of their heading.
+const LEVELS: &[(Level, &str)] = &[
+    (Level::Error, "clippy-errors"),
+    (Level::Warning, "clippy-warnings"),
+    (Level::Note, "clippy-notes"),
+    (Level::Help, "clippy-help"),
+];
+
+#[derive(Clone, PartialEq)]
+pub enum Lints {
+    Running,
+    Done(Rc<Vec<Diagnostic>>),
+}
+
+fn level_classes(level: Level) -> &'static str {
+    match level {
+        Level::Error => "level-error text-red-700 dark:text-red-400",
+        Level::Warning => "level-warning text-yellow-700 dark:text-yellow-400",
+        Level::Note | Level::Help => "text-blue-700 dark:text-blue-400",
+    }
+}
+
+fn view_lint(diagnostic: &Diagnostic, onselect: &Callback<Span>) -> Html {
+    let location = diagnostic
+        .span
+        .map(|span| format!("{}:{}", span.line, span.column));
+    let code = diagnostic
+        .code
+        .as_deref()
+        .map(|code| format!(" [{}]", code));
+    let text = html! {
+        <>
+            if let Some(location) = location {
+                <span class="font-mono text-gray-500 mr-2">{location}</span>
+            }
+            {&diagnostic.message}
+            if let Some(code) = code {
+                <span class="text-gray-500">{code}</span>
+            }
+        </>
+    };
+
+    match diagnostic.span {
+        Some(span) => {
+            let onclick = onselect.reform(move |_| span);
+            html! {
+                <li>
+                    <button {onclick} class="w-full text-left px-3 py-1 hover:bg-gray-200 dark:hover:bg-gray-700">{text}</button>
+                </li>
+            }
+        }
+        None => html! { <li class="px-3 py-1">{text}</li> },
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ClippyPanelProps {
+    pub lints: Lints,
+    /// Called with the location of the lint that was clicked.
+    pub onselect: Callback<Span>,
+    pub onclose: Callback<()>,
+}
+
+/// Lists clippy's lints grouped by level, clicking one jumps to it in the editor.
+#[function_component]
+pub fn ClippyPanel(props: &ClippyPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let body = match &props.lints {
+        Lints::Running => html! { <p class="p-3">{i18n.t("clippy-running")}</p> },
+        Lints::Done(diagnostics) if diagnostics.is_empty() => {
+            html! { <p class="p-3">{i18n.t("clippy-clean")}</p> }
+        }
+        Lints::Done(diagnostics) => html! {
+            { for LEVELS.iter().map(|(level, heading)| {
+                let lints = diagnostics.iter().filter(|it| it.level == *level).collect::<Vec<_>>();
+                if lints.is_empty() {
+                    return html! {};
+                }
+                html! {
+                    <section>
+                        <h3 class={classes!("px-3", "pt-2", "font-bold", level_classes(*level))}>
+                            {i18n.t_args(heading, &[("count", &lints.len())])}
+                        </h3>
+                        <ul>
+                            { for lints.into_iter().map(|it| view_lint(it, &props.onselect)) }
+                        </ul>
+                    </section>
+                }
+            }) }
+        },
+    };
+    let onclose = props.onclose.reform(|_| ());
+
+    html! {
+        <section aria-labelledby="clippy-heading" class="h-32 md:h-48 flex flex-col border-t border-gray-300 dark:border-gray-600 text-sm">
+            <div class="flex gap-1 p-1 bg-gray-100 dark:bg-gray-800">
+                <h2 id="clippy-heading" class="px-2">{i18n.t("clippy")}</h2>
+                <button onclick={onclose} aria-label={i18n.t("clippy-close")} class="ml-auto px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("close")}</button>
+            </div>
+            <div class="flex-grow overflow-auto">
+                {body}
+            </div>
+        </section>
+    }
+}
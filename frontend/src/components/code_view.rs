@@ -0,0 +1,50 @@
+use crate::settings::SettingsContext;
+use crate::theme::Theme;
+use monaco::api::{CodeEditorOptions, TextModel};
+use monaco::sys::editor::ITextModel;
+use monaco::yew::CodeEditor;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct CodeViewProps {
+    pub code: AttrValue,
+    /// Monaco's ID of the language to highlight the code as.
+    pub language: &'static str,
+}
+
+/// Read-only editor for code the playground generated rather than the user wrote.
+#[function_component]
+pub fn CodeView(props: &CodeViewProps) -> Html {
+    let theme = use_context::<Theme>().unwrap();
+    let colorblind = use_context::<SettingsContext>()
+        .unwrap()
+        .colorblind_diagnostics;
+    let options = use_memo(
+        |theme| {
+            let options = CodeEditorOptions::default()
+                .with_theme(theme.clone())
+                .with_scroll_beyond_last_line(false)
+                .with_automatic_layout(true)
+                .to_sys_options();
+            options.set_read_only(Some(true));
+            options
+        },
+        theme.editor_theme(colorblind),
+    );
+    let model = use_memo(
+        |(code, language)| TextModel::create(code, Some(language), None).unwrap(),
+        (props.code.clone(), props.language),
+    );
+    use_effect_with_deps(
+        |model| {
+            let model = model.clone();
+            // models live until they're disposed, not until they're dropped
+            move || AsRef::<ITextModel>::as_ref(&*model).dispose()
+        },
+        model.clone(),
+    );
+
+    html! {
+        <CodeEditor options={(*options).clone()} classes="h-full" model={Some((*model).clone())} />
+    }
+}
@@ -0,0 +1,177 @@
+use crate::i18n::I18n;
+use crate::keymap::Action;
+use std::cmp::Reverse;
+use web_sys::{HtmlElement, HtmlInputElement};
+use yew::prelude::*;
+
+/// How well `query` matches `text`, ignoring case and whitespace in the query. Its
+/// characters have to appear in `text` in order, matches at the start of a word and
+/// runs of consecutive ones score higher. `None` if it doesn't match at all.
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text = text.to_lowercase().chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut from = 0;
+    let mut previous = None;
+    for c in query
+        .to_lowercase()
+        .chars()
+        .filter(|it| !it.is_whitespace())
+    {
+        let at = from + text[from..].iter().position(|it| *it == c)?;
+        score += 1;
+        if at == 0 || !text[at - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if previous.is_some_and(|it| it + 1 == at) {
+            score += 2;
+        }
+        previous = Some(at);
+        from = at + 1;
+    }
+    Some(score)
+}
+
+fn option_id(index: usize) -> String {
+    format!("command-{}", index)
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CommandPaletteProps {
+    /// The ones that can be run right now, in the order they're listed in.
+    pub actions: Vec<Action>,
+    pub onselect: Callback<Action>,
+    pub onclose: Callback<()>,
+}
+
+/// Searchable list of everything there's a button or a shortcut for, run with Enter
+/// or a click.
+#[function_component]
+pub fn CommandPalette(props: &CommandPaletteProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let query = use_state(String::new);
+    let selected = use_state_eq(|| 0);
+
+    let mut matches = props
+        .actions
+        .iter()
+        .filter_map(|action| {
+            let label = i18n.t(action.label());
+            Some((fuzzy_score(&query, &label)?, *action, label))
+        })
+        .collect::<Vec<_>>();
+    // stable, equally good matches stay in the usual order
+    matches.sort_by_key(|(score, ..)| Reverse(*score));
+    let selected_index = (*selected).min(matches.len().saturating_sub(1));
+    let no_match = matches.is_empty();
+
+    let input_ref = use_node_ref();
+    {
+        let input_ref = input_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(input) = input_ref.cast::<HtmlElement>() {
+                    let _ = input.focus();
+                }
+            },
+            (),
+        );
+    }
+    use_effect_with_deps(
+        |selected_index| {
+            let option = gloo::utils::document().get_element_by_id(&option_id(*selected_index));
+            if let Some(option) = option {
+                option.scroll_into_view_with_bool(false);
+            }
+        },
+        selected_index,
+    );
+
+    let oninput = {
+        let query = query.clone();
+        let selected = selected.clone();
+        move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            selected.set(0);
+        }
+    };
+    let onkeydown = {
+        let actions = matches
+            .iter()
+            .map(|(_, action, _)| *action)
+            .collect::<Vec<_>>();
+        let selected = selected.clone();
+        let onselect = props.onselect.clone();
+        let onclose = props.onclose.clone();
+        move |e: KeyboardEvent| match e.key().as_str() {
+            "ArrowDown" if !actions.is_empty() => {
+                e.prevent_default();
+                selected.set((selected_index + 1) % actions.len());
+            }
+            "ArrowUp" if !actions.is_empty() => {
+                e.prevent_default();
+                selected.set((selected_index + actions.len() - 1) % actions.len());
+            }
+            "Enter" => {
+                if let Some(action) = actions.get(selected_index) {
+                    onselect.emit(*action);
+                }
+            }
+            "Escape" => onclose.emit(()),
+            _ => {}
+        }
+    };
+    let onclose = props.onclose.reform(|_: MouseEvent| ());
+
+    html! {
+        <div onclick={onclose} class="fixed inset-0 z-20 bg-black/50 flex justify-center items-start pt-[15vh]">
+            <div onclick={|e: MouseEvent| e.stop_propagation()} role="dialog" aria-modal="true" aria-label={i18n.t("command-palette")} class="w-[32rem] max-w-[90vw] bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col">
+                <input
+                    ref={input_ref}
+                    {oninput}
+                    {onkeydown}
+                    value={(*query).clone()}
+                    placeholder={i18n.t("command-search")}
+                    role="combobox"
+                    aria-expanded="true"
+                    aria-controls="commands"
+                    aria-activedescendant={(!no_match).then(|| option_id(selected_index))}
+                    class="p-3 bg-transparent border-b border-gray-300 dark:border-gray-600 outline-none"
+                />
+                <ul id="commands" role="listbox" aria-label={i18n.t("command-palette")} class="max-h-[50vh] overflow-y-auto py-1">
+                    { for matches.into_iter().enumerate().map(|(index, (_, action, label))| {
+                        let is_selected = index == selected_index;
+                        let onclick = {
+                            let onselect = props.onselect.clone();
+                            move |_| onselect.emit(action)
+                        };
+                        let onmousemove = {
+                            let selected = selected.clone();
+                            move |_| selected.set(index)
+                        };
+                        let classes = classes!(
+                            "px-3",
+                            "py-2",
+                            "flex",
+                            "justify-between",
+                            "gap-3",
+                            "cursor-pointer",
+                            is_selected.then_some("bg-gray-200 dark:bg-gray-700")
+                        );
+                        html! {
+                            <li id={option_id(index)} role="option" aria-selected={is_selected.to_string()} {onclick} {onmousemove} class={classes}>
+                                {label}
+                                if let Some(binding) = action.binding() {
+                                    <kbd class="text-sm text-gray-600 dark:text-gray-400">{binding.label(i18n)}</kbd>
+                                }
+                            </li>
+                        }
+                    }) }
+                </ul>
+                if no_match {
+                    <p class="px-3 py-2 text-gray-600 dark:text-gray-400">{i18n.t("command-no-match")}</p>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,107 @@
+use crate::components::benchmark::{Benchmark, BenchmarkBar};
+use crate::components::compile_error::CompileErrorPanel;
+use crate::components::console::{ConsoleAction, ConsoleLog, ConsolePanel};
+use crate::components::output::{Evaluation, OutputContainer, PreviewMessage};
+use crate::files::RunRequest;
+use crate::i18n::I18n;
+use common::{BuildOptions, Span, YewVersion};
+use yew::prelude::*;
+
+/// Heading of a side of the comparison, naming the Yew version it's built with.
+pub fn version_label(version: YewVersion, i18n: I18n) -> Html {
+    html! {
+        <p class="px-2 py-1 text-sm font-semibold bg-gray-100 dark:bg-gray-800">
+            {i18n.t_args("compare-version", &[("version", &version.as_str())])}
+        </p>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ComparePaneProps {
+    /// The run of the playground, built here against `version` instead.
+    pub request: RunRequest,
+    pub version: YewVersion,
+    /// The snippet's files, references to them in errors become links.
+    pub paths: Vec<String>,
+    /// Called with the file and location of a clicked reference.
+    pub onselect: Callback<(String, Span)>,
+}
+
+/// The second side of a comparison: the same code built against another Yew version,
+/// with a preview, build errors, benchmark timings and console of its own.
+#[function_component]
+pub fn ComparePane(props: &ComparePaneProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let console = use_reducer(ConsoleLog::default);
+    let compile_error = use_state(|| None::<AttrValue>);
+    let benchmark = use_state(|| None::<(Benchmark, Option<Benchmark>)>);
+    let last_benchmark = use_mut_ref(|| None::<Benchmark>);
+    let evaluation = use_state_eq(|| None::<Evaluation>);
+
+    let request = use_memo(
+        |(request, version)| RunRequest {
+            files: request.files.clone(),
+            options: BuildOptions {
+                yew: *version,
+                ..request.options.clone()
+            },
+            bench: request.bench,
+        },
+        (props.request.clone(), props.version),
+    );
+    {
+        let console = console.clone();
+        let compile_error = compile_error.clone();
+        let benchmark = benchmark.clone();
+        use_effect_with_deps(
+            move |_| {
+                console.dispatch(ConsoleAction::Clear);
+                compile_error.set(None);
+                benchmark.set(None);
+            },
+            request.clone(),
+        );
+    }
+
+    let onmessage = {
+        let console = console.clone();
+        let compile_error = compile_error.clone();
+        Callback::from(move |message| {
+            if let PreviewMessage::CompileError { message, .. } = &message {
+                compile_error.set(Some(AttrValue::from(message.clone())));
+            }
+            console.dispatch(ConsoleAction::Push(message))
+        })
+    };
+
+    let onbenchmark = {
+        let benchmark = benchmark.clone();
+        Callback::from(move |it: Benchmark| {
+            let previous = last_benchmark.borrow_mut().replace(it.clone());
+            benchmark.set(Some((it, previous)));
+        })
+    };
+
+    let onevaluate = {
+        let evaluation = evaluation.clone();
+        Callback::from(move |it| evaluation.set(Some(it)))
+    };
+
+    html! {
+        <div class="min-h-0 min-w-0 flex-1 flex flex-col">
+            {version_label(props.version, i18n)}
+            <div class="flex-grow min-h-0 relative">
+                <OutputContainer request={(*request).clone()} {onmessage} {onbenchmark} evaluation={(*evaluation).clone()} />
+                if let Some(message) = &*compile_error {
+                    <div class="absolute inset-0">
+                        <CompileErrorPanel message={message.clone()} paths={props.paths.clone()} onselect={props.onselect.clone()} />
+                    </div>
+                }
+            </div>
+            if let Some((benchmark, previous)) = (*benchmark).clone() {
+                <BenchmarkBar {benchmark} {previous} />
+            }
+            <ConsolePanel log={console} {onevaluate} />
+        </div>
+    }
+}
@@ -0,0 +1,103 @@
+use crate::utils::ansi;
+use common::Span;
+use std::ops::Range;
+use yew::prelude::*;
+
+/// A `src/main.rs:5:18` reference to one of the snippet's files.
+struct Reference {
+    range: Range<usize>,
+    path: String,
+    span: Span,
+}
+
+/// The references in `text` to any of `paths`.
+fn references(text: &str, paths: &[String]) -> Vec<Reference> {
+    let mut found = Vec::new();
+    let mut rest = 0;
+    while let Some(offset) = text[rest..].find("src/") {
+        let start = rest + offset;
+        rest = start + 1;
+        let Some(path) = paths
+            .iter()
+            .find(|it| text[start..].starts_with(&format!("{}:", it)))
+        else {
+            continue;
+        };
+        let after = &text[start + path.len() + 1..];
+        let digits = |text: &str| {
+            text.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len())
+        };
+        let line_len = digits(after);
+        let Some(column_text) = after[line_len..].strip_prefix(':') else {
+            continue;
+        };
+        let column_len = digits(column_text);
+        let (Ok(line), Ok(column)) = (
+            after[..line_len].parse::<u32>(),
+            column_text[..column_len].parse::<u32>(),
+        ) else {
+            continue;
+        };
+        let end = start + path.len() + 1 + line_len + 1 + column_len;
+        found.push(Reference {
+            range: start..end,
+            path: path.clone(),
+            span: Span {
+                line,
+                column,
+                end_line: line,
+                end_column: column,
+            },
+        });
+        rest = end;
+    }
+    found
+}
+
+#[derive(PartialEq, Properties)]
+pub struct CompileErrorPanelProps {
+    /// Cargo's output, colors included.
+    pub message: AttrValue,
+    /// The snippet's files, references to them become links.
+    #[prop_or_default]
+    pub paths: Vec<String>,
+    /// Called with the file and location of a clicked reference.
+    #[prop_or_default]
+    pub onselect: Callback<(String, Span)>,
+}
+
+/// Shows a failed build's diagnostics the way they look in a terminal.
+#[function_component]
+pub fn CompileErrorPanel(props: &CompileErrorPanelProps) -> Html {
+    let spans = use_memo(|message| ansi::parse(message), props.message.clone());
+
+    let linked = |text: &str| {
+        let mut html = Vec::new();
+        let mut last = 0;
+        for Reference { range, path, span } in references(text, &props.paths) {
+            html.push(html! { {&text[last..range.start]} });
+            let onclick = props
+                .onselect
+                .reform(move |_: MouseEvent| (path.clone(), span));
+            html.push(html! {
+                <button {onclick} class="underline hover:text-green-700 dark:hover:text-green-400">{&text[range.clone()]}</button>
+            });
+            last = range.end;
+        }
+        html.push(html! { {&text[last..]} });
+        html.into_iter().collect::<Html>()
+    };
+
+    html! {
+        <pre class="h-full overflow-auto p-3 text-sm bg-gray-50 dark:bg-gray-900">
+            { for spans.iter().map(|(style, text)| {
+                let classes = classes!(
+                    style.color.map(ansi::Color::classes),
+                    style.bold.then_some("font-bold")
+                );
+                html! { <span class={classes}>{linked(text)}</span> }
+            }) }
+        </pre>
+    }
+}
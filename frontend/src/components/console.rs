@@ -0,0 +1,188 @@
+use crate::components::output::{ConsoleLevel, Evaluation, PreviewMessage};
+use crate::i18n::I18n;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// With the message id of their filter's label.
+const LEVELS: &[(ConsoleLevel, &str)] = &[
+    (ConsoleLevel::Error, "console-errors"),
+    (ConsoleLevel::Warn, "console-warnings"),
+    (ConsoleLevel::Info, "console-info"),
+    (ConsoleLevel::Log, "console-logs"),
+    (ConsoleLevel::Debug, "console-debug"),
+];
+
+/// Numbers the evaluations, across panels and their remounts.
+static EVALUATIONS: AtomicU32 = AtomicU32::new(0);
+
+/// Messages the preview of the current run reported.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConsoleLog {
+    pub messages: Vec<PreviewMessage>,
+}
+
+pub enum ConsoleAction {
+    Push(PreviewMessage),
+    Clear,
+}
+
+impl Reducible for ConsoleLog {
+    type Action = ConsoleAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let messages = match action {
+            ConsoleAction::Push(message) => {
+                let mut messages = self.messages.clone();
+                messages.push(message);
+                messages
+            }
+            ConsoleAction::Clear => Vec::new(),
+        };
+        Self { messages }.into()
+    }
+}
+
+impl PreviewMessage {
+    /// Panics are filtered along with errors.
+    fn level(&self) -> ConsoleLevel {
+        match self {
+            Self::Console { level, .. } => *level,
+            Self::Panic { .. } | Self::CompileError { .. } => ConsoleLevel::Error,
+            Self::Result { error: true, .. } => ConsoleLevel::Error,
+            Self::Input { .. } | Self::Result { .. } => ConsoleLevel::Log,
+        }
+    }
+}
+
+fn level_classes(level: ConsoleLevel) -> &'static str {
+    match level {
+        // `level-*` mark them for the colourblind-safe palette
+        ConsoleLevel::Error => {
+            "level-error text-red-700 dark:text-red-400 bg-red-50 dark:bg-red-950"
+        }
+        ConsoleLevel::Warn => {
+            "level-warning text-yellow-700 dark:text-yellow-300 bg-yellow-50 dark:bg-yellow-950"
+        }
+        ConsoleLevel::Debug => "text-gray-500 dark:text-gray-400",
+        ConsoleLevel::Log | ConsoleLevel::Info => "",
+    }
+}
+
+fn view_message(message: &PreviewMessage, i18n: I18n) -> Html {
+    let classes = classes!(
+        "px-3",
+        "py-1",
+        "border-b",
+        "border-gray-200",
+        "dark:border-gray-700",
+        "whitespace-pre-wrap",
+        level_classes(message.level())
+    );
+    match message {
+        PreviewMessage::Console { message, .. } => html! {
+            <div class={classes}>{message}</div>
+        },
+        PreviewMessage::CompileError { .. } => html! {
+            <div class={classes}>{i18n.t("console-compile-error")}</div>
+        },
+        PreviewMessage::Input { code } => html! {
+            <div class={classes}>{format!("› {}", code)}</div>
+        },
+        PreviewMessage::Result { message, .. } => html! {
+            <div class={classes}>{format!("‹ {}", message)}</div>
+        },
+        PreviewMessage::Panic { message, stack } => html! {
+            <details class={classes}>
+                <summary class="cursor-pointer">{i18n.t_args("console-uncaught", &[("message", message)])}</summary>
+                if let Some(stack) = stack {
+                    <pre class="text-xs">{stack}</pre>
+                }
+            </details>
+        },
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ConsolePanelProps {
+    pub log: UseReducerHandle<ConsoleLog>,
+    /// Called with code entered to be evaluated in the preview.
+    pub onevaluate: Callback<Evaluation>,
+}
+
+/// Shows what the preview logged, filterable by level, with an input evaluating
+/// JavaScript in the preview.
+#[function_component]
+pub fn ConsolePanel(props: &ConsolePanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let hidden = use_state(HashSet::<ConsoleLevel>::new);
+
+    let filters = LEVELS.iter().map(|(level, label)| {
+        let level = *level;
+        let shown = !hidden.contains(&level);
+        let onclick = {
+            let hidden = hidden.clone();
+            move |_| {
+                let mut levels = (*hidden).clone();
+                if !levels.remove(&level) {
+                    levels.insert(level);
+                }
+                hidden.set(levels);
+            }
+        };
+        let classes = classes!(
+            "px-2",
+            "rounded",
+            shown.then_some("bg-gray-300 dark:bg-gray-600")
+        );
+        html! {
+            <button {onclick} aria-pressed={shown.to_string()} class={classes}>{i18n.t(label)}</button>
+        }
+    });
+    let input_ref = use_node_ref();
+    let onsubmit = {
+        let log = props.log.clone();
+        let onevaluate = props.onevaluate.clone();
+        let input_ref = input_ref.clone();
+        move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(input) = input_ref.cast::<HtmlInputElement>() else {
+                return;
+            };
+            let code = input.value();
+            if code.trim().is_empty() {
+                return;
+            }
+            input.set_value("");
+            log.dispatch(ConsoleAction::Push(PreviewMessage::Input {
+                code: code.clone(),
+            }));
+            onevaluate.emit(Evaluation {
+                id: EVALUATIONS.fetch_add(1, Ordering::Relaxed),
+                code: AttrValue::from(code),
+            });
+        }
+    };
+    let onclear = {
+        let log = props.log.clone();
+        move |_| log.dispatch(ConsoleAction::Clear)
+    };
+
+    html! {
+        <section aria-label={i18n.t("console")} class="h-32 md:h-48 flex flex-col border-t border-gray-300 dark:border-gray-600 text-sm">
+            <div role="toolbar" aria-label={i18n.t("console-levels")} class="flex gap-1 p-1 bg-gray-100 dark:bg-gray-800">
+                { for filters }
+                <button onclick={onclear} aria-label={i18n.t("console-clear-label")} class="ml-auto px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("console-clear")}</button>
+            </div>
+            <div role="log" class="flex-grow overflow-auto font-mono">
+                { for props.log.messages.iter().filter(|it| !hidden.contains(&it.level())).map(|it| view_message(it, i18n)) }
+            </div>
+            <form {onsubmit} class="flex items-center gap-1 px-3 border-t border-gray-200 dark:border-gray-700 font-mono">
+                <span aria-hidden="true">{"›"}</span>
+                <input ref={input_ref} placeholder={i18n.t("console-input")} aria-label={i18n.t("console-input")} spellcheck="false" autocomplete="off" class="flex-grow py-1 bg-transparent outline-none" />
+            </form>
+        </section>
+    }
+}
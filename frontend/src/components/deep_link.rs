@@ -0,0 +1,36 @@
+use crate::i18n::I18n;
+use crate::utils::deep_link::DeepLink;
+use crate::utils::fragment;
+use gloo::history::{BrowserHistory, History};
+use yew::prelude::*;
+use yew_router::hooks::use_location;
+
+/// `/new`, opens the snippet a [`DeepLink`] describes in the playground. The link is
+/// replaced in the history, going back doesn't open the snippet again.
+#[function_component]
+pub fn NewSnippet() -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let location = use_location();
+
+    use_effect_with_deps(
+        move |_| {
+            // a link with a malformed `autorun` still opens its code
+            let link = location
+                .and_then(|it| it.query::<DeepLink>().ok())
+                .unwrap_or_default();
+            let (query, code) = link.into_query();
+            let query = serde_urlencoded::to_string(query).expect("query serializes");
+            let separator = if query.is_empty() { "" } else { "?" };
+            let fragment = code
+                .filter(|it| !it.trim().is_empty())
+                .map(|it| fragment::encode(&it))
+                .unwrap_or_default();
+            BrowserHistory::new().replace(format!("/{}{}{}", separator, query, fragment));
+        },
+        (),
+    );
+
+    html! {
+        <p class="p-4">{i18n.t("loading")}</p>
+    }
+}
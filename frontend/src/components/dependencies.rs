@@ -0,0 +1,244 @@
+use crate::api;
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::dependencies::{self, Dependency};
+use crate::i18n::I18n;
+use common::{CrateInfo, CrateSummary};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// A crate being added, with the release and the features picked for it so far.
+#[derive(Clone, PartialEq)]
+struct Pick {
+    info: Rc<CrateInfo>,
+    version: usize,
+    features: BTreeSet<String>,
+}
+
+#[derive(PartialEq, Properties)]
+pub struct DependenciesPanelProps {
+    /// The `[dependencies]` entries of the build options.
+    pub dependencies: Option<String>,
+    pub onchange: Callback<Option<String>>,
+}
+
+/// Drawer for adding crates to the snippet: search the allowed ones, pick a release
+/// and features, and see the `Cargo.toml` entries the runs are built with.
+#[function_component]
+pub fn DependenciesPanel(props: &DependenciesPanelProps) -> Html {
+    let toasts = use_context::<ToastContext>().unwrap();
+    let i18n = use_context::<I18n>().unwrap();
+    let current = dependencies::parse(props.dependencies.as_deref().unwrap_or_default());
+    let query = use_state(String::new);
+    let results = use_state(|| None::<Vec<CrateSummary>>);
+    {
+        let results = results.clone();
+        use_effect_with_deps(
+            move |query| {
+                let query = query.clone();
+                // a slower earlier search must not overwrite the results of a later one
+                let latest = Rc::new(Cell::new(true));
+                {
+                    let latest = latest.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match api::client().crates(&query).await {
+                            Ok(list) if latest.get() => results.set(Some(list)),
+                            Ok(_) => {}
+                            Err(e) => tracing::warn!(?e, "failed to search crates"),
+                        }
+                    });
+                }
+                move || latest.set(false)
+            },
+            (*query).clone(),
+        );
+    }
+    let oninput = {
+        let query = query.clone();
+        move |e: InputEvent| query.set(e.target_unchecked_into::<HtmlInputElement>().value())
+    };
+
+    let pick = use_state(|| None::<Pick>);
+    let on_result_click = |name: String| {
+        let pick = pick.clone();
+        let toasts = toasts.clone();
+        // picking a crate that's already there starts from its features
+        let features: BTreeSet<String> = current
+            .iter()
+            .find(|it| it.name == name)
+            .map(|it| it.features.iter().cloned().collect())
+            .unwrap_or_default();
+        move |_| {
+            let (name, pick, toasts) = (name.clone(), pick.clone(), toasts.clone());
+            let features = features.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match api::client().crate_info(&name).await {
+                    Ok(info) if info.versions.is_empty() => toast::show(
+                        &toasts,
+                        ToastKind::Error,
+                        i18n.t_args("dependencies-no-releases", &[("name", &name)]),
+                    ),
+                    Ok(info) => pick.set(Some(Pick {
+                        info: Rc::new(info),
+                        version: 0,
+                        features,
+                    })),
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to look up crate");
+                        toast::show(
+                            &toasts,
+                            ToastKind::Error,
+                            i18n.t_args("dependencies-lookup-failed", &[("name", &name)]),
+                        );
+                    }
+                }
+            });
+        }
+    };
+
+    let update = {
+        let onchange = props.onchange.clone();
+        move |list: Vec<Dependency>| onchange.emit(dependencies::to_toml(&list))
+    };
+    let picker = pick.as_ref().map(|it| {
+        let version = &it.info.versions[it.version];
+        let on_version_change = {
+            let pick = pick.clone();
+            let it = it.clone();
+            move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                let version = select.selected_index().max(0) as usize;
+                // features the other release doesn't have go
+                let available = &it.info.versions[version].features;
+                let features = it
+                    .features
+                    .iter()
+                    .filter(|feature| available.contains(feature))
+                    .cloned()
+                    .collect();
+                pick.set(Some(Pick {
+                    version,
+                    features,
+                    ..it.clone()
+                }));
+            }
+        };
+        let feature = |name: &String| {
+            let checked = it.features.contains(name);
+            let onchange = {
+                let pick = pick.clone();
+                let (it, name) = (it.clone(), name.clone());
+                move |_| {
+                    let mut it = it.clone();
+                    if !it.features.remove(&name) {
+                        it.features.insert(name.clone());
+                    }
+                    pick.set(Some(it));
+                }
+            };
+            html! {
+                <label class="flex gap-2 items-center">
+                    <input type="checkbox" {checked} {onchange} />
+                    {name}
+                </label>
+            }
+        };
+        let on_add_click = {
+            let pick = pick.clone();
+            let (it, current) = (it.clone(), current.clone());
+            let update = update.clone();
+            move |_| {
+                let added = Dependency {
+                    name: it.info.name.clone(),
+                    version: it.info.versions[it.version].num.clone(),
+                    features: it.features.iter().cloned().collect(),
+                };
+                let mut list = current.clone();
+                match list.iter_mut().find(|it| it.name == added.name) {
+                    Some(existing) => *existing = added,
+                    None => list.push(added),
+                }
+                update(list);
+                pick.set(None);
+            }
+        };
+        let on_cancel_click = {
+            let pick = pick.clone();
+            move |_| pick.set(None)
+        };
+
+        html! {
+            <div class="flex flex-col gap-2 p-2 rounded bg-gray-200 dark:bg-gray-700">
+                <label class="flex justify-between items-center">
+                    {&it.info.name}
+                    <select onchange={on_version_change} aria-label={i18n.t("dependencies-version")} class="bg-gray-100 dark:bg-gray-800 rounded p-1">
+                        { for it.info.versions.iter().enumerate().map(|(i, version)| html! {
+                            <option value={version.num.clone()} selected={i == it.version}>{&version.num}</option>
+                        }) }
+                    </select>
+                </label>
+                if !version.features.is_empty() {
+                    <fieldset class="flex flex-col max-h-32 overflow-auto">
+                        <legend class="text-sm text-gray-600 dark:text-gray-400">{i18n.t("dependencies-features")}</legend>
+                        { for version.features.iter().map(feature) }
+                    </fieldset>
+                }
+                <div class="flex gap-2 justify-end">
+                    <button onclick={on_cancel_click} class="px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("cancel")}</button>
+                    <button onclick={on_add_click} class="px-2 rounded bg-gray-300 dark:bg-gray-600 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("dependencies-add")}</button>
+                </div>
+            </div>
+        }
+    });
+
+    let results = match &*results {
+        None => html! { <p>{i18n.t("loading")}</p> },
+        Some(list) if list.is_empty() => html! { <p>{i18n.t("dependencies-no-match")}</p> },
+        Some(list) => html! {
+            <ul class="max-h-40 overflow-auto">
+                { for list.iter().map(|it| html! {
+                    <li key={it.name.clone()}>
+                        <button onclick={on_result_click(it.name.clone())} class="w-full text-left px-2 py-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700">
+                            <span class="font-mono">{&it.name}</span>
+                            <span class="ml-2 text-sm text-gray-600 dark:text-gray-400">{&it.description}</span>
+                        </button>
+                    </li>
+                }) }
+            </ul>
+        },
+    };
+    let added = current.iter().map(|dependency| {
+        let onclick = {
+            let update = update.clone();
+            let current = current.clone();
+            let name = dependency.name.clone();
+            move |_| update(current.iter().filter(|it| it.name != name).cloned().collect())
+        };
+        html! {
+            <li key={dependency.name.clone()} class="flex items-center gap-2">
+                <span class="font-mono">{&dependency.name}</span>
+                <span class="text-sm text-gray-600 dark:text-gray-400">{&dependency.version}</span>
+                <button {onclick} aria-label={i18n.t_args("dependencies-remove", &[("name", &dependency.name)])} class="ml-auto px-1 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{"×"}</button>
+            </li>
+        }
+    });
+
+    html! {
+        <div class="absolute left-3 right-3 sm:left-auto top-20 z-10 sm:w-96 max-h-[80vh] overflow-auto p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+            <h2 class="text-lg">{i18n.t("dependencies")}</h2>
+            <input type="search" value={(*query).clone()} {oninput} placeholder={i18n.t("dependencies-search")} aria-label={i18n.t("dependencies-search")} class="bg-gray-200 dark:bg-gray-700 rounded p-1" />
+            {results}
+            {picker.unwrap_or_default()}
+            if current.is_empty() {
+                <p class="text-sm text-gray-600 dark:text-gray-400">{i18n.t("dependencies-empty")}</p>
+            } else {
+                <ul class="flex flex-col gap-1">{ for added }</ul>
+                <pre class="p-2 text-xs rounded bg-white dark:bg-gray-900 overflow-auto">
+                    {"[dependencies]\n"}{props.dependencies.as_deref().unwrap_or_default()}
+                </pre>
+            }
+        </div>
+    }
+}
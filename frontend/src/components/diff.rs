@@ -0,0 +1,81 @@
+use crate::i18n::I18n;
+use js_sys::{Object, Reflect};
+use monaco::api::TextModel;
+use monaco::sys::editor::{IDiffEditorConstructionOptions, IDiffEditorModel, ITextModel};
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+fn dispose(model: &TextModel) {
+    AsRef::<ITextModel>::as_ref(model).dispose();
+}
+
+#[derive(PartialEq, Properties)]
+pub struct DiffViewProps {
+    /// The code as it was shared.
+    pub original: Rc<str>,
+    pub modified: AttrValue,
+    pub onclose: Callback<()>,
+}
+
+/// Read-only side by side diff of the shared code and what it was edited into.
+#[function_component]
+pub fn DiffView(props: &DiffViewProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let container = use_node_ref();
+    let modified_model = use_mut_ref(|| None::<TextModel>);
+    {
+        let container = container.clone();
+        let modified_model = modified_model.clone();
+        let modified = props.modified.clone();
+        use_effect_with_deps(
+            move |original| {
+                let element = container.cast::<HtmlElement>().unwrap();
+                let original = TextModel::create(original, Some("rust"), None).unwrap();
+                let modified = TextModel::create(&modified, Some("rust"), None).unwrap();
+
+                let options: IDiffEditorConstructionOptions = Object::new().unchecked_into();
+                options.set_read_only(Some(true));
+                options.set_scroll_beyond_last_line(Some(false));
+                // only part of the editor construction options, which these don't extend
+                Reflect::set(&options, &"automaticLayout".into(), &true.into()).unwrap();
+                let editor =
+                    monaco::sys::editor::create_diff_editor(&element, Some(&options), None);
+                let model: IDiffEditorModel = Object::new().unchecked_into();
+                model.set_original(original.as_ref());
+                model.set_modified(modified.as_ref());
+                editor.set_model(Some(&model));
+                *modified_model.borrow_mut() = Some(modified);
+
+                move || {
+                    editor.dispose();
+                    dispose(&original);
+                    if let Some(modified) = modified_model.borrow_mut().take() {
+                        dispose(&modified);
+                    }
+                }
+            },
+            props.original.clone(),
+        );
+    }
+    use_effect_with_deps(
+        move |modified| {
+            if let Some(model) = &*modified_model.borrow() {
+                model.set_value(modified);
+            }
+        },
+        props.modified.clone(),
+    );
+    let onclose = props.onclose.reform(|_| ());
+
+    html! {
+        <div class="h-full flex flex-col bg-white dark:bg-gray-900">
+            <div class="flex gap-1 p-1 text-sm bg-gray-100 dark:bg-gray-800">
+                <h2 class="px-2">{i18n.t("diff")}</h2>
+                <button onclick={onclose} class="ml-auto px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("close")}</button>
+            </div>
+            <div ref={container} class="flex-grow min-h-0" />
+        </div>
+    }
+}
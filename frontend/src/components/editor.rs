@@ -1,16 +1,44 @@
+use crate::api;
+use crate::api::share::PasteDetails;
+use crate::collab::{Collab, CollabState};
+use crate::files::{self, FileContents, MAIN};
+use crate::i18n::I18n;
+use crate::keybindings::use_keybindings;
 use crate::rc_type;
+use crate::settings::{KeybindingMode, Settings, SettingsContext, WhitespaceMode};
+use crate::theme::Theme;
+use crate::utils::fragment::{self, use_fragment};
+use crate::utils::media::use_media_query;
 use crate::utils::query::use_query;
 use anyhow::Result;
+use common::{CompletionKind, Diagnostic, Level, Span};
 use gloo::console::log;
-use monaco::api::TextModel;
-use monaco::yew::CodeEditor;
-use monaco::{api::CodeEditorOptions, sys::editor::BuiltinTheme};
+use js_sys::{Array, Object, Promise, Reflect};
+use monaco::api::{CodeEditorOptions, DisposableClosure, TextModel};
+use monaco::sys::editor::{
+    EditorAutoClosingStrategy, ICodeEditor, ICursorSelectionChangedEvent, IEditor,
+    IEditorFindOptions, IEditorMinimapOptions, IEditorOptions, IEditorOptionsAutoIndent,
+    IEditorOptionsMatchBrackets, IEditorOptionsRenderWhitespace, IEditorOptionsWordWrap,
+    IGlobalEditorOptions, IIdentifiedSingleEditOperation, IMarkerData, IModelContentChangedEvent,
+    IModelDecorationOptions, IModelDeltaDecoration, IStandaloneCodeEditor,
+    IStandaloneEditorConstructionOptions, ITextModel, ITextModelUpdateOptions,
+};
+use monaco::sys::languages::{
+    register_completion_item_provider, CompletionItem, CompletionItemKind, CompletionItemProvider,
+    CompletionList,
+};
+use monaco::sys::{IDisposable, IPosition, MarkerSeverity, Position, Range, Uri};
+use monaco::yew::{CodeEditor, CodeEditorLink};
+use std::collections::HashMap;
 use std::rc::Rc;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::future_to_promise;
 use yew::prelude::*;
 use yew::suspense::use_future_with_deps;
 use yew::HtmlResult;
 
-const BASE_CONTENT: &str = r#"
+pub const BASE_CONTENT: &str = r#"
 use yew::prelude::*;
 
 #[function_component]
@@ -23,52 +51,543 @@ fn main() {
 }
 "#;
 
+/// Where the cursor is in the active file, 1-based like the editor shows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub line: u32,
+    pub column: u32,
+    /// Characters selected, 0 without a selection.
+    pub selected: u32,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            line: 1,
+            column: 1,
+            selected: 0,
+        }
+    }
+}
+
 rc_type!(TextContent => Option<Result<String>>);
 
 impl TextContent {
     fn new(val: Option<Result<String>>) -> Self {
         Self(Rc::new(val))
     }
-    fn new_with_string(text: String) -> Self {
-        Self::new(Some(Ok(text)))
-    }
 }
 
-fn get_options() -> CodeEditorOptions {
-    CodeEditorOptions::default()
-        .with_builtin_theme(BuiltinTheme::VsDark)
+/// `touch` trims the editor down for phones and tablets: no minimap eating the narrow
+/// screen, wrapped lines instead of sideways scrolling, and no auto-closed brackets or
+/// quotes, on-screen keyboards insert those themselves and autocorrect trips over them.
+fn get_options(theme: &str, touch: bool) -> IStandaloneEditorConstructionOptions {
+    let options = CodeEditorOptions::default()
+        .with_theme(theme.to_string())
         .with_scroll_beyond_last_line(false)
         .with_automatic_layout(true)
+        .to_sys_options();
+    options.set_match_brackets(Some(IEditorOptionsMatchBrackets::Always));
+    options.set_auto_indent(Some(IEditorOptionsAutoIndent::Full));
+    // room for the diagnostics' gutter marks
+    options.set_glyph_margin(Some(true));
+    // find and replace are Monaco's own, Ctrl+F and Ctrl+H (Cmd+Alt+F on macOS)
+    let find: IEditorFindOptions = Object::new().unchecked_into();
+    find.set_seed_search_string_from_selection(Some(true));
+    find.set_add_extra_space_on_top(Some(false));
+    options.set_find(Some(&find));
+    if touch {
+        let minimap: IEditorMinimapOptions = Object::new().unchecked_into();
+        minimap.set_enabled(Some(false));
+        options.set_minimap(Some(&minimap));
+        options.set_word_wrap(Some(IEditorOptionsWordWrap::On));
+        options.set_line_numbers_min_chars(Some(3.0));
+        options.set_folding(Some(false));
+        options.set_auto_closing_brackets(Some(EditorAutoClosingStrategy::Never));
+        options.set_auto_closing_quotes(Some(EditorAutoClosingStrategy::Never));
+    } else {
+        options.set_auto_closing_brackets(Some(EditorAutoClosingStrategy::LanguageDefined));
+    }
+    // the tab width comes from the settings, don't let the snippet's contents override it
+    AsRef::<IGlobalEditorOptions>::as_ref(&options).set_detect_indentation(Some(false));
+    options
+}
+
+/// Monaco's own fonts for the platforms it knows, in one list.
+const DEFAULT_FONT_FAMILY: &str =
+    "Menlo, Monaco, Consolas, 'Droid Sans Mono', 'Courier New', monospace";
+/// Smallest font size of presentations, readable from the back of a room.
+const PRESENTATION_FONT_SIZE: u32 = 24;
+
+/// The appearance the user picked in the settings, on top of what [`get_options`] set.
+/// Touch screens keep their minimap off and their lines wrapped, presentations too and
+/// they get large type.
+fn appearance_options(settings: &Settings, touch: bool, presenting: bool) -> IEditorOptions {
+    let options: IEditorOptions = Object::new().unchecked_into();
+    let font_size = if presenting {
+        settings.font_size.max(PRESENTATION_FONT_SIZE)
+    } else {
+        settings.font_size
+    };
+    options.set_font_size(Some(font_size as f64));
+    // an empty family would leave the last one picked
+    let family = settings.font_family.as_str();
+    options.set_font_family(Some(if family.is_empty() {
+        DEFAULT_FONT_FAMILY
+    } else {
+        family
+    }));
+    let wrap = if settings.word_wrap || touch || presenting {
+        IEditorOptionsWordWrap::On
+    } else {
+        IEditorOptionsWordWrap::Off
+    };
+    options.set_word_wrap(Some(wrap));
+    let whitespace = match settings.whitespace {
+        WhitespaceMode::None => IEditorOptionsRenderWhitespace::None,
+        WhitespaceMode::Selection => IEditorOptionsRenderWhitespace::Selection,
+        WhitespaceMode::Boundary => IEditorOptionsRenderWhitespace::Boundary,
+        WhitespaceMode::All => IEditorOptionsRenderWhitespace::All,
+    };
+    options.set_render_whitespace(Some(whitespace));
+    let minimap: IEditorMinimapOptions = Object::new().unchecked_into();
+    minimap.set_enabled(Some(settings.minimap && !touch && !presenting));
+    options.set_minimap(Some(&minimap));
+    options
+}
+
+fn completion_item_kind(kind: CompletionKind) -> CompletionItemKind {
+    match kind {
+        CompletionKind::Function => CompletionItemKind::Function,
+        CompletionKind::Field => CompletionItemKind::Field,
+        CompletionKind::Variable => CompletionItemKind::Variable,
+        CompletionKind::Struct => CompletionItemKind::Struct,
+        CompletionKind::Enum => CompletionItemKind::Enum,
+        CompletionKind::EnumMember => CompletionItemKind::Enummember,
+        CompletionKind::Trait => CompletionItemKind::Interface,
+        CompletionKind::Module => CompletionItemKind::Module,
+        CompletionKind::Keyword => CompletionItemKind::Keyword,
+        CompletionKind::Snippet => CompletionItemKind::Snippet,
+        CompletionKind::TypeParameter => CompletionItemKind::Typeparameter,
+        CompletionKind::Other => CompletionItemKind::Text,
+    }
+}
+
+/// Fetches rust-analyzer's completions for `position` and converts them into
+/// suggestions replacing the word before the cursor.
+async fn provide_completions(model: ITextModel, position: Position) -> CompletionList {
+    let list: CompletionList = Object::new().unchecked_into();
+    // rust-analyzer only sees `main.rs`, module files are created with `file` URIs
+    if model.uri().scheme() == "file" {
+        list.set_suggestions(&Array::new());
+        return list;
+    }
+    let (line, column) = (position.line_number(), position.column());
+    let word = model.get_word_until_position(position.unchecked_ref::<IPosition>());
+    let range: JsValue = Range::new(line, word.start_column(), line, column).into();
+
+    let code = model.get_value(None, None);
+    let items = match api::client()
+        .complete(&code, line as u32, column as u32)
+        .await
+    {
+        Ok(resp) => resp.items,
+        Err(e) => {
+            tracing::warn!(?e, "failed to fetch completions");
+            Vec::new()
+        }
+    };
+    let suggestions = items
+        .into_iter()
+        .map(|item| {
+            let suggestion: CompletionItem = Object::new().unchecked_into();
+            suggestion.set_label(&item.label.into());
+            suggestion.set_kind(completion_item_kind(item.kind));
+            suggestion.set_detail(item.detail.as_deref());
+            suggestion.set_insert_text(&item.insert_text);
+            suggestion.set_range(&range);
+            suggestion
+        })
+        .collect::<Array>();
+    list.set_suggestions(&suggestions);
+    list
+}
+
+/// Registers rust-analyzer as the completion source for Rust. The returned closure
+/// must be kept alive for as long as the provider is registered.
+fn register_completions() -> (
+    IDisposable,
+    Closure<dyn Fn(ITextModel, Position) -> Promise>,
+) {
+    let provide = Closure::<dyn Fn(ITextModel, Position) -> Promise>::new(
+        |model: ITextModel, position: Position| {
+            future_to_promise(async move { Ok(provide_completions(model, position).await.into()) })
+        },
+    );
+    let provider: CompletionItemProvider = Object::new().unchecked_into();
+    Reflect::set(
+        &provider,
+        &"provideCompletionItems".into(),
+        provide.as_ref(),
+    )
+    .expect("provider is a plain object");
+    let triggers = Array::of2(&".".into(), &":".into());
+    provider.set_trigger_characters(Some(&triggers));
+
+    (
+        register_completion_item_provider("rust", &provider),
+        provide,
+    )
+}
+
+/// Code to replace the contents of a file with, e.g. after formatting. Compared by
+/// identity, so replacing the contents with the same code twice still takes effect.
+#[derive(Clone)]
+pub struct Replacement {
+    pub path: String,
+    pub code: Rc<str>,
+}
+
+impl PartialEq for Replacement {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.code, &other.code)
+    }
+}
+
+/// A place to move the cursor to and scroll into view. Compared by identity, so
+/// jumping to the same place twice still takes effect.
+#[derive(Clone)]
+pub struct Reveal {
+    pub path: String,
+    pub span: Rc<Span>,
+}
+
+impl PartialEq for Reveal {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.span, &other.span)
+    }
+}
+
+/// Diagnostics to underline in a file, replacing the earlier ones from the same
+/// source.
+#[derive(Clone, PartialEq)]
+pub struct Markers {
+    pub path: String,
+    pub source: &'static str,
+    pub diagnostics: Rc<Vec<Diagnostic>>,
+}
+
+fn marker_severity(level: Level) -> MarkerSeverity {
+    match level {
+        Level::Error => MarkerSeverity::Error,
+        Level::Warning => MarkerSeverity::Warning,
+        Level::Note => MarkerSeverity::Info,
+        Level::Help => MarkerSeverity::Hint,
+    }
+}
+
+/// Class of the gutter mark, styled in `globals.scss`.
+fn glyph_class(level: Level) -> &'static str {
+    match level {
+        Level::Error => "diagnostic-glyph error",
+        Level::Warning => "diagnostic-glyph warning",
+        Level::Note | Level::Help => "diagnostic-glyph info",
+    }
+}
+
+/// Underlines the diagnostics, with their messages on hover, and marks the lines they
+/// start on in the gutter. The marks replace `decorations`, the ones returned by the
+/// last call for this source.
+fn set_markers(
+    model: &ITextModel,
+    source: &str,
+    diagnostics: &[Diagnostic],
+    decorations: &Array,
+) -> Array {
+    if model.is_disposed() {
+        return Array::new();
+    }
+    let spanned = || {
+        diagnostics
+            .iter()
+            .filter_map(|diagnostic| Some((diagnostic, diagnostic.span?)))
+    };
+    let markers = spanned()
+        .map(|(diagnostic, span)| {
+            let marker: IMarkerData = Object::new().unchecked_into();
+            marker.set_severity(marker_severity(diagnostic.level));
+            marker.set_message(&diagnostic.message);
+            marker.set_source(Some(source));
+            marker.set_start_line_number(span.line as f64);
+            marker.set_start_column(span.column as f64);
+            marker.set_end_line_number(span.end_line as f64);
+            marker.set_end_column(span.end_column as f64);
+            JsValue::from(marker)
+        })
+        .collect::<Array>();
+    monaco::sys::editor::set_model_markers(model, source, &markers);
+    let glyphs = spanned()
+        .map(|(diagnostic, span)| {
+            let options: IModelDecorationOptions = Object::new().unchecked_into();
+            options.set_glyph_margin_class_name(Some(glyph_class(diagnostic.level)));
+            let range = Range::new(span.line as f64, 1.0, span.line as f64, 1.0);
+            let glyph: IModelDeltaDecoration = Object::new().unchecked_into();
+            glyph.set_range(range.unchecked_ref());
+            glyph.set_options(&options);
+            JsValue::from(glyph)
+        })
+        .collect::<Array>();
+    model.delta_decorations(decorations, &glyphs, None)
+}
+
+/// Where the cursor at the UTF-16 `offset` into `old` belongs in `new`, when `new` is
+/// `old` reformatted: after as many non-whitespace characters as it was before.
+fn carry_offset(old: &str, offset: usize, new: &str) -> usize {
+    let mut units = 0;
+    let mut left = old
+        .chars()
+        .take_while(|c| {
+            units += c.len_utf16();
+            units <= offset
+        })
+        .filter(|c| !c.is_whitespace())
+        .count();
+    let mut units = 0;
+    for c in new.chars() {
+        if left == 0 {
+            break;
+        }
+        units += c.len_utf16();
+        if !c.is_whitespace() {
+            left -= 1;
+        }
+    }
+    units
+}
+
+/// Replaces the contents of the model as a single undoable edit. When the model is
+/// open in `editor`, the cursor stays next to the same code.
+fn replace_contents(model: &TextModel, code: &str, editor: Option<&ICodeEditor>) {
+    let raw: &ITextModel = model.as_ref();
+    let old = model.get_value();
+    let cursor = editor
+        .filter(|editor| {
+            editor
+                .get_model()
+                .is_some_and(|it| JsValue::from(it) == *AsRef::<JsValue>::as_ref(raw))
+        })
+        .and_then(|editor| {
+            let position = editor.get_position()?;
+            Some((editor, raw.get_offset_at(position.unchecked_ref()) as usize))
+        });
+
+    let edit: IIdentifiedSingleEditOperation = Object::new().unchecked_into();
+    edit.set_range(&raw.get_full_model_range());
+    edit.set_text(Some(code));
+    raw.push_edit_operations(&Array::new(), &Array::of1(&edit), None);
+
+    if let Some((editor, offset)) = cursor {
+        let position = raw.get_position_at(carry_offset(&old, offset, code) as f64);
+        editor.set_position(position.unchecked_ref());
+        editor.reveal_position_in_center_if_outside_viewport(position.unchecked_ref(), None);
+    }
+}
+
+/// The model of a module file or a file of the preview's page, along with the listener
+/// reporting its changes.
+struct ModuleModel {
+    model: TextModel,
+    _listener: DisposableClosure<dyn FnMut(IModelContentChangedEvent)>,
+}
+
+impl ModuleModel {
+    fn new(path: &str, code: &str, oninput: Callback<(String, String)>) -> Self {
+        let uri = Uri::file(&format!("/{}", path));
+        let model = TextModel::get_or_create(&uri, code, Some(files::language(path))).unwrap();
+        let listener = {
+            let (model, path) = (model.clone(), path.to_string());
+            model
+                .clone()
+                .on_did_change_content(move |_| oninput.emit((path.clone(), model.get_value())))
+        };
+        Self {
+            model,
+            _listener: listener,
+        }
+    }
+}
+
+impl Drop for ModuleModel {
+    fn drop(&mut self) {
+        // models live until they're disposed, which would leave their URI taken
+        AsRef::<ITextModel>::as_ref(&self.model).dispose();
+    }
 }
 
 #[derive(PartialEq, Properties)]
 pub struct EditorProps {
-    pub oninput: Callback<String>,
+    /// Paths of all files, `main.rs` is loaded from the link that opened the page.
+    pub paths: Vec<String>,
+    pub active: String,
+    /// Contents new module files are created with.
+    pub contents: FileContents,
+    /// Called with the path and the new contents of a file whenever it changes.
+    pub oninput: Callback<(String, String)>,
+    /// Applied together, at most one per file.
+    #[prop_or_default]
+    pub replacements: Vec<Replacement>,
+    /// At most one set per file and source.
+    #[prop_or_default]
+    pub markers: Vec<Markers>,
+    /// Only takes effect if its file is the active one.
+    #[prop_or_default]
+    pub reveal: Option<Reveal>,
+    /// Called with the code the link opened, if it opened any.
+    #[prop_or_default]
+    pub onopened: Callback<Rc<str>>,
+    /// Called with the paste the link opened, without its content.
+    #[prop_or_default]
+    pub onpaste: Callback<PasteDetails>,
+    /// Called as the cursor moves or the selection changes.
+    #[prop_or_default]
+    pub oncursor: Callback<Cursor>,
+    #[prop_or_default]
+    pub read_only: bool,
+    /// For presentations, see [`appearance_options`].
+    #[prop_or_default]
+    pub presenting: bool,
+    /// Room `main.rs` is edited in together with others, see [`Collab`].
+    #[prop_or_default]
+    pub room: Option<AttrValue>,
+    /// Called as the connection to the room changes and others join or leave it.
+    #[prop_or_default]
+    pub oncollab: Callback<CollabState>,
 }
 
 #[function_component]
 pub fn Editor(props: &EditorProps) -> HtmlResult {
     let query = use_query().unwrap();
+    let settings = use_context::<SettingsContext>().unwrap();
+    let theme = use_context::<Theme>().unwrap();
+    let i18n = use_context::<I18n>().unwrap();
+    let touch = use_media_query("(pointer: coarse)", false);
+    // changing the options re-creates the editor, so only create them once
+    let read_only = props.read_only;
+    let editor_theme = theme.editor_theme(settings.colorblind_diagnostics);
+    let options = use_memo(
+        |_| {
+            let options = get_options(&editor_theme, touch);
+            options.set_read_only(Some(read_only));
+            options
+        },
+        (),
+    );
+    // the raw editor, its bindings can't be cloned
+    let editor = use_state(|| None::<JsValue>);
+    let on_editor_created = {
+        let editor = editor.clone();
+        Callback::from(move |link: CodeEditorLink| {
+            editor.set(link.with_editor(|it| {
+                let editor: &IStandaloneCodeEditor = it.as_ref();
+                AsRef::<JsValue>::as_ref(editor).clone()
+            }));
+        })
+    };
+    {
+        let oncursor = props.oncursor.clone();
+        use_effect_with_deps(
+            move |editor| {
+                let registration = editor.as_ref().map(|editor| {
+                    let listener = {
+                        let editor: ICodeEditor = editor.clone().unchecked_into();
+                        Closure::<dyn Fn(ICursorSelectionChangedEvent)>::new(
+                            move |e: ICursorSelectionChangedEvent| {
+                                let selection = e.selection();
+                                let selected = editor.get_model().map_or(0.0, |model| {
+                                    model.get_value_length_in_range(selection.unchecked_ref())
+                                });
+                                oncursor.emit(Cursor {
+                                    line: selection.position_line_number() as u32,
+                                    column: selection.position_column() as u32,
+                                    selected: selected as u32,
+                                });
+                            },
+                        )
+                    };
+                    let disposable = editor
+                        .unchecked_ref::<ICodeEditor>()
+                        .on_did_change_cursor_selection(listener.as_ref().unchecked_ref());
+                    (disposable, listener)
+                });
+                move || {
+                    if let Some((disposable, listener)) = registration {
+                        disposable.dispose();
+                        drop(listener);
+                    }
+                }
+            },
+            (*editor).clone(),
+        );
+    }
 
+    let statusbar = use_node_ref();
+    use_keybindings(
+        editor.as_ref().map(|it| it.unchecked_ref()),
+        settings.keybindings,
+        statusbar.clone(),
+    );
+    // the theme is global to all editors and can be switched in place
+    use_effect_with_deps(|theme| monaco::sys::editor::set_theme(theme), editor_theme);
+
+    let fragment = use_fragment();
+    let onpaste = props.onpaste.clone();
     let text_content = use_future_with_deps(
-        |query| async move {
-            if let Some(code) = &query.code {
-                return TextContent::new_with_string(code.to_string());
+        |deps| async move {
+            let (query, fragment) = &*deps;
+            if let Some(code) = fragment::decode(fragment) {
+                return TextContent::new(Some(code));
+            }
+            if let Some(code) = query.decoded_code() {
+                return TextContent::new(Some(code));
+            }
+            if let Some(id) = &query.gist {
+                return TextContent::new(Some(crate::api::gist::get(id).await));
+            }
+            if let Some(id) = &query.example {
+                return TextContent::new(Some(api::client().example(id).await));
             }
 
             let shared = match &query.shared {
-                Some(text) => Some(
-                    crate::api::share::get(text)
-                        .await
-                        .map_err(anyhow::Error::from)
-                        .map(|paste| paste.fields.into_content()),
-                ),
+                Some(text) => Some(crate::api::share::get(text).await.map(|paste| {
+                    onpaste.emit(PasteDetails::from(&paste.fields));
+                    paste.fields.into_content()
+                })),
                 None => None,
             };
+            // the paste's title and description go for its revisions as well
+            let shared = match (shared, &query.shared, &query.revision) {
+                (Some(Ok(_)), Some(paste), Some(revision)) => {
+                    let revision = api::client().revision(paste, revision).await;
+                    Some(revision.map(|it| it.content))
+                }
+                (shared, ..) => shared,
+            };
             TextContent::new(shared)
         },
-        query,
+        (query, fragment),
     )?;
+    {
+        // ahead of the effect reporting the contents, so they're compared to this code
+        let onopened = props.onopened.clone();
+        use_effect_with_deps(
+            move |text_content| {
+                if let Some(Ok(code)) = &**text_content {
+                    onopened.emit(Rc::from(code.as_str()));
+                }
+            },
+            text_content.clone(),
+        );
+    }
 
     let modal = use_memo(
         |text_content| {
@@ -89,9 +608,9 @@ pub fn Editor(props: &EditorProps) -> HtmlResult {
             move |modal| {
                 log!("we got called");
                 let modal2 = modal.clone();
-                cb.emit(modal2.get_value());
+                cb.emit((MAIN.to_string(), modal2.get_value()));
                 let disposable = modal.on_did_change_content(move |_| {
-                    cb.emit(modal2.get_value());
+                    cb.emit((MAIN.to_string(), modal2.get_value()));
                 });
 
                 move || drop(disposable)
@@ -100,7 +619,174 @@ pub fn Editor(props: &EditorProps) -> HtmlResult {
         )
     }
 
+    let collab = use_mut_ref(|| None::<Collab>);
+    let collab_name = settings.display_name(i18n);
+    {
+        let collab = collab.clone();
+        let oncollab = props.oncollab.clone();
+        let name = collab_name.clone();
+        use_effect_with_deps(
+            move |(modal, room, editor)| {
+                if let (Some(id), Some(editor)) = (room, editor) {
+                    let editor = editor.clone().unchecked_into();
+                    let joined = Collab::join(id, (**modal).clone(), editor, name, oncollab);
+                    *collab.borrow_mut() = Some(joined);
+                }
+                move || drop(collab.borrow_mut().take())
+            },
+            (modal.clone(), props.room.clone(), (*editor).clone()),
+        );
+    }
+    // the room is kept while the name is typed
+    use_effect_with_deps(
+        move |name| {
+            if let Some(collab) = &*collab.borrow() {
+                collab.set_name(name.clone());
+            }
+        },
+        collab_name,
+    );
+
+    let modules = use_mut_ref(HashMap::<String, ModuleModel>::new);
+    {
+        let mut modules = modules.borrow_mut();
+        modules.retain(|path, _| props.paths.contains(path));
+        for path in props.paths.iter().filter(|it| *it != MAIN) {
+            if !modules.contains_key(path) {
+                let code = props.contents.get(path).unwrap_or_default();
+                let model = ModuleModel::new(path, &code, props.oninput.clone());
+                modules.insert(path.clone(), model);
+            }
+        }
+    }
+    {
+        let modules = modules.clone();
+        use_effect_with_deps(move |_| move || modules.borrow_mut().clear(), ());
+    }
+    let active = match modules.borrow().get(&props.active) {
+        Some(module) => module.model.clone(),
+        None => (*modal).clone(),
+    };
+
+    {
+        let modules = modules.clone();
+        let editor = editor.clone();
+        use_effect_with_deps(
+            move |(modal, replacements)| {
+                let editor = editor.as_ref().map(|it| it.unchecked_ref::<ICodeEditor>());
+                for Replacement { path, code } in replacements {
+                    match modules.borrow().get(path) {
+                        Some(module) => replace_contents(&module.model, code, editor),
+                        None => replace_contents(modal, code, editor),
+                    }
+                }
+            },
+            (modal.clone(), props.replacements.clone()),
+        );
+    }
+    {
+        let editor = editor.clone();
+        // the find widget searches whatever file is shown, a search belongs to the file
+        // it was started in
+        use_effect_with_deps(
+            move |_| {
+                if let Some(editor) = &*editor {
+                    editor.unchecked_ref::<IEditor>().trigger(
+                        "playground",
+                        "closeFindWidget",
+                        &JsValue::NULL,
+                    );
+                }
+            },
+            props.active.clone(),
+        );
+    }
+    {
+        let editor = editor.clone();
+        let active = props.active.clone();
+        use_effect_with_deps(
+            move |reveal| {
+                let editor = editor.as_ref().map(|it| it.unchecked_ref::<ICodeEditor>());
+                if let (Some(Reveal { path, span }), Some(editor)) = (reveal, editor) {
+                    if *path == active {
+                        let position = Position::new(span.line as f64, span.column as f64);
+                        editor.set_position(position.unchecked_ref());
+                        editor.reveal_line_in_center(span.line as f64, None);
+                        editor.focus();
+                    }
+                }
+            },
+            props.reveal.clone(),
+        );
+    }
+    {
+        let modules = modules.clone();
+        use_effect_with_deps(
+            move |(modal, markers)| {
+                let targets = markers
+                    .iter()
+                    .map(|markers| {
+                        let model = match modules.borrow().get(&markers.path) {
+                            Some(module) => module.model.clone(),
+                            None => (**modal).clone(),
+                        };
+                        let glyphs = set_markers(
+                            model.as_ref(),
+                            markers.source,
+                            &markers.diagnostics,
+                            &Array::new(),
+                        );
+                        (model, markers.source, glyphs)
+                    })
+                    .collect::<Vec<_>>();
+                move || {
+                    for (model, source, glyphs) in targets {
+                        set_markers(model.as_ref(), source, &[], &glyphs);
+                    }
+                }
+            },
+            (modal.clone(), props.markers.clone()),
+        );
+    }
+
+    use_effect_with_deps(
+        |_| {
+            let (provider, closure) = register_completions();
+            move || {
+                provider.dispose();
+                drop(closure);
+            }
+        },
+        (),
+    );
+
+    // unlike the rest of the options, these can change in place
+    use_effect_with_deps(
+        move |(editor, settings, presenting)| {
+            if let Some(editor) = editor {
+                let options = appearance_options(settings, touch, *presenting);
+                editor.unchecked_ref::<IEditor>().update_options(&options);
+            }
+        },
+        ((*editor).clone(), (*settings).clone(), props.presenting),
+    );
+
+    use_effect_with_deps(
+        |(model, tab_size)| {
+            let options: ITextModelUpdateOptions = js_sys::Object::new().unchecked_into();
+            options.set_tab_size(Some(*tab_size as f64));
+            options.set_insert_spaces(Some(true));
+            AsRef::<ITextModel>::as_ref(model).update_options(&options);
+        },
+        (active.clone(), settings.tab_size),
+    );
+
     Ok(html! {
-        <CodeEditor options={get_options().to_sys_options()} classes="the-editor h-full min-h-0" model={Some((*modal).clone())} />
+        <div class="h-full min-h-0 flex flex-col">
+            <CodeEditor options={(*options).clone()} classes="the-editor flex-grow min-h-0" model={Some(active)} {on_editor_created} />
+            if settings.keybindings == KeybindingMode::Vim {
+                <div ref={statusbar} class="px-2 font-mono text-sm bg-gray-200 dark:bg-gray-800" />
+            }
+        </div>
     })
 }
@@ -0,0 +1,83 @@
+use crate::api;
+use crate::i18n::I18n;
+use crate::utils::query::{Embed, Metadata, Query};
+use crate::Route;
+use common::{BuildOptions, ExampleSummary};
+use yew::prelude::*;
+use yew::suspense::{use_future, Suspense};
+use yew_router::prelude::*;
+
+#[derive(PartialEq, Properties)]
+struct ExampleCardProps {
+    example: ExampleSummary,
+}
+
+#[function_component]
+fn ExampleCard(props: &ExampleCardProps) -> Html {
+    let example = &props.example;
+    let query = Query {
+        shared: None,
+        revision: None,
+        code: None,
+        gist: None,
+        example: Some(example.id.clone()),
+        room: None,
+        options: BuildOptions::default(),
+        embed: Embed::default(),
+        // shown as the snippet's title once it's opened
+        metadata: Metadata {
+            title: Some(example.title.clone()),
+            description: Some(example.description.clone()),
+            ..Metadata::default()
+        },
+    };
+
+    html! {
+        <Link<Route, Query> to={Route::Home} query={Some(query)} classes="flex flex-col rounded-md overflow-hidden shadow-lg bg-gray-100 dark:bg-gray-800 hover:ring-2 ring-green-600">
+            <img src={api::client().example_thumbnail_url(&example.id).ok()} alt="" class="w-full aspect-video object-cover" />
+            <div class="p-3 flex flex-col gap-1">
+                <h2 class="text-lg">{&example.title}</h2>
+                <p class="text-sm text-gray-600 dark:text-gray-400">{&example.description}</p>
+            </div>
+        </Link<Route, Query>>
+    }
+}
+
+#[function_component]
+fn ExampleGrid() -> HtmlResult {
+    let i18n = use_context::<I18n>().unwrap();
+    let list = use_future(|| async { api::client().examples().await })?;
+    let html = match &*list {
+        Ok(list) => html! {
+            <div class="grid gap-4 grid-cols-1 sm:grid-cols-2 lg:grid-cols-3">
+                { for list.iter().map(|example| html! {
+                    <ExampleCard key={example.id.clone()} example={example.clone()} />
+                }) }
+            </div>
+        },
+        Err(e) => {
+            tracing::warn!(?e, "failed to fetch examples");
+            html! { <p>{i18n.t("examples-failed")}</p> }
+        }
+    };
+    Ok(html)
+}
+
+/// Gallery of the backend's starter snippets, picking one opens it in the editor.
+#[function_component]
+pub fn ExamplesPage() -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    html! {
+        <div class="min-h-screen flex flex-col">
+            <header class="bg-gray-100 dark:bg-gray-700 p-3 flex items-center gap-4">
+                <Link<Route> to={Route::Home} classes="p-3 rounded-md bg-gray-300 dark:bg-gray-800 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("examples-back")}</Link<Route>>
+                <h1 class="text-xl">{i18n.t("examples")}</h1>
+            </header>
+            <main class="p-4">
+                <Suspense fallback={html! {i18n.t("loading")}}>
+                    <ExampleGrid />
+                </Suspense>
+            </main>
+        </div>
+    }
+}
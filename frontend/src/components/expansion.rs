@@ -0,0 +1,37 @@
+use crate::components::code_view::CodeView;
+use crate::components::compile_error::CompileErrorPanel;
+use crate::i18n::I18n;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq)]
+pub enum Expansion {
+    Running,
+    Expanded(AttrValue),
+    /// Cargo's output, colors included.
+    CompileError(AttrValue),
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ExpansionViewProps {
+    pub expansion: Expansion,
+}
+
+/// The main file with its macros expanded, read-only, to see what `html!` and friends
+/// generate.
+#[function_component]
+pub fn ExpansionView(props: &ExpansionViewProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let body = match &props.expansion {
+        Expansion::Running => html! { <p class="p-3">{i18n.t("expansion-running")}</p> },
+        Expansion::Expanded(code) => html! { <CodeView code={code.clone()} language="rust" /> },
+        Expansion::CompileError(message) => {
+            html! { <CompileErrorPanel message={message.clone()} /> }
+        }
+    };
+
+    html! {
+        <div class="h-full bg-white dark:bg-gray-900">
+            {body}
+        </div>
+    }
+}
@@ -0,0 +1,47 @@
+use crate::components::code_view::CodeView;
+use crate::i18n::I18n;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct GlueViewProps {
+    /// Of the last run, `None` if it failed or the backend didn't keep it.
+    pub build_id: Option<AttrValue>,
+}
+
+/// The JS wasm-bindgen generated for the last run, read-only, to see how the module is
+/// loaded and what the exported `init` function does.
+#[function_component]
+pub fn GlueView(props: &GlueViewProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let glue = use_state(|| None::<Result<AttrValue, ()>>);
+    {
+        let glue = glue.clone();
+        use_effect_with_deps(
+            move |build_id| {
+                glue.set(None);
+                if let Some(build_id) = build_id.clone() {
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let js = crate::api::client().glue_js(&build_id).await;
+                        glue.set(Some(js.map(AttrValue::from).map_err(|e| {
+                            tracing::warn!(?e, "failed to fetch the glue code");
+                        })));
+                    });
+                }
+            },
+            props.build_id.clone(),
+        );
+    }
+
+    let body = match (&props.build_id, &*glue) {
+        (None, _) => html! { <p class="p-3">{i18n.t("glue-unavailable")}</p> },
+        (Some(_), None) => html! { <p class="p-3">{i18n.t("glue-loading")}</p> },
+        (Some(_), Some(Ok(js))) => html! { <CodeView code={js.clone()} language="javascript" /> },
+        (Some(_), Some(Err(()))) => html! { <p class="p-3">{i18n.t("glue-failed")}</p> },
+    };
+
+    html! {
+        <div class="h-full bg-white dark:bg-gray-900">
+            {body}
+        </div>
+    }
+}
@@ -0,0 +1,92 @@
+use crate::history::{self, Revision};
+use crate::i18n::I18n;
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+fn format_time(time: f64, i18n: I18n) -> String {
+    js_sys::Date::new(&time.into())
+        .to_locale_string(i18n.locale().tag(), &JsValue::UNDEFINED)
+        .into()
+}
+
+#[derive(PartialEq, Properties)]
+pub struct HistoryPanelProps {
+    /// Key of the snippet to list the revisions of.
+    pub snippet: AttrValue,
+    /// Called with the code of the revision to restore.
+    pub onrestore: Callback<String>,
+}
+
+/// Drawer listing the earlier versions of the snippet, one can be previewed and
+/// restored.
+#[function_component]
+pub fn HistoryPanel(props: &HistoryPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let revisions = use_state(|| None::<Vec<Revision>>);
+    let selected = use_state(|| None::<u64>);
+    {
+        let revisions = revisions.clone();
+        use_effect_with_deps(
+            move |snippet| {
+                let snippet = snippet.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match history::list(&snippet).await {
+                        Ok(list) => revisions.set(Some(list)),
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to load revisions");
+                            revisions.set(Some(Vec::new()));
+                        }
+                    }
+                });
+            },
+            props.snippet.clone(),
+        );
+    }
+    let preview = revisions
+        .iter()
+        .flatten()
+        .find(|it| Some(it.id) == *selected)
+        .cloned();
+
+    let list = match &*revisions {
+        None => html! { <p>{i18n.t("loading")}</p> },
+        Some(list) if list.is_empty() => html! { <p>{i18n.t("history-empty")}</p> },
+        Some(list) => html! {
+            <ul class="max-h-48 overflow-auto">
+                { for list.iter().map(|revision| {
+                    let id = revision.id;
+                    let onclick = {
+                        let selected = selected.clone();
+                        move |_| selected.set(Some(id))
+                    };
+                    let classes = classes!(
+                        "w-full",
+                        "text-left",
+                        "px-2",
+                        "py-1",
+                        "rounded",
+                        "hover:bg-gray-200",
+                        "dark:hover:bg-gray-700",
+                        (*selected == Some(id)).then_some("bg-gray-300 dark:bg-gray-600")
+                    );
+                    html! {
+                        <li key={id}>
+                            <button {onclick} class={classes}>{format_time(revision.time, i18n)}</button>
+                        </li>
+                    }
+                }) }
+            </ul>
+        },
+    };
+
+    html! {
+        <div class="absolute left-3 right-3 sm:left-auto top-20 z-10 sm:w-96 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+            <h2 class="text-lg">{i18n.t("history")}</h2>
+            {list}
+            if let Some(revision) = preview {
+                <pre class="max-h-64 overflow-auto p-2 text-xs rounded bg-white dark:bg-gray-900">{&revision.code}</pre>
+                <button onclick={props.onrestore.reform(move |_| revision.code.clone())} class="p-2 rounded bg-gray-300 dark:bg-gray-700 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("history-restore")}</button>
+            }
+        </div>
+    }
+}
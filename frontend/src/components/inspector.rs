@@ -0,0 +1,113 @@
+use crate::i18n::I18n;
+use serde::Deserialize;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Levels of the tree that start out expanded, deeper ones are opened by hand.
+const EXPANDED_DEPTH: usize = 4;
+
+/// A node of the preview's DOM as the bridge script reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum InspectedNode {
+    Element {
+        tag: String,
+        /// In document order, the values of form fields last as `(value)`.
+        attributes: Vec<(String, String)>,
+        children: Vec<InspectedNode>,
+    },
+    /// Text with something besides whitespace, trimmed.
+    Text { text: String },
+}
+
+/// The preview's element tree, posted by the bridge script when asked for it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ElementTree {
+    /// The page's `<body>`, Yew mounts there unless told otherwise.
+    pub root: InspectedNode,
+    /// Whether the bridge stopped walking the page before it was done.
+    pub truncated: bool,
+}
+
+fn view_tag(tag: &str, attributes: &[(String, String)], empty: bool) -> Html {
+    html! {
+        <span>
+            <span class="text-purple-700 dark:text-purple-300">{format!("<{}", tag)}</span>
+            { for attributes.iter().map(|(name, value)| html! {
+                <>
+                    {" "}
+                    <span class="text-orange-700 dark:text-orange-300">{name}</span>
+                    {"=\""}
+                    <span class="text-blue-700 dark:text-blue-300">{value}</span>
+                    {"\""}
+                </>
+            }) }
+            <span class="text-purple-700 dark:text-purple-300">{if empty { " />" } else { ">" }}</span>
+        </span>
+    }
+}
+
+fn view_node(node: &InspectedNode, depth: usize) -> Html {
+    match node {
+        InspectedNode::Text { text } => html! {
+            <div class="pl-4 whitespace-pre-wrap text-gray-600 dark:text-gray-400">{text}</div>
+        },
+        InspectedNode::Element {
+            tag,
+            attributes,
+            children,
+        } if children.is_empty() => html! {
+            <div class="pl-4">{view_tag(tag, attributes, true)}</div>
+        },
+        InspectedNode::Element {
+            tag,
+            attributes,
+            children,
+        } => html! {
+            <details class="pl-4" open={depth < EXPANDED_DEPTH}>
+                <summary class="cursor-pointer">{view_tag(tag, attributes, false)}</summary>
+                { for children.iter().map(|it| view_node(it, depth + 1)) }
+                <div class="pl-4 text-purple-700 dark:text-purple-300">{format!("</{}>", tag)}</div>
+            </details>
+        },
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct InspectorPanelProps {
+    /// The tree the preview last reported, `None` until it did.
+    pub tree: Option<Rc<ElementTree>>,
+    /// Asks the preview for its current tree.
+    pub onrefresh: Callback<()>,
+}
+
+/// The elements of the preview with their attributes, as of when they were last asked
+/// for. Yew leaves no trace of its components in the DOM, so they aren't shown.
+#[function_component]
+pub fn InspectorPanel(props: &InspectorPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let onclick = props.onrefresh.reform(|_| ());
+
+    let body = match &props.tree {
+        None => html! { <p class="p-3">{i18n.t("inspector-empty")}</p> },
+        Some(tree) => html! {
+            <>
+                {view_node(&tree.root, 0)}
+                if tree.truncated {
+                    <p class="p-3 text-gray-600 dark:text-gray-400">{i18n.t("inspector-truncated")}</p>
+                }
+            </>
+        },
+    };
+
+    html! {
+        <div class="h-full flex flex-col text-sm bg-white dark:bg-gray-900">
+            <div class="flex p-1 bg-gray-100 dark:bg-gray-800">
+                <button {onclick} class="ml-auto px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("inspector-refresh")}</button>
+            </div>
+            <div class="flex-grow overflow-auto p-1 font-mono">
+                {body}
+            </div>
+        </div>
+    }
+}
@@ -1,2 +1,32 @@
+pub mod badge;
+pub mod benchmark;
+pub mod build_options;
+pub mod build_stats;
+pub mod clippy;
+pub mod code_view;
+pub mod command_palette;
+pub mod compare;
+pub mod compile_error;
+pub mod console;
+pub mod deep_link;
+pub mod dependencies;
+pub mod diff;
 pub mod editor;
+pub mod examples;
+pub mod expansion;
+pub mod glue;
+pub mod history;
+pub mod inspector;
+pub mod network;
 pub mod output;
+pub mod page_source;
+pub mod progress;
+pub mod recent;
+pub mod revisions;
+pub mod search;
+pub mod settings;
+pub mod share;
+pub mod shortcuts;
+pub mod status_bar;
+pub mod tabs;
+pub mod toast;
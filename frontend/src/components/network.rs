@@ -0,0 +1,149 @@
+use crate::i18n::I18n;
+use serde::Deserialize;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Posted by the bridge script when the preview starts a request, through `fetch` or
+/// `XMLHttpRequest`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RequestStarted {
+    /// Numbers the requests of the page, matching them to their responses.
+    pub id: u32,
+    pub method: String,
+    /// As the page gave it, relative URLs aren't resolved.
+    pub url: String,
+}
+
+/// Posted by the bridge script when a request of the preview is done.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RequestFinished {
+    pub id: u32,
+    /// `None` if no response came, `error` says why.
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Until the response's headers, or the failure.
+    pub duration_ms: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct NetworkRequest {
+    started: RequestStarted,
+    /// `None` while it's pending.
+    finished: Option<RequestFinished>,
+}
+
+/// Requests the preview of the current run made, oldest first.
+#[derive(Debug, Default, PartialEq)]
+pub struct NetworkLog {
+    requests: Vec<NetworkRequest>,
+}
+
+pub enum NetworkAction {
+    Started(RequestStarted),
+    Finished(RequestFinished),
+    Clear,
+}
+
+impl Reducible for NetworkLog {
+    type Action = NetworkAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut requests = self.requests.clone();
+        match action {
+            NetworkAction::Started(started) => requests.push(NetworkRequest {
+                started,
+                finished: None,
+            }),
+            NetworkAction::Finished(finished) => {
+                let request = requests
+                    .iter_mut()
+                    .rev()
+                    .find(|it| it.started.id == finished.id && it.finished.is_none());
+                if let Some(request) = request {
+                    request.finished = Some(finished);
+                }
+            }
+            NetworkAction::Clear => requests.clear(),
+        }
+        Self { requests }.into()
+    }
+}
+
+fn view_request(request: &NetworkRequest, i18n: I18n) -> Html {
+    let (status, time) = match &request.finished {
+        None => (i18n.t("network-pending"), String::new()),
+        Some(finished) => {
+            let status = match (finished.status, &finished.error) {
+                (Some(status), _) => status.to_string(),
+                (None, Some(error)) => error.clone(),
+                (None, None) => i18n.t("network-failed"),
+            };
+            (status, format!("{:.0} ms", finished.duration_ms))
+        }
+    };
+    let failed = request
+        .finished
+        .as_ref()
+        .is_some_and(|it| it.status.is_none_or(|status| status >= 400));
+    let classes = classes!(
+        "border-b",
+        "border-gray-200",
+        "dark:border-gray-700",
+        failed.then_some("text-red-700 dark:text-red-400")
+    );
+    html! {
+        <tr class={classes}>
+            <td class="px-3 py-1">{&request.started.method}</td>
+            <td class="px-3 py-1 break-all">{&request.started.url}</td>
+            <td class="px-3 py-1">{status}</td>
+            <td class="px-3 py-1 text-right whitespace-nowrap">{time}</td>
+        </tr>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct NetworkPanelProps {
+    pub log: UseReducerHandle<NetworkLog>,
+}
+
+/// Lists the requests of the preview with their status and timing, for debugging
+/// snippets that fetch without opening the browser's devtools.
+#[function_component]
+pub fn NetworkPanel(props: &NetworkPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let onclear = {
+        let log = props.log.clone();
+        move |_| log.dispatch(NetworkAction::Clear)
+    };
+
+    let body = if props.log.requests.is_empty() {
+        html! { <p class="p-3">{i18n.t("network-empty")}</p> }
+    } else {
+        html! {
+            <table class="w-full text-left">
+                <thead class="bg-gray-100 dark:bg-gray-800">
+                    <tr>
+                        <th class="px-3 py-1 font-normal">{i18n.t("network-method")}</th>
+                        <th class="px-3 py-1 font-normal">{i18n.t("network-url")}</th>
+                        <th class="px-3 py-1 font-normal">{i18n.t("network-status")}</th>
+                        <th class="px-3 py-1 font-normal text-right">{i18n.t("network-time")}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for props.log.requests.iter().map(|it| view_request(it, i18n)) }
+                </tbody>
+            </table>
+        }
+    };
+
+    html! {
+        <div class="h-full flex flex-col text-sm bg-white dark:bg-gray-900">
+            <div class="flex p-1 bg-gray-100 dark:bg-gray-800">
+                <button onclick={onclear} aria-label={i18n.t("network-clear-label")} class="ml-auto px-2 rounded hover:bg-gray-300 dark:hover:bg-gray-600">{i18n.t("console-clear")}</button>
+            </div>
+            <div class="flex-grow overflow-auto font-mono">
+                {body}
+            </div>
+        </div>
+    }
+}
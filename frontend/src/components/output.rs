@@ -1,52 +1,436 @@
+use crate::api::progress::{self, BuildStage};
 use crate::api::BACKEND_URL;
+use crate::components::benchmark::Benchmark;
+use crate::components::inspector::ElementTree;
+use crate::components::network::{NetworkAction, RequestFinished, RequestStarted};
+use crate::components::progress::BuildProgress;
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::files::RunRequest;
+use crate::i18n::I18n;
+use crate::utils;
 use crate::{ActionButtonState, ActionButtonStateContext};
-use gloo_net::http::QueryParams;
-use std::rc::Rc;
+use common::client::{BuildStats, GlooTransport, PlaygroundClient};
+use common::{BuildOptions, FileDiagnostic, Snippet};
+use gloo::events::EventListener;
+use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{AbortController, HtmlIFrameElement, MessageEvent};
 use yew::prelude::*;
 
-#[derive(Properties, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleLevel {
+    Log,
+    Info,
+    Warn,
+    Error,
+    Debug,
+}
+
+/// Message the bridge script injected into the preview page posts to the playground.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreviewMessage {
+    Console {
+        level: ConsoleLevel,
+        message: String,
+    },
+    /// An uncaught error, which is what a panic without a panic hook turns into.
+    Panic {
+        message: String,
+        stack: Option<String>,
+    },
+    /// Sent by the page the backend serves instead when the build failed.
+    CompileError {
+        message: String,
+        #[serde(default)]
+        diagnostics: Vec<FileDiagnostic>,
+    },
+    /// Code entered into the console, shown before what it evaluates to.
+    #[serde(skip_deserializing)]
+    Input { code: String },
+    /// What code entered into the console evaluated to, or the error it threw.
+    Result { message: String, error: bool },
+}
+
+/// Code entered into the console, numbered so entering the same code again evaluates it
+/// again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    pub id: u32,
+    pub code: AttrValue,
+}
+
+/// Message of the bridge about the preview itself, handled here rather than passed on.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FrameMessage {
+    /// Pressed in the preview, where the playground can't see it.
+    Escape,
+    /// Posted by the benchmark built for runs with `bench`, once it's done.
+    Benchmark(Benchmark),
+    Request(RequestStarted),
+    Response(RequestFinished),
+    /// The element tree, when asked for it.
+    Tree(ElementTree),
+    /// The page as a PNG `data:` URL when asked for it, `None` with the `error` if
+    /// rendering it failed.
+    Screenshot {
+        data: Option<String>,
+        error: Option<String>,
+    },
+}
+
+/// Posts a request to the bridge script of the preview in `iframe`, if it's loaded.
+/// Returns whether it was posted.
+fn post_to_preview(iframe: &NodeRef, message: serde_json::Value) -> bool {
+    let Some(preview) = iframe
+        .cast::<HtmlIFrameElement>()
+        .and_then(|it| it.content_window())
+    else {
+        return false;
+    };
+    // the preview's origin is opaque, it can't be named
+    match preview.post_message(&message.to_string().into(), "*") {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(?e, "failed to post to the preview");
+            false
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
 pub struct OutputContainerProps {
-    pub value: Rc<str>,
+    pub request: RunRequest,
+    pub onmessage: Callback<PreviewMessage>,
+    /// Called with the stats of each build, `None` if it failed.
+    #[prop_or_default]
+    pub onstats: Callback<Option<BuildStats>>,
+    /// Called with the ID the backend keeps each build under, `None` if it doesn't.
+    #[prop_or_default]
+    pub onbuild: Callback<Option<AttrValue>>,
+    /// Called with the HTML document each run's preview is loaded with.
+    #[prop_or_default]
+    pub onpage: Callback<AttrValue>,
+    /// Called with the timings of benchmark runs.
+    #[prop_or_default]
+    pub onbenchmark: Callback<Benchmark>,
+    /// Called with the requests the preview makes, as they start and finish.
+    #[prop_or_default]
+    pub onnetwork: Callback<NetworkAction>,
+    /// Code the preview evaluates, it reports the result as a `PreviewMessage::Result`.
+    #[prop_or_default]
+    pub evaluation: Option<Evaluation>,
+    /// Bumped to have the preview report its element tree, which it does again each
+    /// time it loads once it was. `0` never asks for it.
+    #[prop_or_default]
+    pub inspection: u32,
+    /// Called with the element trees the preview reports.
+    #[prop_or_default]
+    pub ontree: Callback<ElementTree>,
+    /// Bumped to export the preview as an image, like its own button does. `0` never
+    /// does.
+    #[prop_or_default]
+    pub capture: u32,
 }
 
 #[function_component]
 pub fn OutputContainer(props: &OutputContainerProps) -> Html {
     let action_button_state = use_context::<ActionButtonStateContext>().unwrap();
+    let toasts = use_context::<ToastContext>().unwrap();
+    let i18n = use_context::<I18n>().unwrap();
     let loading = use_state(|| true);
-    let src = use_state(|| AttrValue::from("about:black"));
+    let page = use_state(|| None::<AttrValue>);
+    let stage = use_state(|| BuildStage::Queued);
+    // aborts the request of the run in progress
+    let controller = use_mut_ref(|| None::<AbortController>);
 
     {
         let loading = loading.clone();
-        let src = src.clone();
+        let page = page.clone();
+        let stage = stage.clone();
+        let action_button_state = action_button_state.clone();
+        let toasts = toasts.clone();
+        let controller = controller.clone();
+        let onstats = props.onstats.clone();
+        let onbuild = props.onbuild.clone();
+        let onpage = props.onpage.clone();
         use_effect_with_deps(
-            move |value| {
-                src.set({
-                    let query = QueryParams::new();
-                    query.append("code", value);
-                    AttrValue::from(format!("{}/run?{}", BACKEND_URL, query))
+            move |request| {
+                let request = request.clone();
+                loading.set(true);
+                stage.set(BuildStage::Queued);
+                let id = progress::new_id();
+                let subscription = progress::follow(&id, Callback::from(move |it| stage.set(it)))
+                    .map_err(|e| tracing::warn!(?e, "failed to follow the build"))
+                    .ok();
+                let abort = AbortController::new().unwrap();
+                let signal = abort.signal();
+                *controller.borrow_mut() = Some(abort);
+                wasm_bindgen_futures::spawn_local(async move {
+                    // aborting cancels the build, the compiler stops working on it once
+                    // the request is gone
+                    let client = PlaygroundClient::new(
+                        BACKEND_URL,
+                        GlooTransport::with_abort_signal(signal.clone()),
+                    );
+                    let snippet = Snippet {
+                        files: (*request.files).clone(),
+                    };
+                    let options = BuildOptions {
+                        bench: request.bench,
+                        ..request.options.clone()
+                    };
+                    let result = client.run(&snippet, &options, Some(&id)).await;
+                    drop(subscription);
+                    // whoever aborted it already moved on, to a newer run or back to idle
+                    if signal.aborted() {
+                        return;
+                    }
+                    match result {
+                        Ok(output) => {
+                            onstats.emit(output.stats);
+                            onbuild.emit(output.build_id.map(AttrValue::from));
+                            let output_page = AttrValue::from(output.page);
+                            onpage.emit(output_page.clone());
+                            page.set(Some(output_page));
+                        }
+                        Err(e) => {
+                            onstats.emit(None);
+                            onbuild.emit(None);
+                            tracing::warn!(?e, "failed to run code");
+                            toast::show(&toasts, ToastKind::Error, i18n.t("run-failed"));
+                            // there's no page to wait for
+                            action_button_state.dispatch(ActionButtonState::Enabled);
+                        }
+                    }
+                    loading.set(false);
                 });
-                loading.set(false);
+                // a newer run or leaving the page cancels this one
+                move || {
+                    if let Some(abort) = controller.borrow_mut().take() {
+                        abort.abort();
+                    }
+                }
             },
-            Rc::clone(&props.value),
+            props.request.clone(),
         )
     };
+    let oncancel = {
+        let loading = loading.clone();
+        let action_button_state = action_button_state.clone();
+        let toasts = toasts.clone();
+        Callback::from(move |_| {
+            if let Some(abort) = controller.borrow_mut().take() {
+                abort.abort();
+            }
+            loading.set(false);
+            action_button_state.dispatch(ActionButtonState::Enabled);
+            toast::show(&toasts, ToastKind::Info, i18n.t("run-cancelled"));
+        })
+    };
+
+    // the preview filling the viewport, for components that need the room
+    let fullscreen = use_state_eq(|| false);
+    {
+        let open = *fullscreen;
+        let fullscreen = fullscreen.clone();
+        use_effect_with_deps(
+            move |open| {
+                let listener = open.then(|| {
+                    EventListener::new(&gloo::utils::window(), "keydown", move |e| {
+                        if e.unchecked_ref::<KeyboardEvent>().key() == "Escape" {
+                            fullscreen.set(false);
+                        }
+                    })
+                });
+                move || drop(listener)
+            },
+            open,
+        );
+    }
+
+    let iframe = use_node_ref();
+    // while the preview renders the image, which takes a moment for big pages
+    let capturing = use_state_eq(|| false);
+    {
+        let iframe = iframe.clone();
+        let fullscreen = fullscreen.clone();
+        let capturing = capturing.clone();
+        let toasts = toasts.clone();
+        use_effect_with_deps(
+            move |(onmessage, onbenchmark, onnetwork, ontree, i18n)| {
+                let (onmessage, onbenchmark) = (onmessage.clone(), onbenchmark.clone());
+                let (onnetwork, ontree) = (onnetwork.clone(), ontree.clone());
+                let i18n = *i18n;
+                let listener = EventListener::new(&gloo::utils::window(), "message", move |e| {
+                    let e = e.unchecked_ref::<MessageEvent>();
+                    // only trust messages coming from our own preview
+                    let preview = iframe
+                        .cast::<HtmlIFrameElement>()
+                        .and_then(|it| it.content_window());
+                    let from_preview = match (e.source(), preview) {
+                        (Some(source), Some(preview)) => js_sys::Object::is(&source, &preview),
+                        _ => false,
+                    };
+                    if !from_preview {
+                        return;
+                    }
+                    let Some(data) = e.data().as_string() else {
+                        return;
+                    };
+                    match serde_json::from_str(&data) {
+                        Ok(FrameMessage::Escape) => {
+                            fullscreen.set(false);
+                            return;
+                        }
+                        Ok(FrameMessage::Benchmark(benchmark)) => {
+                            onbenchmark.emit(benchmark);
+                            return;
+                        }
+                        Ok(FrameMessage::Request(started)) => {
+                            onnetwork.emit(NetworkAction::Started(started));
+                            return;
+                        }
+                        Ok(FrameMessage::Response(finished)) => {
+                            onnetwork.emit(NetworkAction::Finished(finished));
+                            return;
+                        }
+                        Ok(FrameMessage::Tree(tree)) => {
+                            ontree.emit(tree);
+                            return;
+                        }
+                        Ok(FrameMessage::Screenshot { data, error }) => {
+                            capturing.set(false);
+                            let saved = data
+                                .ok_or_else(|| JsValue::from(error))
+                                .and_then(|url| utils::download_url(&url, "preview.png"));
+                            if let Err(e) = saved {
+                                tracing::warn!(?e, "failed to export the preview");
+                                toast::show(
+                                    &toasts,
+                                    ToastKind::Error,
+                                    i18n.t("export-image-failed"),
+                                );
+                            }
+                            return;
+                        }
+                        Err(_) => {}
+                    }
+                    if let Ok(message) = serde_json::from_str(&data) {
+                        onmessage.emit(message);
+                    }
+                });
+                move || drop(listener)
+            },
+            (
+                props.onmessage.clone(),
+                props.onbenchmark.clone(),
+                props.onnetwork.clone(),
+                props.ontree.clone(),
+                i18n,
+            ),
+        );
+    }
+
+    {
+        let iframe = iframe.clone();
+        use_effect_with_deps(
+            move |evaluation| {
+                if let Some(evaluation) = evaluation {
+                    let code = evaluation.code.as_str();
+                    post_to_preview(&iframe, json!({ "type": "evaluate", "code": code }));
+                }
+            },
+            props.evaluation.clone(),
+        );
+    }
+    {
+        let iframe = iframe.clone();
+        use_effect_with_deps(
+            move |inspection| {
+                if *inspection != 0 {
+                    post_to_preview(&iframe, json!({ "type": "inspect" }));
+                }
+            },
+            props.inspection,
+        );
+    }
+
+    let capture = {
+        let iframe = iframe.clone();
+        let capturing = capturing.clone();
+        Callback::from(move |_| {
+            capturing.set(post_to_preview(&iframe, json!({ "type": "screenshot" })));
+        })
+    };
+    {
+        let capture = capture.clone();
+        use_effect_with_deps(
+            move |capture_count| {
+                if *capture_count != 0 {
+                    capture.emit(());
+                }
+            },
+            props.capture,
+        );
+    }
+    let on_capture_click = capture.reform(|_: MouseEvent| ());
 
-    let fallback = html! { <div class="h-full bg-gray-600">{"Loading"}</div> };
+    let fallback = html! { <BuildProgress stage={*stage} {oncancel} /> };
 
-    let onload = move |_| {
-        action_button_state.dispatch(ActionButtonState::Enabled);
+    let onload = {
+        let iframe = iframe.clone();
+        let inspection = props.inspection;
+        let capturing = capturing.clone();
+        move |_| {
+            action_button_state.dispatch(ActionButtonState::Enabled);
+            // a new page won't answer what the old one was asked
+            capturing.set(false);
+            if inspection != 0 {
+                post_to_preview(&iframe, json!({ "type": "inspect" }));
+            }
+        }
     };
     let classes = classes!(
         "w-full",
         "h-full",
         if *loading { "invisible" } else { "visible" }
     );
+    let on_fullscreen_click = {
+        let fullscreen = fullscreen.clone();
+        move |_| fullscreen.set(!*fullscreen)
+    };
+    // restyled rather than moved, moving the iframe would reload the preview
+    let frame_classes = if *fullscreen {
+        "fixed inset-0 z-30 bg-white"
+    } else {
+        "relative w-full h-full"
+    };
+    let label = i18n.t(if *fullscreen {
+        "fullscreen-exit"
+    } else {
+        "fullscreen"
+    });
     html! {
         <>
             if *loading {
                 {fallback}
             }
-            <iframe src={AttrValue::clone(&*src)} {onload} class={classes} />
+            // scripts only: the preview gets an opaque origin and can't reach the playground
+            if let Some(page) = &*page {
+                <div class={frame_classes}>
+                    <iframe ref={iframe} sandbox="allow-scripts" srcdoc={page.clone()} {onload} class={classes} />
+                    if !*loading {
+                        <div class="absolute top-2 right-2 flex gap-2">
+                            <button onclick={on_capture_click} disabled={*capturing} class="px-2 py-1 text-sm rounded-md shadow bg-gray-200/80 dark:bg-gray-700/80 hover:bg-gray-300 dark:hover:bg-gray-600 disabled:opacity-50">{i18n.t(if *capturing { "export-image-running" } else { "export-image" })}</button>
+                            <button onclick={on_fullscreen_click} aria-pressed={fullscreen.to_string()} class="px-2 py-1 text-sm rounded-md shadow bg-gray-200/80 dark:bg-gray-700/80 hover:bg-gray-300 dark:hover:bg-gray-600">{label}</button>
+                        </div>
+                    }
+                </div>
+            }
         </>
     }
 }
@@ -0,0 +1,53 @@
+use crate::components::code_view::CodeView;
+use crate::i18n::I18n;
+use yew::prelude::*;
+
+/// What the backend inlines the module as, see its `page`.
+const WASM_ARRAY_START: &str = "new Int8Array([";
+
+/// The page with the module's bytes left out, they'd be megabytes of numbers.
+fn elide_wasm(page: &str) -> String {
+    let Some(start) = page
+        .find(WASM_ARRAY_START)
+        .map(|it| it + WASM_ARRAY_START.len())
+    else {
+        return page.to_string();
+    };
+    let Some(len) = page[start..].find(']') else {
+        return page.to_string();
+    };
+    let bytes = page[start..start + len].matches(',').count() + 1;
+    format!(
+        "{}/* {} bytes of wasm */{}",
+        &page[..start],
+        bytes,
+        &page[start + len..]
+    )
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PageSourceViewProps {
+    /// What the preview was last loaded with.
+    pub page: Option<AttrValue>,
+}
+
+/// The HTML document the backend generated for the preview, read-only, to see what a
+/// blank preview was actually given.
+#[function_component]
+pub fn PageSourceView(props: &PageSourceViewProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let source = use_memo(
+        |page| page.as_deref().map(|it| AttrValue::from(elide_wasm(it))),
+        props.page.clone(),
+    );
+    let body = match &*source {
+        Some(source) => html! { <CodeView code={source.clone()} language="html" /> },
+        None => html! { <p class="p-3">{i18n.t("page-source-unavailable")}</p> },
+    };
+
+    html! {
+        <div class="h-full bg-white dark:bg-gray-900">
+            {body}
+        </div>
+    }
+}
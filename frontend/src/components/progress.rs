@@ -0,0 +1,44 @@
+use crate::api::progress::BuildStage;
+use crate::i18n::I18n;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct BuildProgressProps {
+    pub stage: BuildStage,
+    pub oncancel: Callback<()>,
+}
+
+/// The stages of a running build, with the ones it's through checked off.
+#[function_component]
+pub fn BuildProgress(props: &BuildProgressProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let steps = BuildStage::STEPS;
+    let current = steps
+        .iter()
+        .position(|(stage, _)| *stage == props.stage)
+        .unwrap_or(steps.len());
+    let percent = (current * 100 / steps.len()).to_string();
+
+    html! {
+        <div class="h-full flex flex-col items-center justify-center gap-4 bg-gray-300 dark:bg-gray-600">
+            <div role="progressbar" aria-label={i18n.t("build-progress")} aria-valuemin="0" aria-valuemax="100" aria-valuenow={percent.clone()} class="w-64 h-2 rounded bg-gray-400 dark:bg-gray-700 overflow-hidden">
+                <div class="h-full bg-green-600 transition-all duration-500" style={format!("width: {}%", percent)} />
+            </div>
+            <ol class="flex flex-col gap-1 text-sm">
+                { for steps.iter().enumerate().map(|(i, (_, label))| {
+                    let (marker, classes) = match i.cmp(&current) {
+                        std::cmp::Ordering::Less => ("✓", "text-gray-600 dark:text-gray-400"),
+                        std::cmp::Ordering::Equal => ("›", "font-bold"),
+                        std::cmp::Ordering::Greater => ("·", "text-gray-500"),
+                    };
+                    html! {
+                        <li class={classes}>
+                            <span class="inline-block w-4">{marker}</span>{i18n.t(label)}
+                        </li>
+                    }
+                }) }
+            </ol>
+            <button onclick={props.oncancel.reform(|_| ())} class="px-3 py-1 rounded-md bg-gray-200 dark:bg-gray-700 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("cancel")}</button>
+        </div>
+    }
+}
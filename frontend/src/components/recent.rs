@@ -0,0 +1,100 @@
+use crate::i18n::I18n;
+use crate::recent::{self, RecentSnippet};
+use wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+fn format_time(time: f64, i18n: I18n) -> String {
+    js_sys::Date::new(&time.into())
+        .to_locale_string(i18n.locale().tag(), &JsValue::UNDEFINED)
+        .into()
+}
+
+/// What's shown for snippets without a title, from the kind of link that opened them.
+fn fallback_label(snippet: &str, i18n: I18n) -> String {
+    let (kind, id) = snippet.split_once(':').unwrap_or((snippet, ""));
+    match kind {
+        "shared" => i18n.t_args("recent-shared", &[("id", &id)]),
+        "gist" => i18n.t_args("recent-gist", &[("id", &id)]),
+        "example" => i18n.t_args("recent-example", &[("id", &id)]),
+        _ => i18n.t("recent-link"),
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct RecentMenuProps {
+    /// Of the button, the same as the header's other buttons.
+    pub classes: Classes,
+}
+
+/// Header button opening a list of the snippets recently opened or edited in this
+/// browser, read from IndexedDB each time it's opened.
+#[function_component]
+pub fn RecentMenu(props: &RecentMenuProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let open = use_state_eq(|| false);
+    let snippets = use_state(|| None::<Vec<RecentSnippet>>);
+
+    let onclick = {
+        let (open, snippets) = (open.clone(), snippets.clone());
+        move |_| {
+            if *open {
+                open.set(false);
+                return;
+            }
+            open.set(true);
+            snippets.set(None);
+            let snippets = snippets.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match recent::list().await {
+                    Ok(list) => snippets.set(Some(list)),
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to load recent snippets");
+                        snippets.set(Some(Vec::new()));
+                    }
+                }
+            });
+        }
+    };
+    let onkeydown = {
+        let open = open.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                open.set(false);
+            }
+        }
+    };
+
+    let list = match &*snippets {
+        None => html! { <p class="p-2">{i18n.t("loading")}</p> },
+        Some(list) if list.is_empty() => html! { <p class="p-2">{i18n.t("recent-empty")}</p> },
+        Some(list) => html! {
+            <ul class="max-h-96 overflow-auto flex flex-col">
+                { for list.iter().map(|snippet| {
+                    let label = snippet
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| fallback_label(&snippet.snippet, i18n));
+                    html! {
+                        <li>
+                            <a href={snippet.href.clone()} class="flex flex-col px-2 py-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700">
+                                <span class="truncate">{label}</span>
+                                <span class="text-xs text-gray-600 dark:text-gray-400">{format_time(snippet.time, i18n)}</span>
+                            </a>
+                        </li>
+                    }
+                }) }
+            </ul>
+        },
+    };
+
+    html! {
+        <div {onkeydown} class="relative">
+            <button {onclick} aria-expanded={open.to_string()} class={props.classes.clone()}>{i18n.t("recent")}</button>
+            if *open {
+                <div class="absolute right-0 top-full mt-1 z-20 w-72 p-1 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg">
+                    {list}
+                </div>
+            }
+        </div>
+    }
+}
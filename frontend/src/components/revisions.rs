@@ -0,0 +1,189 @@
+use crate::api;
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::files::{FileContents, MAIN};
+use crate::i18n::I18n;
+use crate::utils::copy_to_clipboard;
+use crate::utils::query::{use_query, Query};
+use crate::Route;
+use common::{NewRevision, RevisionSummary};
+use wasm_bindgen::JsValue;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// Of a revision's name, in characters, the backend takes no more.
+const MAX_NAME_LEN: u32 = 64;
+
+fn format_time(time: &str, i18n: I18n) -> String {
+    js_sys::Date::new(&time.into())
+        .to_locale_string(i18n.locale().tag(), &JsValue::UNDEFINED)
+        .into()
+}
+
+#[derive(PartialEq, Properties)]
+pub struct RevisionsPanelProps {
+    /// Id of the paste the revisions are of.
+    pub paste: AttrValue,
+    /// Of the paste if it was shared from here, only then are revisions saved.
+    pub owner_token: Option<String>,
+    /// `main.rs` of it is saved as a new revision.
+    pub contents: FileContents,
+}
+
+/// Drawer listing the named revisions of a shared snippet, each one opened through a
+/// link of its own that can be passed on.
+#[function_component]
+pub fn RevisionsPanel(props: &RevisionsPanelProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let toasts = use_context::<ToastContext>().unwrap();
+    let query = use_query();
+    let list = use_state(|| None::<Vec<RevisionSummary>>);
+    let saving = use_state_eq(|| false);
+    let name_ref = use_node_ref();
+    {
+        let list = list.clone();
+        use_effect_with_deps(
+            move |paste| {
+                let paste = paste.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api::client().revisions(&paste).await {
+                        Ok(revisions) => list.set(Some(revisions)),
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to load revisions");
+                            list.set(Some(Vec::new()));
+                        }
+                    }
+                });
+            },
+            props.paste.clone(),
+        );
+    }
+
+    let onsubmit = {
+        let (list, saving) = (list.clone(), saving.clone());
+        let name_ref = name_ref.clone();
+        let paste = props.paste.clone();
+        let contents = props.contents.clone();
+        let owner_token = props.owner_token.clone().unwrap_or_default();
+        let toasts = toasts.clone();
+        move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(input) = name_ref.cast::<HtmlInputElement>() else {
+                return;
+            };
+            let name = input.value();
+            if name.trim().is_empty() || *saving {
+                return;
+            }
+            saving.set(true);
+            let code = contents.get(MAIN).unwrap_or_default();
+            let (list, saving) = (list.clone(), saving.clone());
+            let (paste, toasts) = (paste.clone(), toasts.clone());
+            let owner_token = owner_token.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let revision = NewRevision {
+                    name,
+                    content: code,
+                    owner_token,
+                };
+                match api::client().add_revision(&paste, &revision).await {
+                    Ok(revision) => {
+                        input.set_value("");
+                        let mut revisions = (*list).clone().unwrap_or_default();
+                        revisions.push(RevisionSummary {
+                            id: revision.id,
+                            name: revision.name,
+                            created_at: revision.created_at,
+                        });
+                        list.set(Some(revisions));
+                        toast::show(&toasts, ToastKind::Success, i18n.t("revisions-saved"));
+                    }
+                    Err(e) => {
+                        tracing::warn!(?e, "failed to save a revision");
+                        toast::show(&toasts, ToastKind::Error, i18n.t("revisions-save-failed"));
+                    }
+                }
+                saving.set(false);
+            });
+        }
+    };
+
+    let current = query.as_ref().and_then(|it| it.revision.clone());
+    // the link the paste was opened with, pointing at another revision
+    let link = |revision: Option<String>| {
+        query.clone().map(|it| Query {
+            revision,
+            room: None,
+            ..it
+        })
+    };
+    let item = |revision: Option<String>, label: String| {
+        let is_current = revision == current;
+        let query = link(revision);
+        let oncopy = {
+            let toasts = toasts.clone();
+            let query = query.clone();
+            move |_| {
+                let query = serde_urlencoded::to_string(&query).expect("query serializes");
+                let origin = gloo::utils::window()
+                    .location()
+                    .origin()
+                    .expect("failed to get origin");
+                let url = format!("{}/?{}", origin, query);
+                let toasts = toasts.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match copy_to_clipboard(&url).await {
+                        Ok(()) => toast::show(&toasts, ToastKind::Success, i18n.t("share-copied")),
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to copy link");
+                            toast::show(&toasts, ToastKind::Info, i18n.t("revisions-copy-failed"));
+                        }
+                    }
+                });
+            }
+        };
+        let classes = classes!(
+            "flex-grow",
+            "px-2",
+            "py-1",
+            "rounded",
+            "hover:bg-gray-200",
+            "dark:hover:bg-gray-700",
+            is_current.then_some("bg-gray-300 dark:bg-gray-600")
+        );
+        html! {
+            <li class="flex items-center gap-1">
+                <Link<Route, Query> to={Route::Home} {query} classes={classes}>{label}</Link<Route, Query>>
+                <button onclick={oncopy} title={i18n.t("revisions-copy")} aria-label={i18n.t("revisions-copy")} class="px-2 py-1 text-sm rounded hover:bg-gray-200 dark:hover:bg-gray-700">{i18n.t("revisions-copy-short")}</button>
+            </li>
+        }
+    };
+
+    let revisions = match &*list {
+        None => html! { <p>{i18n.t("loading")}</p> },
+        Some(list) => html! {
+            <ul class="max-h-64 overflow-auto flex flex-col gap-1">
+                {item(None, i18n.t("revisions-original"))}
+                { for list.iter().map(|revision| {
+                    let label = format!("{} · {}", revision.name, format_time(&revision.created_at, i18n));
+                    item(Some(revision.id.clone()), label)
+                }) }
+            </ul>
+        },
+    };
+
+    html! {
+        <div class="absolute left-3 right-3 sm:left-auto top-20 z-10 sm:w-96 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+            <h2 class="text-lg">{i18n.t("revisions")}</h2>
+            {revisions}
+            if props.owner_token.is_some() {
+                <form {onsubmit} class="flex gap-2">
+                    <input ref={name_ref} maxlength={MAX_NAME_LEN.to_string()} placeholder={i18n.t("revisions-name")} aria-label={i18n.t("revisions-name")} class="flex-grow p-2 rounded bg-white dark:bg-gray-900" />
+                    <button type="submit" disabled={*saving} class="p-2 rounded bg-gray-300 dark:bg-gray-700 hover:bg-gray-400 dark:hover:bg-gray-900 disabled:opacity-50">{i18n.t("revisions-save")}</button>
+                </form>
+            } else {
+                <p class="text-sm text-gray-600 dark:text-gray-400">{i18n.t("revisions-not-owned")}</p>
+            }
+        </div>
+    }
+}
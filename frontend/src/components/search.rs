@@ -0,0 +1,161 @@
+use crate::api;
+use crate::i18n::I18n;
+use crate::utils::query::{Embed, Metadata, Query};
+use crate::Route;
+use common::{BuildOptions, SnippetSummary};
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+/// What's searched for, kept in the page's query so searches can be linked to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl SearchQuery {
+    /// The snippets with the tag.
+    pub fn tag(tag: &str) -> Self {
+        Self {
+            q: None,
+            tag: Some(tag.to_string()),
+        }
+    }
+}
+
+/// Links to the search for each tag.
+pub fn tag_links(tags: &[String]) -> Html {
+    html! {
+        <ul class="flex flex-wrap gap-1">
+            { for tags.iter().map(|tag| html! {
+                <li>
+                    <Link<Route, SearchQuery> to={Route::Search} query={Some(SearchQuery::tag(tag))} classes="px-2 rounded-full text-sm bg-gray-200 dark:bg-gray-700 hover:bg-gray-300 dark:hover:bg-gray-600">{format!("#{}", tag)}</Link<Route, SearchQuery>>
+                </li>
+            }) }
+        </ul>
+    }
+}
+
+fn format_time(time: &str, i18n: I18n) -> String {
+    js_sys::Date::new(&time.into())
+        .to_locale_date_string(i18n.locale().tag(), &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+fn view_snippet(snippet: &SnippetSummary, i18n: I18n) -> Html {
+    let query = Query {
+        shared: Some(snippet.id.clone()),
+        revision: None,
+        code: None,
+        gist: None,
+        example: None,
+        room: None,
+        options: BuildOptions::default(),
+        embed: Embed::default(),
+        // the paste holds it
+        metadata: Metadata::default(),
+    };
+    let title = snippet
+        .title
+        .clone()
+        .unwrap_or_else(|| i18n.t("search-untitled"));
+    html! {
+        <li class="p-3 flex flex-col gap-1 rounded-md bg-gray-100 dark:bg-gray-800">
+            <Link<Route, Query> to={Route::Home} query={Some(query)} classes="text-lg hover:underline">{title}</Link<Route, Query>>
+            if let Some(description) = &snippet.description {
+                <p class="text-sm text-gray-600 dark:text-gray-400">{description}</p>
+            }
+            <div class="flex flex-wrap gap-2 items-center text-sm">
+                <span class="text-gray-600 dark:text-gray-400">{format_time(&snippet.created_at, i18n)}</span>
+                {tag_links(&snippet.tags)}
+            </div>
+        </li>
+    }
+}
+
+/// Searches the shared snippets by text and tag.
+#[function_component]
+pub fn SearchPage() -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let navigator = use_navigator().unwrap();
+    let search = use_location()
+        .and_then(|it| it.query::<SearchQuery>().ok())
+        .unwrap_or_default();
+    let results = use_state(|| None::<Result<Vec<SnippetSummary>, ()>>);
+    let text_ref = use_node_ref();
+    let tag_ref = use_node_ref();
+    {
+        let results = results.clone();
+        use_effect_with_deps(
+            move |search: &SearchQuery| {
+                results.set(None);
+                let search = search.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let text = search.q.as_deref().unwrap_or_default();
+                    match api::client().snippets(text, search.tag.as_deref()).await {
+                        Ok(snippets) => results.set(Some(Ok(snippets))),
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to search snippets");
+                            results.set(Some(Err(())));
+                        }
+                    }
+                });
+            },
+            search.clone(),
+        );
+    }
+
+    let onsubmit = {
+        let (text_ref, tag_ref) = (text_ref.clone(), tag_ref.clone());
+        move |e: SubmitEvent| {
+            e.prevent_default();
+            let field = |node: &NodeRef| {
+                node.cast::<HtmlInputElement>()
+                    .map(|it| it.value().trim().to_string())
+                    .filter(|it| !it.is_empty())
+            };
+            let search = SearchQuery {
+                q: field(&text_ref),
+                tag: field(&tag_ref).map(|it| it.trim_start_matches('#').to_lowercase()),
+            };
+            if let Err(e) = navigator.push_with_query(&Route::Search, &search) {
+                tracing::warn!(?e, "failed to search");
+            }
+        }
+    };
+
+    let body = match &*results {
+        None => html! { <p>{i18n.t("loading")}</p> },
+        Some(Err(())) => html! { <p>{i18n.t("search-failed")}</p> },
+        Some(Ok(snippets)) if snippets.is_empty() => html! { <p>{i18n.t("search-empty")}</p> },
+        Some(Ok(snippets)) => html! {
+            <ul class="flex flex-col gap-3">
+                { for snippets.iter().map(|it| view_snippet(it, i18n)) }
+            </ul>
+        },
+    };
+    let field_classes = "p-2 rounded bg-gray-200 dark:bg-gray-700";
+
+    html! {
+        <div class="min-h-screen flex flex-col">
+            <header class="bg-gray-100 dark:bg-gray-700 p-3 flex items-center gap-4">
+                <Link<Route> to={Route::Home} classes="p-3 rounded-md bg-gray-300 dark:bg-gray-800 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("examples-back")}</Link<Route>>
+                <h1 class="text-xl">{i18n.t("search")}</h1>
+            </header>
+            <main class="p-4 max-w-3xl w-full mx-auto flex flex-col gap-4">
+                // keyed by the search so the fields show it after following a tag link
+                <form key={format!("{:?}", search)} {onsubmit} role="search" class="flex flex-wrap gap-2">
+                    <input ref={text_ref} type="search" value={search.q.clone().unwrap_or_default()} placeholder={i18n.t("search-text")} aria-label={i18n.t("search-text")} class={classes!("flex-grow", field_classes)} />
+                    <input ref={tag_ref} type="text" value={search.tag.clone().unwrap_or_default()} placeholder={i18n.t("search-tag")} aria-label={i18n.t("search-tag")} class={classes!("w-40", field_classes)} />
+                    <button type="submit" class="p-2 rounded bg-gray-300 dark:bg-gray-700 hover:bg-gray-400 dark:hover:bg-gray-900">{i18n.t("search")}</button>
+                </form>
+                <p class="text-sm text-gray-600 dark:text-gray-400">{i18n.t("search-hint")}</p>
+                {body}
+            </main>
+        </div>
+    }
+}
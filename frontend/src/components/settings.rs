@@ -0,0 +1,244 @@
+use crate::i18n::{I18n, Locale};
+use crate::settings::{
+    KeybindingMode, SettingsAction, SettingsContext, SplitOrientation, ThemePreference,
+    WhitespaceMode, FONT_FAMILIES, FONT_SIZES, LIVE_DELAYS_MS, MAX_COLLAB_NAME_LEN, TAB_SIZES,
+};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Drawer with the user's preferences, opened from the header.
+#[function_component]
+pub fn SettingsPanel() -> Html {
+    let settings = use_context::<SettingsContext>().unwrap();
+    let i18n = use_context::<I18n>().unwrap();
+
+    // the empty value follows the browser's language
+    let on_locale_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let locale = Locale::from_tag(&select.value());
+            settings.dispatch(SettingsAction::Locale(locale));
+        }
+    };
+
+    let on_tab_size_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(tab_size) = select.value().parse() {
+                settings.dispatch(SettingsAction::TabSize(tab_size));
+            }
+        }
+    };
+
+    let on_theme_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(theme) = ThemePreference::from_str(&select.value()) {
+                settings.dispatch(SettingsAction::Theme(theme));
+            }
+        }
+    };
+
+    let on_colorblind_change = {
+        let settings = settings.clone();
+        move |_| {
+            let colorblind = !settings.colorblind_diagnostics;
+            settings.dispatch(SettingsAction::ColorblindDiagnostics(colorblind));
+        }
+    };
+
+    let on_keybindings_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(mode) = KeybindingMode::from_str(&select.value()) {
+                settings.dispatch(SettingsAction::Keybindings(mode));
+            }
+        }
+    };
+
+    let on_font_size_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(size) = select.value().parse() {
+                settings.dispatch(SettingsAction::FontSize(size));
+            }
+        }
+    };
+
+    let on_font_family_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            settings.dispatch(SettingsAction::FontFamily(select.value()));
+        }
+    };
+
+    let on_whitespace_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(mode) = WhitespaceMode::from_str(&select.value()) {
+                settings.dispatch(SettingsAction::Whitespace(mode));
+            }
+        }
+    };
+
+    let on_word_wrap_change = {
+        let settings = settings.clone();
+        move |_| settings.dispatch(SettingsAction::WordWrap(!settings.word_wrap))
+    };
+
+    let on_minimap_change = {
+        let settings = settings.clone();
+        move |_| settings.dispatch(SettingsAction::Minimap(!settings.minimap))
+    };
+
+    let on_autorun_change = {
+        let settings = settings.clone();
+        move |_| {
+            let autorun = !settings.autorun_shared;
+            settings.dispatch(SettingsAction::AutorunShared(autorun));
+        }
+    };
+
+    // the empty value turns live mode off
+    let on_live_delay_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            let delay = select.value().parse().ok();
+            settings.dispatch(SettingsAction::LiveDelay(delay));
+        }
+    };
+
+    let on_split_change = {
+        let settings = settings.clone();
+        move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            if let Some(split) = SplitOrientation::from_str(&select.value()) {
+                settings.dispatch(SettingsAction::Split(split));
+            }
+        }
+    };
+
+    let on_swap_panes_change = {
+        let settings = settings.clone();
+        move |_| settings.dispatch(SettingsAction::SwapPanes(!settings.swap_panes))
+    };
+
+    let on_collab_name_input = {
+        let settings = settings.clone();
+        move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            settings.dispatch(SettingsAction::CollabName(input.value()));
+        }
+    };
+
+    html! {
+        <div class="absolute left-3 right-3 sm:left-auto top-20 z-10 sm:w-64 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-language")}
+                <select onchange={on_locale_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    <option value="" selected={settings.locale.is_none()}>{i18n.t("settings-language-browser")}</option>
+                    { for Locale::ALL.iter().map(|locale| html! {
+                        <option value={locale.tag()} lang={locale.tag()} selected={settings.locale == Some(*locale)}>{locale.name()}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-tab-width")}
+                <select onchange={on_tab_size_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for TAB_SIZES.iter().map(|size| html! {
+                        <option value={size.to_string()} selected={*size == settings.tab_size}>{size}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-theme")}
+                <select onchange={on_theme_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for ThemePreference::ALL.iter().map(|theme| html! {
+                        <option value={theme.as_str()} selected={*theme == settings.theme}>{theme.label(i18n)}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-colorblind")}
+                <input type="checkbox" checked={settings.colorblind_diagnostics} onchange={on_colorblind_change} />
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-keybindings")}
+                <select onchange={on_keybindings_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for KeybindingMode::ALL.iter().map(|mode| html! {
+                        <option value={mode.as_str()} selected={*mode == settings.keybindings}>{mode.label(i18n)}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-font-size")}
+                <select onchange={on_font_size_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for FONT_SIZES.iter().map(|size| html! {
+                        <option value={size.to_string()} selected={*size == settings.font_size}>{size}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-font")}
+                <select onchange={on_font_family_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for FONT_FAMILIES.iter().map(|(family, label)| html! {
+                        <option value={*family} selected={*family == settings.font_family}>{i18n.t(label)}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-whitespace")}
+                <select onchange={on_whitespace_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for WhitespaceMode::ALL.iter().map(|mode| html! {
+                        <option value={mode.as_str()} selected={*mode == settings.whitespace}>{mode.label(i18n)}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-word-wrap")}
+                <input type="checkbox" checked={settings.word_wrap} onchange={on_word_wrap_change} />
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-minimap")}
+                <input type="checkbox" checked={settings.minimap} onchange={on_minimap_change} />
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-split")}
+                <select onchange={on_split_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    { for SplitOrientation::ALL.iter().map(|split| html! {
+                        <option value={split.as_str()} selected={*split == settings.split}>{split.label(i18n)}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-swap-panes")}
+                <input type="checkbox" checked={settings.swap_panes} onchange={on_swap_panes_change} />
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-autorun-shared")}
+                <input type="checkbox" checked={settings.autorun_shared} onchange={on_autorun_change} />
+            </label>
+            <label class="flex justify-between items-center">
+                {i18n.t("settings-live")}
+                <select onchange={on_live_delay_change} class="bg-gray-200 dark:bg-gray-700 rounded p-1">
+                    <option value="" selected={settings.live_delay_ms.is_none()}>{i18n.t("settings-live-off")}</option>
+                    { for LIVE_DELAYS_MS.iter().map(|delay| html! {
+                        <option value={delay.to_string()} selected={settings.live_delay_ms == Some(*delay)}>{i18n.t_args("settings-live-delay", &[("ms", delay)])}</option>
+                    }) }
+                </select>
+            </label>
+            <label class="flex justify-between items-center gap-2">
+                {i18n.t("settings-collab-name")}
+                <input value={settings.collab_name.clone()} oninput={on_collab_name_input} placeholder={i18n.t("collab-guest")} maxlength={MAX_COLLAB_NAME_LEN.to_string()} class="w-28 bg-gray-200 dark:bg-gray-700 rounded p-1" />
+            </label>
+        </div>
+    }
+}
@@ -0,0 +1,82 @@
+use crate::i18n::I18n;
+use crate::utils::query::{Metadata, MAX_TAGS};
+use web_sys::{HtmlElement, HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct ShareModalProps {
+    /// What the fields start out with, the snippet's current title, description and tags.
+    pub metadata: Metadata,
+    pub onshare: Callback<Metadata>,
+    pub onclose: Callback<()>,
+}
+
+/// Asks for a title, a short description and tags before sharing, all can be left blank.
+#[function_component]
+pub fn ShareModal(props: &ShareModalProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let title_ref = use_node_ref();
+    let description_ref = use_node_ref();
+    let tags_ref = use_node_ref();
+    {
+        let title_ref = title_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(input) = title_ref.cast::<HtmlElement>() {
+                    let _ = input.focus();
+                }
+            },
+            (),
+        );
+    }
+
+    let onclose = props.onclose.reform(|_: MouseEvent| ());
+    let onkeydown = {
+        let onclose = props.onclose.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                onclose.emit(());
+            }
+        }
+    };
+    let onsubmit = {
+        let (title_ref, description_ref) = (title_ref.clone(), description_ref.clone());
+        let tags_ref = tags_ref.clone();
+        let onshare = props.onshare.clone();
+        move |e: SubmitEvent| {
+            e.prevent_default();
+            let title = title_ref.cast::<HtmlInputElement>().unwrap().value();
+            let description = description_ref
+                .cast::<HtmlTextAreaElement>()
+                .unwrap()
+                .value();
+            let tags = tags_ref.cast::<HtmlInputElement>().unwrap().value();
+            onshare.emit(Metadata::new(&title, &description, &tags));
+        }
+    };
+    let field_classes = "bg-gray-200 dark:bg-gray-700 rounded p-1";
+
+    html! {
+        <div onclick={onclose.clone()} {onkeydown} class="fixed inset-0 z-20 bg-black/50 flex items-center justify-center">
+            <form {onsubmit} onclick={|e: MouseEvent| e.stop_propagation()} role="dialog" aria-modal="true" aria-labelledby="share-heading" class="w-96 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+                <h2 id="share-heading" class="text-lg">{i18n.t("share")}</h2>
+                <label class="flex flex-col gap-1">
+                    {i18n.t("share-title")}
+                    <input ref={title_ref} type="text" maxlength="100" value={props.metadata.title.clone().unwrap_or_default()} class={field_classes} />
+                </label>
+                <label class="flex flex-col gap-1">
+                    {i18n.t("share-description")}
+                    <textarea ref={description_ref} maxlength="300" rows="3" value={props.metadata.description.clone().unwrap_or_default()} class={field_classes} />
+                </label>
+                <label class="flex flex-col gap-1">
+                    {i18n.t("share-tags")}
+                    <input ref={tags_ref} type="text" placeholder={i18n.t_args("share-tags-placeholder", &[("count", &MAX_TAGS)])} value={props.metadata.tags.join(", ")} class={field_classes} />
+                </label>
+                <div class="flex gap-2 justify-end">
+                    <button type="button" onclick={onclose} class="p-2 rounded-md hover:bg-gray-200 dark:hover:bg-gray-700">{i18n.t("cancel")}</button>
+                    <button type="submit" class="p-2 bg-gray-200 dark:bg-gray-700 rounded-md hover:bg-gray-300 dark:hover:bg-gray-900">{i18n.t("share")}</button>
+                </div>
+            </form>
+        </div>
+    }
+}
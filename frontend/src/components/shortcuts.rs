@@ -0,0 +1,67 @@
+use crate::i18n::I18n;
+use crate::keymap::BINDINGS;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+/// Monaco's own as message ids of the description and the keys, the last one is what
+/// keyboard users need to get out of the editor.
+const EDITOR_BINDINGS: &[(&str, &str)] = &[
+    ("shortcut-find", "shortcut-find-keys"),
+    ("shortcut-replace", "shortcut-replace-keys"),
+    ("shortcut-tab-focus", "shortcut-tab-focus-keys"),
+];
+
+#[derive(PartialEq, Properties)]
+pub struct ShortcutsModalProps {
+    pub onclose: Callback<()>,
+}
+
+/// Cheat-sheet listing the keyboard shortcuts.
+#[function_component]
+pub fn ShortcutsModal(props: &ShortcutsModalProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let onclose = props.onclose.reform(|_: MouseEvent| ());
+    let onkeydown = {
+        let onclose = props.onclose.clone();
+        move |e: KeyboardEvent| {
+            if e.key() == "Escape" {
+                onclose.emit(());
+            }
+        }
+    };
+    let close_ref = use_node_ref();
+    {
+        let close_ref = close_ref.clone();
+        use_effect_with_deps(
+            move |_| {
+                if let Some(button) = close_ref.cast::<HtmlElement>() {
+                    let _ = button.focus();
+                }
+            },
+            (),
+        );
+    }
+
+    html! {
+        <div onclick={onclose.clone()} {onkeydown} class="fixed inset-0 z-20 bg-black/50 flex items-center justify-center">
+            <div onclick={|e: MouseEvent| e.stop_propagation()} role="dialog" aria-modal="true" aria-labelledby="shortcuts-heading" class="w-96 p-4 bg-gray-100 dark:bg-gray-800 rounded-md shadow-lg flex flex-col gap-3">
+                <h2 id="shortcuts-heading" class="text-lg">{i18n.t("shortcuts")}</h2>
+                <table>
+                    { for BINDINGS.iter().map(|binding| html! {
+                        <tr>
+                            <td class="py-1">{i18n.t(binding.description)}</td>
+                            <td class="py-1 text-right"><kbd class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-0.5">{binding.label(i18n)}</kbd></td>
+                        </tr>
+                    }) }
+                    { for EDITOR_BINDINGS.iter().map(|(description, keys)| html! {
+                        <tr>
+                            <td class="py-1">{i18n.t(description)}</td>
+                            <td class="py-1 text-right"><kbd class="bg-gray-200 dark:bg-gray-700 rounded px-2 py-0.5">{i18n.t(keys)}</kbd></td>
+                        </tr>
+                    }) }
+                </table>
+                <button ref={close_ref} onclick={onclose} class="self-end p-2 bg-gray-200 dark:bg-gray-700 rounded-md hover:bg-gray-300 dark:hover:bg-gray-900">{i18n.t("close")}</button>
+            </div>
+        </div>
+    }
+}
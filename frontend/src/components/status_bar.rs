@@ -0,0 +1,38 @@
+use crate::components::editor::Cursor;
+use crate::i18n::I18n;
+use common::BuildOptions;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct StatusBarProps {
+    pub cursor: Cursor,
+    /// The active file.
+    pub path: AttrValue,
+    pub options: BuildOptions,
+    /// Clicking the build options shown opens the drawer to change them.
+    pub onoptionsclick: Callback<()>,
+}
+
+/// Bar under the editor with where the cursor is and what the snippet is built with.
+#[function_component]
+pub fn StatusBar(props: &StatusBarProps) -> Html {
+    let i18n = use_context::<I18n>().unwrap();
+    let cursor = props.cursor;
+    let edition = props.options.edition.as_str();
+    let yew = props.options.yew.as_str();
+    let onclick = props.onoptionsclick.reform(|_: MouseEvent| ());
+
+    html! {
+        <div class="px-3 py-1 flex flex-wrap gap-x-4 text-xs bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300">
+            <span>{i18n.t_args("status-cursor", &[("line", &cursor.line), ("column", &cursor.column)])}</span>
+            if cursor.selected > 0 {
+                <span>{i18n.t_args("status-selected", &[("count", &cursor.selected)])}</span>
+            }
+            <span class="font-mono">{&props.path}</span>
+            <button {onclick} title={i18n.t("status-change-options")} class="ml-auto flex gap-4 hover:underline">
+                <span>{i18n.t_args("status-edition", &[("edition", &edition)])}</span>
+                <span>{i18n.t_args("status-yew", &[("version", &yew)])}</span>
+            </button>
+        </div>
+    }
+}
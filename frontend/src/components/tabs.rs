@@ -0,0 +1,202 @@
+use crate::components::toast::{self, ToastContext, ToastKind};
+use crate::files::{self, HEAD, MAIN, STYLE};
+use crate::i18n::I18n;
+use gloo::dialogs::{confirm, prompt};
+use std::collections::BTreeSet;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct FileTabsProps {
+    pub paths: Vec<String>,
+    pub active: String,
+    /// Files changed since the last run, marked with a dot.
+    pub dirty: BTreeSet<String>,
+    pub onselect: Callback<String>,
+    pub oncreate: Callback<String>,
+    /// Called with the old and the new path.
+    pub onrename: Callback<(String, String)>,
+    pub ondelete: Callback<String>,
+    /// Label of a read-only tab after the files, which is the active one while it's open.
+    #[prop_or_default]
+    pub viewer: Option<AttrValue>,
+    #[prop_or_default]
+    pub onviewerclose: Callback<()>,
+}
+
+/// Asks for a file name and turns it into a path that isn't taken yet.
+fn ask_path(
+    message: &str,
+    default: Option<&str>,
+    paths: &[String],
+    i18n: I18n,
+) -> Option<Result<String, String>> {
+    let name = prompt(&i18n.t(message), default)?;
+    let path = match files::module_path(&name) {
+        Ok(path) => path,
+        Err(e) => return Some(Err(i18n.t(e))),
+    };
+    if paths.contains(&path) {
+        let name = files::file_name(&path);
+        return Some(Err(i18n.t_args("files-exists", &[("name", &name)])));
+    }
+    Some(Ok(path))
+}
+
+/// Tab bar switching between the files of the snippet. `main.rs` is always there,
+/// the module files next to it can be added, renamed with a double-click and deleted.
+/// The files of the preview's page can be added and deleted as well, but keep their names.
+#[function_component]
+pub fn FileTabs(props: &FileTabsProps) -> Html {
+    let toasts = use_context::<ToastContext>().unwrap();
+    let i18n = use_context::<I18n>().unwrap();
+
+    let on_create_click = {
+        let paths = props.paths.clone();
+        let oncreate = props.oncreate.clone();
+        let toasts = toasts.clone();
+        move |_| match ask_path("files-new-name", Some("module.rs"), &paths, i18n) {
+            Some(Ok(path)) => oncreate.emit(path),
+            Some(Err(e)) => toast::show(&toasts, ToastKind::Error, e),
+            None => {}
+        }
+    };
+
+    let on_style_click = props.oncreate.reform(|_| STYLE.to_string());
+    let on_head_click = props.oncreate.reform(|_| HEAD.to_string());
+
+    let rename = {
+        let paths = props.paths.clone();
+        let onrename = props.onrename.clone();
+        let toasts = toasts.clone();
+        move |path: &str, name: &str| {
+            if path == MAIN || files::is_page_file(path) {
+                return;
+            }
+            match ask_path("files-rename-name", Some(name), &paths, i18n) {
+                Some(Ok(new_path)) => onrename.emit((path.to_string(), new_path)),
+                Some(Err(e)) => toast::show(&toasts, ToastKind::Error, e),
+                None => {}
+            }
+        }
+    };
+    let delete = {
+        let ondelete = props.ondelete.clone();
+        move |path: &str, name: &str| {
+            if path != MAIN && confirm(&i18n.t_args("files-delete-confirm", &[("name", &name)])) {
+                ondelete.emit(path.to_string());
+            }
+        }
+    };
+
+    let tab = |(index, path): (usize, &String)| {
+        let name = files::file_name(path).to_string();
+        let active = props.viewer.is_none() && *path == props.active;
+        let onclick = {
+            let onselect = props.onselect.clone();
+            let path = path.clone();
+            move |_| onselect.emit(path.clone())
+        };
+        let ondblclick = {
+            let rename = rename.clone();
+            let (path, name) = (path.clone(), name.clone());
+            move |_| rename(&path, &name)
+        };
+        // arrow keys move between the tabs, F2 renames and Delete deletes like in file managers
+        let onkeydown = {
+            let paths = props.paths.clone();
+            let onselect = props.onselect.clone();
+            let (rename, delete) = (rename.clone(), delete.clone());
+            let (path, name) = (path.clone(), name.clone());
+            move |e: KeyboardEvent| {
+                let target = match e.key().as_str() {
+                    "ArrowLeft" => index.checked_sub(1),
+                    "ArrowRight" => Some(index + 1).filter(|it| *it < paths.len()),
+                    "Home" => Some(0),
+                    "End" => Some(paths.len() - 1),
+                    "F2" => {
+                        rename(&path, &name);
+                        None
+                    }
+                    "Delete" => {
+                        delete(&path, &name);
+                        None
+                    }
+                    _ => return,
+                };
+                e.prevent_default();
+                let Some(target) = target else {
+                    return;
+                };
+                onselect.emit(paths[target].clone());
+                let tab = e
+                    .target_unchecked_into::<HtmlElement>()
+                    .parent_element()
+                    .and_then(|list| list.children().item(target as u32))
+                    .and_then(|it| it.dyn_into::<HtmlElement>().ok());
+                if let Some(tab) = tab {
+                    let _ = tab.focus();
+                }
+            }
+        };
+        let on_delete_click = {
+            let delete = delete.clone();
+            let (path, name) = (path.clone(), name.clone());
+            move |e: MouseEvent| {
+                e.stop_propagation();
+                delete(&path, &name);
+            }
+        };
+        let colors = if active {
+            "bg-white dark:bg-gray-900"
+        } else {
+            "hover:bg-gray-300 dark:hover:bg-gray-700"
+        };
+
+        // only the active tab is in the focus order, the arrow keys reach the others
+        html! {
+            <div {onclick} {ondblclick} {onkeydown} tabindex={if active { "0" } else { "-1" }} title={(path != MAIN && !files::is_page_file(path)).then(|| i18n.t("files-rename-hint"))} role="tab" aria-selected={active.to_string()} aria-controls="editor" class={classes!("flex", "items-center", "gap-1", "px-3", "py-1", "rounded-t", "cursor-pointer", colors)}>
+                {&name}
+                if props.dirty.contains(path) {
+                    <span title={i18n.t("files-changed")} aria-label={i18n.t("files-changed")} class="w-2 h-2 rounded-full bg-gray-500 dark:bg-gray-400" />
+                }
+                if path != MAIN {
+                    <button onclick={on_delete_click} title={i18n.t("files-delete")} aria-label={i18n.t_args("files-delete-named", &[("name", &name)])} tabindex="-1" class="px-1 rounded hover:bg-gray-400 dark:hover:bg-gray-600">{"×"}</button>
+                }
+            </div>
+        }
+    };
+
+    let viewer = props.viewer.as_ref().map(|label| {
+        let onclose = props.onviewerclose.reform(|e: MouseEvent| e.stop_propagation());
+        let onkeydown = {
+            let onclose = props.onviewerclose.clone();
+            move |e: KeyboardEvent| {
+                if matches!(e.key().as_str(), "Escape" | "Delete") {
+                    onclose.emit(());
+                }
+            }
+        };
+        html! {
+            <div {onkeydown} tabindex="0" role="tab" aria-selected="true" aria-controls="editor" class="flex items-center gap-1 px-3 py-1 rounded-t italic bg-white dark:bg-gray-900">
+                {label}
+                <button onclick={onclose} title={i18n.t("close")} aria-label={i18n.t_args("files-close-named", &[("name", label)])} tabindex="-1" class="px-1 rounded hover:bg-gray-400 dark:hover:bg-gray-600">{"×"}</button>
+            </div>
+        }
+    });
+
+    html! {
+        <div role="tablist" aria-label={i18n.t("files")} class="flex items-end gap-1 px-2 pt-1 text-sm bg-gray-200 dark:bg-gray-800 overflow-x-auto">
+            { for props.paths.iter().enumerate().map(tab) }
+            {viewer}
+            <button onclick={on_create_click} title={i18n.t("files-new")} aria-label={i18n.t("files-new")} class="px-2 py-1 rounded hover:bg-gray-300 dark:hover:bg-gray-700">{"+"}</button>
+            if !props.paths.iter().any(|it| it == STYLE) {
+                <button onclick={on_style_click} title={i18n.t("files-new-style")} aria-label={i18n.t("files-new-style")} class="px-2 py-1 rounded hover:bg-gray-300 dark:hover:bg-gray-700">{i18n.t("files-style")}</button>
+            }
+            if !props.paths.iter().any(|it| it == HEAD) {
+                <button onclick={on_head_click} title={i18n.t("files-new-head")} aria-label={i18n.t("files-new-head")} class="px-2 py-1 rounded hover:bg-gray-300 dark:hover:bg-gray-700">{i18n.t("files-head")}</button>
+            }
+        </div>
+    }
+}
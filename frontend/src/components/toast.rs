@@ -0,0 +1,122 @@
+use gloo::timers::callback::Timeout;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// How long a toast stays on screen.
+const TOAST_DURATION_MS: u32 = 3000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Success,
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    id: usize,
+    kind: ToastKind,
+    message: AttrValue,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Toasts {
+    next_id: usize,
+    toasts: Vec<Toast>,
+}
+
+pub enum ToastAction {
+    Show(ToastKind, AttrValue),
+    Dismiss(usize),
+}
+
+impl Reducible for Toasts {
+    type Action = ToastAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut toasts = self.toasts.clone();
+        let mut next_id = self.next_id;
+        match action {
+            ToastAction::Show(kind, message) => {
+                toasts.push(Toast {
+                    id: next_id,
+                    kind,
+                    message,
+                });
+                next_id += 1;
+            }
+            ToastAction::Dismiss(id) => toasts.retain(|it| it.id != id),
+        }
+        Self { next_id, toasts }.into()
+    }
+}
+
+pub type ToastContext = UseReducerHandle<Toasts>;
+
+/// Shows a toast from anywhere below [`ToastProvider`].
+pub fn show(toasts: &ToastContext, kind: ToastKind, message: impl Into<AttrValue>) {
+    toasts.dispatch(ToastAction::Show(kind, message.into()));
+}
+
+#[derive(PartialEq, Properties)]
+struct ToastItemProps {
+    toast: Toast,
+    ondismiss: Callback<usize>,
+}
+
+#[function_component]
+fn ToastItem(props: &ToastItemProps) -> Html {
+    {
+        let ondismiss = props.ondismiss.clone();
+        use_effect_with_deps(
+            move |id| {
+                let id = *id;
+                let timeout = Timeout::new(TOAST_DURATION_MS, move || ondismiss.emit(id));
+                move || drop(timeout)
+            },
+            props.toast.id,
+        );
+    }
+
+    let colors = match props.toast.kind {
+        ToastKind::Success => "bg-green-200 dark:bg-green-800",
+        ToastKind::Info => "bg-blue-200 dark:bg-blue-800",
+        ToastKind::Error => "bg-red-200 dark:bg-red-800",
+    };
+    let onclick = {
+        let ondismiss = props.ondismiss.clone();
+        let id = props.toast.id;
+        move |_| ondismiss.emit(id)
+    };
+    html! {
+        <div {onclick} role="status" class={classes!("p-3", "rounded-md", "shadow-lg", "cursor-pointer", colors)}>
+            {props.toast.message.clone()}
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ToastProviderProps {
+    pub children: Children,
+}
+
+/// Provides the [`ToastContext`] and shows the toasts in the bottom right corner.
+#[function_component]
+pub fn ToastProvider(props: &ToastProviderProps) -> Html {
+    let toasts = use_reducer_eq(Toasts::default);
+    let ondismiss = {
+        let toasts = toasts.clone();
+        Callback::from(move |id| toasts.dispatch(ToastAction::Dismiss(id)))
+    };
+
+    html! {
+        <ContextProvider<ToastContext> context={toasts.clone()}>
+            { for props.children.iter() }
+            <div class="fixed bottom-3 right-3 z-30 flex flex-col gap-2">
+                { for toasts.toasts.iter().map(|toast| html! {
+                    <ToastItem key={toast.id} toast={toast.clone()} ondismiss={ondismiss.clone()} />
+                }) }
+            </div>
+        </ContextProvider<ToastContext>>
+    }
+}
@@ -0,0 +1,75 @@
+//! The `[dependencies]` entries the dependency picker puts together, kept in the
+//! build options as TOML so they travel with shared links.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+}
+
+impl Dependency {
+    fn to_toml(&self) -> String {
+        if self.features.is_empty() {
+            return format!("{} = \"{}\"", self.name, self.version);
+        }
+        let features = self
+            .features
+            .iter()
+            .map(|it| format!("\"{}\"", it))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "{} = {{ version = \"{}\", features = [{}] }}",
+            self.name, self.version, features
+        )
+    }
+}
+
+/// One entry per line, `None` if there are none.
+pub fn to_toml(dependencies: &[Dependency]) -> Option<String> {
+    if dependencies.is_empty() {
+        return None;
+    }
+    let entries = dependencies.iter().map(Dependency::to_toml);
+    Some(entries.collect::<Vec<_>>().join("\n"))
+}
+
+/// The quoted string after `key =` in `text`.
+fn quoted_after<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let rest = text.split_once(key)?.1.trim_start().strip_prefix('=')?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    Some(&rest[..rest.find('"')?])
+}
+
+/// Reads back what [`to_toml`] wrote, skipping entries in any other shape.
+pub fn parse(toml: &str) -> Vec<Dependency> {
+    toml.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim();
+            let (version, features) = match value.strip_prefix('{') {
+                Some(table) => {
+                    let version = quoted_after(table, "version")?;
+                    let features = table
+                        .split_once("features")
+                        .and_then(|(_, it)| it.split_once('[')?.1.split_once(']'))
+                        .map(|(list, _)| {
+                            list.split(',')
+                                .map(|it| it.trim().trim_matches('"').to_string())
+                                .filter(|it| !it.is_empty())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    (version, features)
+                }
+                None => (value.strip_prefix('"')?.strip_suffix('"')?, Vec::new()),
+            };
+            Some(Dependency {
+                name: name.trim().to_string(),
+                version: version.to_string(),
+                features,
+            })
+        })
+        .collect()
+}
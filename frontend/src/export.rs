@@ -0,0 +1,70 @@
+//! The snippet as a cargo project, to keep working on it locally with `trunk serve`.
+
+use anyhow::Result;
+use common::BuildOptions;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Directory the project is unpacked into.
+const PROJECT_DIR: &str = "yew-app";
+
+const INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Yew App</title>
+</head>
+<body></body>
+</html>
+"#;
+
+/// The manifest, with the dependencies the playground offers every snippet.
+fn cargo_toml(options: &BuildOptions) -> String {
+    let edition = options.edition.as_str();
+    let yew = options.yew.as_str();
+    format!(
+        r#"[package]
+name = "{PROJECT_DIR}"
+version = "0.1.0"
+edition = "{edition}"
+
+[dependencies]
+yew = {{ version = "{yew}", features = ["csr"] }}
+wasm-bindgen = "0.2"
+web-sys = "0.3"
+js-sys = "0.3"
+wasm-bindgen-futures = "0.4"
+gloo = "0.8"
+serde = {{ version = "1", features = ["derive"] }}
+serde_json = "1"
+anyhow = "1"
+{dependencies}
+"#,
+        // the ones picked in the playground
+        dependencies = options.dependencies.as_deref().unwrap_or_default()
+    )
+}
+
+/// Zips the files up along with a manifest and the `index.html` trunk needs.
+pub fn project_zip(files: &BTreeMap<String, String>, options: &BuildOptions) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let file_options = FileOptions::default().compression_method(CompressionMethod::Stored);
+    let manifest = cargo_toml(options);
+    let entries = [
+        ("Cargo.toml", manifest.as_str()),
+        ("index.html", INDEX_HTML),
+    ]
+    .into_iter()
+    .chain(
+        files
+            .iter()
+            .map(|(path, code)| (path.as_str(), code.as_str())),
+    );
+    for (path, contents) in entries {
+        zip.start_file(format!("{}/{}", PROJECT_DIR, path), file_options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
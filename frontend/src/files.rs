@@ -0,0 +1,121 @@
+//! The files of a multi-file snippet, laid out the way the backend expects them.
+
+use common::BuildOptions;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+/// The crate root, which every snippet has and which can't be renamed or deleted.
+pub const MAIN: &str = "src/main.rs";
+/// CSS the backend styles the preview's page with. Like `main.rs` it keeps its name,
+/// but it can be added and deleted.
+pub const STYLE: &str = "src/style.css";
+/// Markup the backend adds to the head of the preview's page once it sanitized it, such
+/// as meta tags, style sheets of CDNs or an element to mount on. Kept the same as `STYLE`.
+pub const HEAD: &str = "src/head.html";
+/// The files of the preview's page rather than of the crate, with the language of each.
+const PAGE_FILES: &[(&str, &str)] = &[(STYLE, "css"), (HEAD, "html")];
+
+/// Whether `path` is one of the files of the preview's page, which aren't Rust.
+pub fn is_page_file(path: &str) -> bool {
+    PAGE_FILES.iter().any(|(it, _)| *it == path)
+}
+
+/// The language the file at `path` is edited as.
+pub fn language(path: &str) -> &'static str {
+    PAGE_FILES
+        .iter()
+        .find(|(it, _)| *it == path)
+        .map_or("rust", |(_, language)| language)
+}
+
+/// Name of the file at `path` as shown on its tab.
+pub fn file_name(path: &str) -> &str {
+    path.strip_prefix("src/").unwrap_or(path)
+}
+
+/// Turns a file name entered by the user into the path of a module file, or the
+/// message id of why it can't be one.
+pub fn module_path(name: &str) -> Result<String, &'static str> {
+    let name = name.trim();
+    let stem = name.strip_suffix(".rs").unwrap_or(name);
+    let valid = stem
+        .chars()
+        .next()
+        .is_some_and(|it| it.is_ascii_lowercase() || it == '_')
+        && stem
+            .chars()
+            .all(|it| it.is_ascii_lowercase() || it.is_ascii_digit() || it == '_');
+    if !valid {
+        return Err("files-invalid-name");
+    }
+    if stem == "main" || stem == "lib" {
+        return Err("files-reserved-name");
+    }
+    Ok(format!("src/{}.rs", stem))
+}
+
+/// The current contents of every file, kept outside of component state so typing
+/// doesn't re-render the whole app. Compared by identity.
+#[derive(Clone, Default)]
+pub struct FileContents(Rc<RefCell<BTreeMap<String, String>>>);
+
+impl PartialEq for FileContents {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl FileContents {
+    pub fn get(&self, path: &str) -> Option<String> {
+        self.0.borrow().get(path).cloned()
+    }
+
+    pub fn set(&self, path: &str, contents: String) {
+        self.0.borrow_mut().insert(path.to_string(), contents);
+    }
+
+    pub fn remove(&self, path: &str) -> Option<String> {
+        self.0.borrow_mut().remove(path)
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, String> {
+        self.0.borrow().clone()
+    }
+}
+
+/// The files sent to be run and the options to build them with. The files are
+/// compared by identity, so running the same code twice builds it again.
+#[derive(Clone)]
+pub struct RunRequest {
+    pub files: Rc<BTreeMap<String, String>>,
+    pub options: BuildOptions,
+    /// Re-renders to benchmark the snippet's `App` with instead of just running it.
+    pub bench: Option<u32>,
+}
+
+impl PartialEq for RunRequest {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.files, &other.files)
+            && self.options == other.options
+            && self.bench == other.bench
+    }
+}
+
+/// Paths of the files that changed since they were last run. Nothing counts as
+/// changed before the first run.
+pub fn changed_since(
+    last_run: &BTreeMap<String, String>,
+    contents: &FileContents,
+) -> BTreeSet<String> {
+    if last_run.is_empty() {
+        return BTreeSet::new();
+    }
+    contents
+        .0
+        .borrow()
+        .iter()
+        .filter(|(path, code)| last_run.get(*path) != Some(*code))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
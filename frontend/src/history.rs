@@ -0,0 +1,126 @@
+//! Earlier versions of each snippet, kept in IndexedDB so they survive reloads.
+
+use crate::utils::fragment;
+use crate::utils::query::Query;
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// How many revisions are kept per snippet, the oldest ones are dropped first.
+const MAX_REVISIONS: u32 = 50;
+
+#[wasm_bindgen(inline_js = r#"
+const STORE = "revisions";
+
+function request(req) {
+    return new Promise((resolve, reject) => {
+        req.onsuccess = () => resolve(req.result);
+        req.onerror = () => reject(req.error);
+    });
+}
+
+function openDb() {
+    const req = indexedDB.open("yew-playground", 1);
+    req.onupgradeneeded = () => {
+        const store = req.result.createObjectStore(STORE, { keyPath: "id", autoIncrement: true });
+        store.createIndex("snippet", "snippet");
+    };
+    return request(req);
+}
+
+export async function addRevision(snippet, code, time, limit) {
+    const db = await openDb();
+    const tx = db.transaction(STORE, "readwrite");
+    const store = tx.objectStore(STORE);
+    const keys = await request(store.index("snippet").getAllKeys(snippet));
+    const last = keys.length ? await request(store.get(keys[keys.length - 1])) : undefined;
+    if (last && last.code === code) {
+        return;
+    }
+    store.add({ snippet, code, time });
+    for (const key of keys.slice(0, Math.max(0, keys.length + 1 - limit))) {
+        store.delete(key);
+    }
+    await new Promise((resolve, reject) => {
+        tx.oncomplete = resolve;
+        tx.onerror = () => reject(tx.error);
+    });
+}
+
+export async function listRevisions(snippet) {
+    const db = await openDb();
+    const store = db.transaction(STORE).objectStore(STORE);
+    return JSON.stringify(await request(store.index("snippet").getAll(snippet)));
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = addRevision)]
+    fn add_revision(snippet: &str, code: &str, time: f64, limit: u32) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = listRevisions)]
+    fn list_revisions(snippet: &str) -> js_sys::Promise;
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Revision {
+    pub id: u64,
+    pub code: String,
+    /// Milliseconds since the epoch.
+    pub time: f64,
+}
+
+/// Key of the code the playground opens with when the link doesn't open any.
+pub const SCRATCH: &str = "scratch";
+
+/// Identifies the snippet the link opened, revisions of different snippets are
+/// kept apart.
+pub fn snippet_key(query: Option<&Query>, fragment: &str) -> String {
+    let hashed = |kind: &str, code: &str| {
+        let mut hasher = DefaultHasher::new();
+        code.hash(&mut hasher);
+        format!("{}:{:016x}", kind, hasher.finish())
+    };
+    if fragment::holds_code(fragment) {
+        return hashed("fragment", fragment);
+    }
+    let Some(query) = query else {
+        return SCRATCH.to_string();
+    };
+    if let Some(id) = &query.shared {
+        format!("shared:{}", id)
+    } else if let Some(id) = &query.gist {
+        format!("gist:{}", id)
+    } else if let Some(id) = &query.example {
+        format!("example:{}", id)
+    } else if let Some(code) = &query.code {
+        hashed("code", code)
+    } else {
+        SCRATCH.to_string()
+    }
+}
+
+/// Adds the code as the snippet's newest revision, unless it's the same as the
+/// last one.
+pub async fn save(snippet: &str, code: &str) -> Result<()> {
+    let promise = add_revision(snippet, code, js_sys::Date::now(), MAX_REVISIONS);
+    JsFuture::from(promise)
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?;
+    Ok(())
+}
+
+/// The snippet's revisions, newest first.
+pub async fn list(snippet: &str) -> Result<Vec<Revision>> {
+    let json = JsFuture::from(list_revisions(snippet))
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?;
+    let json = json
+        .as_string()
+        .ok_or_else(|| anyhow!("revisions aren't JSON"))?;
+    let mut revisions = serde_json::from_str::<Vec<Revision>>(&json)?;
+    revisions.reverse();
+    Ok(revisions)
+}
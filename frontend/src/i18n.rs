@@ -0,0 +1,190 @@
+//! Translations of the UI strings. Every locale is a Fluent file under `locales/`, of
+//! which the subset of plain messages with `{ $name }` variables is supported. Messages
+//! a locale lacks fall back to the English ones.
+
+use crate::settings::SettingsContext;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use yew::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    En,
+    De,
+}
+
+impl Locale {
+    pub const ALL: &'static [Self] = &[Self::En, Self::De];
+
+    /// BCP 47 tag, as put on the page's `lang`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+
+    /// Name of the language in itself, how it's listed in the switcher.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::De => "Deutsch",
+        }
+    }
+
+    /// Matches on the language only, `de-AT` is German.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split('-').next()?;
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|it| it.tag().eq_ignore_ascii_case(language))
+    }
+
+    /// The first of the browser's preferred languages there's a translation for.
+    pub fn detect() -> Self {
+        gloo::utils::window()
+            .navigator()
+            .languages()
+            .iter()
+            .filter_map(|it| it.as_string())
+            .find_map(|it| Self::from_tag(&it))
+            .unwrap_or(Self::En)
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::En => include_str!("../locales/en.ftl"),
+            Self::De => include_str!("../locales/de.ftl"),
+        }
+    }
+}
+
+/// The messages of a Fluent file by id. Indented lines continue the message above.
+fn parse(source: &str) -> HashMap<String, String> {
+    let mut messages = HashMap::<String, String>::new();
+    let mut last = None::<String>;
+    for line in source.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            last = None;
+            continue;
+        }
+        if line.starts_with(' ') {
+            if let Some(value) = last.as_ref().and_then(|id| messages.get_mut(id)) {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(line.trim());
+                continue;
+            }
+        }
+        match line.split_once('=') {
+            Some((id, value)) => {
+                let id = id.trim().to_string();
+                messages.insert(id.clone(), value.trim().to_string());
+                last = Some(id);
+            }
+            None => tracing::warn!(line, "skipping a line that isn't a message"),
+        }
+    }
+    messages
+}
+
+/// Fills in the `{ $name }` placeables of `message`. String literals like `{ "{" }`
+/// stand for themselves, unknown variables are left as they are.
+fn format(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut formatted = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        formatted.push_str(&rest[..start]);
+        let placeable = &rest[start..=start + len];
+        let expression = placeable[1..placeable.len() - 1].trim();
+        let literal = expression
+            .strip_prefix('"')
+            .and_then(|it| it.strip_suffix('"'));
+        let arg = expression
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(it, _)| *it == name));
+        match (literal, arg) {
+            (Some(literal), _) => formatted.push_str(literal),
+            (None, Some((_, value))) => formatted.push_str(&value.to_string()),
+            (None, None) => formatted.push_str(placeable),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+thread_local! {
+    static BUNDLES: HashMap<Locale, HashMap<String, String>> = Locale::ALL
+        .iter()
+        .map(|it| (*it, parse(it.source())))
+        .collect();
+}
+
+/// Looks up the UI strings in the locale in effect, provided as a context by the
+/// root component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct I18n {
+    locale: Locale,
+}
+
+impl I18n {
+    pub fn locale(self) -> Locale {
+        self.locale
+    }
+
+    /// The message with the given id. Missing ones show up as their id.
+    pub fn t(self, id: &str) -> String {
+        self.t_args(id, &[])
+    }
+
+    /// The message with the given id, with its variables filled in from `args`.
+    pub fn t_args(self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        BUNDLES.with(|bundles| {
+            let message = bundles[&self.locale]
+                .get(id)
+                .or_else(|| bundles[&Locale::En].get(id));
+            match message {
+                Some(message) => format(message, args),
+                None => {
+                    tracing::warn!(id, "missing message");
+                    id.to_string()
+                }
+            }
+        })
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct I18nProviderProps {
+    pub children: Children,
+}
+
+/// Provides the [`I18n`] of the language picked in the settings, or of the browser's
+/// if none was, and keeps the page's `lang` in line with it.
+#[function_component]
+pub fn I18nProvider(props: &I18nProviderProps) -> Html {
+    let settings = use_context::<SettingsContext>().unwrap();
+    let locale = settings.locale.unwrap_or_else(Locale::detect);
+    use_effect_with_deps(
+        |locale| {
+            if let Some(root) = gloo::utils::document().document_element() {
+                let _ = root.set_attribute("lang", locale.tag());
+            }
+        },
+        locale,
+    );
+
+    html! {
+        <ContextProvider<I18n> context={I18n { locale }}>
+            { for props.children.iter() }
+        </ContextProvider<I18n>>
+    }
+}
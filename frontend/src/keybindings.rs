@@ -0,0 +1,88 @@
+//! Vim and Emacs emulation for the editor, provided by `monaco-vim` and
+//! `monaco-emacs`. Both are only downloaded once a mode is picked.
+
+use crate::settings::KeybindingMode;
+use js_sys::Promise;
+use monaco::sys::editor::IStandaloneCodeEditor;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlElement;
+use yew::prelude::*;
+
+#[wasm_bindgen(inline_js = r#"
+export async function enableVim(editor, statusbar) {
+    const { initVimMode } = await import("monaco-vim");
+    return initVimMode(editor, statusbar);
+}
+
+export async function enableEmacs(editor) {
+    const { EmacsExtension } = await import("monaco-emacs");
+    const emacs = new EmacsExtension(editor);
+    emacs.start();
+    return emacs;
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = enableVim)]
+    fn enable_vim(editor: &IStandaloneCodeEditor, statusbar: &HtmlElement) -> Promise;
+
+    #[wasm_bindgen(js_name = enableEmacs)]
+    fn enable_emacs(editor: &IStandaloneCodeEditor) -> Promise;
+
+    /// What `monaco-vim` and `monaco-emacs` return for an editor they took over.
+    type Emulation;
+
+    #[wasm_bindgen(method)]
+    fn dispose(this: &Emulation);
+}
+
+/// Puts the editor in the given mode for as long as it's selected. Vim reports its
+/// mode and pending commands in `statusbar`.
+#[hook]
+pub fn use_keybindings(
+    editor: Option<&IStandaloneCodeEditor>,
+    mode: KeybindingMode,
+    statusbar: NodeRef,
+) {
+    use_effect_with_deps(
+        move |(editor, mode)| {
+            let editor = editor
+                .as_ref()
+                .map(|it| it.unchecked_ref::<IStandaloneCodeEditor>());
+            // set to `None` once the mode was switched again, possibly before it was enabled
+            let active = Rc::new(RefCell::new(Some(None::<Emulation>)));
+            let promise = match (editor, mode) {
+                (Some(editor), KeybindingMode::Vim) => statusbar
+                    .cast::<HtmlElement>()
+                    .map(|statusbar| enable_vim(editor, &statusbar)),
+                (Some(editor), KeybindingMode::Emacs) => Some(enable_emacs(editor)),
+                _ => None,
+            };
+            if let Some(promise) = promise {
+                let active = active.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let emulation = match JsFuture::from(promise).await {
+                        Ok(emulation) => emulation.unchecked_into::<Emulation>(),
+                        Err(e) => {
+                            tracing::warn!(?e, "failed to enable key bindings");
+                            return;
+                        }
+                    };
+                    match &mut *active.borrow_mut() {
+                        Some(slot) => *slot = Some(emulation),
+                        None => emulation.dispose(),
+                    }
+                });
+            }
+            move || {
+                if let Some(Some(emulation)) = active.borrow_mut().take() {
+                    emulation.dispose();
+                }
+            }
+        },
+        // compared by identity, the editor's bindings don't implement `PartialEq`
+        (editor.map(|it| AsRef::<JsValue>::as_ref(it).clone()), mode),
+    );
+}
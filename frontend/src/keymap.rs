@@ -0,0 +1,206 @@
+use crate::i18n::I18n;
+use gloo::events::{EventListener, EventListenerOptions, EventListenerPhase};
+use wasm_bindgen::JsCast;
+use web_sys::KeyboardEvent;
+use yew::prelude::*;
+
+/// Everything the command palette offers, some of it bound to keys as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Run,
+    Share,
+    Format,
+    ShowShortcuts,
+    CommandPalette,
+    Clippy,
+    Expand,
+    Download,
+    Fork,
+    CopyMarkdown,
+    Collaborate,
+    ShowChanges,
+    CompareVersions,
+    ToggleBenchmark,
+    ExportImage,
+    ToggleTheme,
+    ToggleSplit,
+    SwapPanes,
+    OpenExamples,
+    SearchSnippets,
+    OpenSettings,
+    OpenBuildOptions,
+    OpenDependencies,
+    OpenHistory,
+    OpenRevisions,
+}
+
+impl Action {
+    /// In the order the command palette lists them, it doesn't list itself.
+    pub const ALL: &'static [Self] = &[
+        Self::Run,
+        Self::Format,
+        Self::Clippy,
+        Self::Expand,
+        Self::ShowChanges,
+        Self::CompareVersions,
+        Self::ToggleBenchmark,
+        Self::Share,
+        Self::Fork,
+        Self::CopyMarkdown,
+        Self::Collaborate,
+        Self::Download,
+        Self::ExportImage,
+        Self::OpenExamples,
+        Self::SearchSnippets,
+        Self::ToggleTheme,
+        Self::ToggleSplit,
+        Self::SwapPanes,
+        Self::OpenSettings,
+        Self::OpenBuildOptions,
+        Self::OpenDependencies,
+        Self::OpenHistory,
+        Self::OpenRevisions,
+        Self::ShowShortcuts,
+    ];
+
+    /// Message id of its name in the command palette.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Run => "command-run",
+            Self::Share => "command-share",
+            Self::Format => "command-format",
+            Self::ShowShortcuts => "command-show-shortcuts",
+            Self::CommandPalette => "command-palette",
+            Self::Clippy => "command-clippy",
+            Self::Expand => "command-expand",
+            Self::Download => "command-download",
+            Self::Fork => "command-fork",
+            Self::CopyMarkdown => "command-copy-markdown",
+            Self::Collaborate => "command-collaborate",
+            Self::ShowChanges => "command-show-changes",
+            Self::CompareVersions => "command-compare-versions",
+            Self::ToggleBenchmark => "command-toggle-benchmark",
+            Self::ExportImage => "command-export-image",
+            Self::ToggleTheme => "command-toggle-theme",
+            Self::ToggleSplit => "command-toggle-split",
+            Self::SwapPanes => "command-swap-panes",
+            Self::OpenExamples => "command-open-examples",
+            Self::SearchSnippets => "command-search-snippets",
+            Self::OpenSettings => "command-open-settings",
+            Self::OpenBuildOptions => "command-open-build-options",
+            Self::OpenDependencies => "command-open-dependencies",
+            Self::OpenHistory => "command-open-history",
+            Self::OpenRevisions => "command-open-revisions",
+        }
+    }
+
+    /// The first of the [`BINDINGS`] for it, if it has any.
+    pub fn binding(self) -> Option<&'static Binding> {
+        BINDINGS.iter().find(|it| it.action == self)
+    }
+}
+
+pub struct Binding {
+    pub action: Action,
+    /// Value of `KeyboardEvent.key`, compared case-insensitively.
+    pub key: &'static str,
+    pub shift: bool,
+    /// Message id of what it does.
+    pub description: &'static str,
+}
+
+impl Binding {
+    /// Label shown in the cheat-sheet, Ctrl stands for Cmd on macOS as well.
+    pub fn label(&self, i18n: I18n) -> String {
+        let mut label = i18n.t("key-ctrl");
+        if self.shift {
+            label.push_str(" + ");
+            label.push_str(&i18n.t("key-shift"));
+        }
+        label.push_str(" + ");
+        label.push_str(&self.key.to_uppercase());
+        label
+    }
+
+    fn matches(&self, event: &KeyboardEvent) -> bool {
+        (event.ctrl_key() || event.meta_key())
+            && event.shift_key() == self.shift
+            && event.key().eq_ignore_ascii_case(self.key)
+    }
+}
+
+/// Every binding takes Ctrl, or Cmd on macOS.
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        action: Action::Run,
+        key: "Enter",
+        shift: false,
+        description: "shortcut-run",
+    },
+    Binding {
+        action: Action::Share,
+        key: "s",
+        shift: false,
+        description: "shortcut-share",
+    },
+    Binding {
+        action: Action::Format,
+        key: "f",
+        shift: true,
+        description: "shortcut-format",
+    },
+    Binding {
+        action: Action::ShowShortcuts,
+        key: "/",
+        shift: false,
+        description: "shortcut-show-shortcuts",
+    },
+    Binding {
+        action: Action::CommandPalette,
+        key: "k",
+        shift: false,
+        description: "shortcut-command-palette",
+    },
+    // what VS Code opens it with
+    Binding {
+        action: Action::CommandPalette,
+        key: "p",
+        shift: true,
+        description: "shortcut-command-palette",
+    },
+];
+
+/// Calls `on_action` whenever one of the [`BINDINGS`] is pressed anywhere on the
+/// page.
+///
+/// The listener runs in the capture phase so it sees the keys before the editor
+/// does, which would otherwise swallow the ones it has bindings of its own for.
+#[hook]
+pub fn use_keymap(on_action: Callback<Action>) {
+    use_effect_with_deps(
+        |on_action| {
+            let on_action = on_action.clone();
+            let options = EventListenerOptions {
+                phase: EventListenerPhase::Capture,
+                passive: false,
+            };
+            let listener = EventListener::new_with_options(
+                &gloo::utils::window(),
+                "keydown",
+                options,
+                move |event| {
+                    let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                        return;
+                    };
+                    if let Some(binding) = BINDINGS.iter().find(|it| it.matches(event)) {
+                        event.prevent_default();
+                        event.stop_propagation();
+                        on_action.emit(binding.action);
+                    }
+                },
+            );
+            move || drop(listener)
+        },
+        on_action,
+    );
+}
@@ -1,7 +1,20 @@
 mod api;
 mod app;
+mod collab;
 mod components;
+mod dependencies;
+mod export;
+mod files;
+mod history;
+mod i18n;
+mod keybindings;
+mod keymap;
 mod macros;
+mod owned;
+mod recent;
+mod session;
+mod settings;
+mod theme;
 mod utils;
 use tracing_subscriber::fmt::format::{FmtSpan, Pretty};
 use tracing_subscriber::fmt::time::UtcTime;
@@ -9,7 +22,14 @@ use tracing_subscriber::prelude::*;
 use tracing_web::{performance_layer, MakeConsoleWriter};
 
 use app::App;
+use components::deep_link::NewSnippet;
+use components::examples::ExamplesPage;
+use components::search::SearchPage;
+use components::toast::ToastProvider;
+use i18n::I18nProvider;
+use settings::{Settings, SettingsContext};
 use std::rc::Rc;
+use theme::ThemeProvider;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
@@ -40,16 +60,48 @@ impl Reducible for ActionButtonStateReducible {
 
 pub type ActionButtonStateContext = UseReducerHandle<ActionButtonStateReducible>;
 
+#[derive(Debug, Clone, PartialEq, Eq, Routable)]
+pub enum Route {
+    #[not_found]
+    #[at("/")]
+    Home,
+    #[at("/examples")]
+    Examples,
+    #[at("/search")]
+    Search,
+    /// A [deep link](utils::deep_link), opening a snippet made up from its parameters.
+    #[at("/new")]
+    New,
+}
+
+fn switch(route: Route) -> Html {
+    match route {
+        Route::Home => html! { <App /> },
+        Route::Examples => html! { <ExamplesPage /> },
+        Route::Search => html! { <SearchPage /> },
+        Route::New => html! { <NewSnippet /> },
+    }
+}
+
 #[function_component]
 fn Root() -> Html {
     let msg = use_reducer_eq(|| ActionButtonStateReducible {
         state: ActionButtonState::Enabled,
     });
+    let settings = use_reducer_eq(Settings::load);
 
     html! {
         <BrowserRouter>
             <ContextProvider<ActionButtonStateContext> context={msg}>
-                <App />
+                <ContextProvider<SettingsContext> context={settings}>
+                    <I18nProvider>
+                        <ThemeProvider>
+                            <ToastProvider>
+                                <Switch<Route> render={switch} />
+                            </ToastProvider>
+                        </ThemeProvider>
+                    </I18nProvider>
+                </ContextProvider<SettingsContext>>
             </ContextProvider<ActionButtonStateContext>>
         </BrowserRouter>
     }
@@ -68,5 +120,6 @@ fn main() {
         .with(perf_layer)
         .init();
 
+    utils::service_worker::register();
     yew::Renderer::<Root>::new().render();
 }
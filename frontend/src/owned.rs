@@ -0,0 +1,25 @@
+//! The pastes shared from this browser, with the tokens that prove it. Their revisions
+//! can be saved from here, there are no accounts to tell who shared a paste otherwise.
+
+use std::collections::HashMap;
+
+use gloo::storage::{LocalStorage, Storage};
+
+const STORAGE_KEY: &str = "yew-playground.owner-tokens";
+
+fn load() -> HashMap<String, String> {
+    LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+}
+
+/// The owner token of the paste, if it was shared from here.
+pub fn token(paste: &str) -> Option<String> {
+    load().remove(paste)
+}
+
+pub fn add(paste: &str, token: &str) {
+    let mut tokens = load();
+    tokens.insert(paste.to_string(), token.to_string());
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, tokens) {
+        tracing::warn!(?e, "failed to remember a shared paste");
+    }
+}
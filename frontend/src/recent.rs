@@ -0,0 +1,122 @@
+//! The snippets recently opened or edited in this browser, kept in IndexedDB for the
+//! "Recent" menu. There are no accounts, so it's the only list of them there is.
+
+use crate::history;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+/// How many snippets are kept, the ones touched longest ago are dropped first.
+const MAX_SNIPPETS: u32 = 20;
+
+// a database of its own rather than a store in the history's, so neither has to know
+// about the other's stores when upgrading
+#[wasm_bindgen(inline_js = r#"
+const STORE = "snippets";
+
+function request(req) {
+    return new Promise((resolve, reject) => {
+        req.onsuccess = () => resolve(req.result);
+        req.onerror = () => reject(req.error);
+    });
+}
+
+function openDb() {
+    const req = indexedDB.open("yew-playground-recent", 1);
+    req.onupgradeneeded = () => {
+        const store = req.result.createObjectStore(STORE, { keyPath: "snippet" });
+        store.createIndex("time", "time");
+    };
+    return request(req);
+}
+
+export async function touchSnippet(json, limit) {
+    const snippet = JSON.parse(json);
+    const db = await openDb();
+    const tx = db.transaction(STORE, "readwrite");
+    const store = tx.objectStore(STORE);
+    // edits don't know the title, it's kept from when the snippet was opened
+    const old = await request(store.get(snippet.snippet));
+    store.put({ ...snippet, title: snippet.title ?? old?.title ?? null });
+    const keys = await request(store.index("time").getAllKeys());
+    for (const key of keys.slice(0, Math.max(0, keys.length - limit))) {
+        store.delete(key);
+    }
+    await new Promise((resolve, reject) => {
+        tx.oncomplete = resolve;
+        tx.onerror = () => reject(tx.error);
+    });
+}
+
+export async function listSnippets() {
+    const db = await openDb();
+    const store = db.transaction(STORE).objectStore(STORE);
+    return JSON.stringify(await request(store.index("time").getAll()));
+}
+"#)]
+extern "C" {
+    #[wasm_bindgen(js_name = touchSnippet)]
+    fn touch_snippet(json: &str, limit: u32) -> js_sys::Promise;
+
+    #[wasm_bindgen(js_name = listSnippets)]
+    fn list_snippets() -> js_sys::Promise;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentSnippet {
+    /// The snippet's key in the history, see `history::snippet_key`.
+    pub snippet: String,
+    /// The link it was opened with, relative to the playground.
+    pub href: String,
+    /// `None` when it has none, or touching it didn't know it.
+    pub title: Option<String>,
+    /// Milliseconds since the epoch.
+    pub time: f64,
+}
+
+/// Moves the snippet to the top of the list, or adds it there.
+pub async fn touch(snippet: &RecentSnippet) -> Result<()> {
+    let json = serde_json::to_string(snippet)?;
+    JsFuture::from(touch_snippet(&json, MAX_SNIPPETS))
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?;
+    Ok(())
+}
+
+/// The snippets, most recently touched first.
+pub async fn list() -> Result<Vec<RecentSnippet>> {
+    let json = JsFuture::from(list_snippets())
+        .await
+        .map_err(|e| anyhow!("{:?}", e))?;
+    let json = json
+        .as_string()
+        .ok_or_else(|| anyhow!("recent snippets aren't JSON"))?;
+    let mut snippets = serde_json::from_str::<Vec<RecentSnippet>>(&json)?;
+    snippets.reverse();
+    Ok(snippets)
+}
+
+/// Touches the snippet opened by the current URL in the background. The scratch
+/// snippet is left out, no link opens it.
+pub fn remember(snippet: String, title: Option<String>) {
+    if snippet == history::SCRATCH {
+        return;
+    }
+    let location = gloo::utils::window().location();
+    let href = [location.pathname(), location.search(), location.hash()]
+        .into_iter()
+        .collect::<std::result::Result<String, _>>()
+        .unwrap_or_else(|_| "/".to_string());
+    let snippet = RecentSnippet {
+        snippet,
+        href,
+        title,
+        time: js_sys::Date::now(),
+    };
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = touch(&snippet).await {
+            tracing::warn!(?e, "failed to remember a recent snippet");
+        }
+    });
+}
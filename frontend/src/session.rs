@@ -0,0 +1,26 @@
+//! The code being edited, saved so a reload doesn't lose it.
+
+use crate::components::editor::BASE_CONTENT;
+use gloo::storage::{LocalStorage, Storage};
+
+const STORAGE_KEY: &str = "yew-playground.session";
+
+/// How long the editor has to be idle before the code is saved.
+pub const AUTOSAVE_DELAY_MS: u32 = 1000;
+
+/// The saved code, unless it's empty or the untouched starter code.
+pub fn load() -> Option<String> {
+    LocalStorage::get::<String>(STORAGE_KEY)
+        .ok()
+        .filter(|it| !it.trim().is_empty() && it.trim() != BASE_CONTENT.trim())
+}
+
+pub fn save(code: &str) {
+    if let Err(e) = LocalStorage::set(STORAGE_KEY, code) {
+        tracing::warn!(?e, "failed to save session");
+    }
+}
+
+pub fn clear() {
+    LocalStorage::delete(STORAGE_KEY);
+}
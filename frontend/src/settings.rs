@@ -0,0 +1,303 @@
+use crate::i18n::{I18n, Locale};
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+use yew::prelude::*;
+
+const STORAGE_KEY: &str = "yew-playground.settings";
+
+/// Tab widths the settings panel offers.
+pub const TAB_SIZES: &[u32] = &[2, 4, 8];
+/// Editor font sizes the settings panel offers, in pixels.
+pub const FONT_SIZES: &[u32] = &[12, 14, 16, 18, 20, 24];
+/// Editor fonts as CSS `font-family` and label, the empty one is Monaco's. Fonts that
+/// aren't installed fall back to the system's monospace one. Labels are message ids,
+/// the fonts' names aren't translated.
+pub const FONT_FAMILIES: &[(&str, &str)] = &[
+    ("", "font-default"),
+    ("'Fira Code', monospace", "Fira Code"),
+    ("'JetBrains Mono', monospace", "JetBrains Mono"),
+    ("'Cascadia Code', monospace", "Cascadia Code"),
+    ("monospace", "font-system-monospace"),
+];
+/// Pauses in typing after which live mode runs the code, in milliseconds.
+pub const LIVE_DELAYS_MS: &[u32] = &[300, 500, 1000, 2000];
+/// Of the name shown to the others in a room, in characters.
+pub const MAX_COLLAB_NAME_LEN: usize = 32;
+
+/// Colour scheme the user picked. `System` follows `prefers-color-scheme`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemePreference {
+    pub const ALL: &'static [Self] = &[Self::System, Self::Light, Self::Dark, Self::HighContrast];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn label(self, i18n: I18n) -> String {
+        i18n.t(match self {
+            Self::System => "theme-system",
+            Self::Light => "theme-light",
+            Self::Dark => "theme-dark",
+            Self::HighContrast => "theme-high-contrast",
+        })
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|it| it.as_str() == value)
+    }
+}
+
+/// Key bindings of the editor, on top of Monaco's own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeybindingMode {
+    #[default]
+    Default,
+    Vim,
+    Emacs,
+}
+
+impl KeybindingMode {
+    pub const ALL: &'static [Self] = &[Self::Default, Self::Vim, Self::Emacs];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Vim => "vim",
+            Self::Emacs => "emacs",
+        }
+    }
+
+    pub fn label(self, i18n: I18n) -> String {
+        match self {
+            Self::Default => i18n.t("keybindings-default"),
+            Self::Vim => "Vim".to_string(),
+            Self::Emacs => "Emacs".to_string(),
+        }
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|it| it.as_str() == value)
+    }
+}
+
+/// Which whitespace characters the editor draws.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhitespaceMode {
+    None,
+    /// Only in the selected text.
+    #[default]
+    Selection,
+    /// All but single spaces between words.
+    Boundary,
+    All,
+}
+
+impl WhitespaceMode {
+    pub const ALL: &'static [Self] = &[Self::None, Self::Selection, Self::Boundary, Self::All];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Selection => "selection",
+            Self::Boundary => "boundary",
+            Self::All => "all",
+        }
+    }
+
+    pub fn label(self, i18n: I18n) -> String {
+        i18n.t(match self {
+            Self::None => "whitespace-none",
+            Self::Selection => "whitespace-selection",
+            Self::Boundary => "whitespace-boundary",
+            Self::All => "whitespace-all",
+        })
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|it| it.as_str() == value)
+    }
+}
+
+/// How the editor and the output share the page on wide screens, narrow ones always
+/// stack them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitOrientation {
+    #[default]
+    SideBySide,
+    Stacked,
+}
+
+impl SplitOrientation {
+    pub const ALL: &'static [Self] = &[Self::SideBySide, Self::Stacked];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SideBySide => "side_by_side",
+            Self::Stacked => "stacked",
+        }
+    }
+
+    pub fn label(self, i18n: I18n) -> String {
+        i18n.t(match self {
+            Self::SideBySide => "split-side-by-side",
+            Self::Stacked => "split-stacked",
+        })
+    }
+
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|it| it.as_str() == value)
+    }
+
+    /// The other one.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::SideBySide => Self::Stacked,
+            Self::Stacked => Self::SideBySide,
+        }
+    }
+}
+
+/// User preferences, persisted in local storage so they survive reloads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub tab_size: u32,
+    pub theme: ThemePreference,
+    /// Tell the diagnostics apart by shape as well as colour, in colours that stay
+    /// distinct with colour blindness.
+    pub colorblind_diagnostics: bool,
+    pub keybindings: KeybindingMode,
+    /// Share of the split pane taken by the editor, in percent.
+    pub editor_size: f64,
+    /// Run the code of shared links as soon as it's opened, as if they had `autorun`.
+    pub autorun_shared: bool,
+    /// In pixels.
+    pub font_size: u32,
+    /// One of [`FONT_FAMILIES`].
+    pub font_family: String,
+    pub word_wrap: bool,
+    pub whitespace: WhitespaceMode,
+    pub minimap: bool,
+    /// Language of the UI, `None` follows the browser's.
+    pub locale: Option<Locale>,
+    /// What the others in a room see, empty for a generic one.
+    pub collab_name: String,
+    /// Once the code ran, run it again this long after the typing stops, one of
+    /// [`LIVE_DELAYS_MS`]. `None` leaves running to the user.
+    pub live_delay_ms: Option<u32>,
+    pub split: SplitOrientation,
+    /// Put the output before the editor, on the left or at the top.
+    pub swap_panes: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            tab_size: 4,
+            theme: ThemePreference::default(),
+            colorblind_diagnostics: false,
+            keybindings: KeybindingMode::default(),
+            editor_size: 50.0,
+            autorun_shared: false,
+            font_size: 14,
+            font_family: String::new(),
+            word_wrap: false,
+            whitespace: WhitespaceMode::default(),
+            minimap: true,
+            locale: None,
+            collab_name: String::new(),
+            live_delay_ms: None,
+            split: SplitOrientation::default(),
+            swap_panes: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    /// The name the user goes by with others, a generic one if they didn't pick any.
+    pub fn display_name(&self, i18n: I18n) -> String {
+        match self.collab_name.trim() {
+            "" => i18n.t("collab-guest"),
+            name => name.chars().take(MAX_COLLAB_NAME_LEN).collect(),
+        }
+    }
+
+    fn save(&self) {
+        if let Err(e) = LocalStorage::set(STORAGE_KEY, self) {
+            tracing::warn!(?e, "failed to persist settings");
+        }
+    }
+}
+
+pub enum SettingsAction {
+    TabSize(u32),
+    Theme(ThemePreference),
+    ColorblindDiagnostics(bool),
+    Keybindings(KeybindingMode),
+    EditorSize(f64),
+    AutorunShared(bool),
+    FontSize(u32),
+    FontFamily(String),
+    WordWrap(bool),
+    Whitespace(WhitespaceMode),
+    Minimap(bool),
+    Locale(Option<Locale>),
+    CollabName(String),
+    LiveDelay(Option<u32>),
+    Split(SplitOrientation),
+    SwapPanes(bool),
+}
+
+impl Reducible for Settings {
+    type Action = SettingsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let mut settings = (*self).clone();
+        match action {
+            SettingsAction::TabSize(tab_size) => settings.tab_size = tab_size,
+            SettingsAction::Theme(theme) => settings.theme = theme,
+            SettingsAction::ColorblindDiagnostics(colorblind) => {
+                settings.colorblind_diagnostics = colorblind
+            }
+            SettingsAction::Keybindings(mode) => settings.keybindings = mode,
+            SettingsAction::EditorSize(size) => settings.editor_size = size,
+            SettingsAction::AutorunShared(autorun) => settings.autorun_shared = autorun,
+            SettingsAction::FontSize(size) => settings.font_size = size,
+            SettingsAction::FontFamily(family) => settings.font_family = family,
+            SettingsAction::WordWrap(wrap) => settings.word_wrap = wrap,
+            SettingsAction::Whitespace(mode) => settings.whitespace = mode,
+            SettingsAction::Minimap(minimap) => settings.minimap = minimap,
+            SettingsAction::Locale(locale) => settings.locale = locale,
+            SettingsAction::CollabName(name) => settings.collab_name = name,
+            SettingsAction::LiveDelay(delay) => settings.live_delay_ms = delay,
+            SettingsAction::Split(split) => settings.split = split,
+            SettingsAction::SwapPanes(swap) => settings.swap_panes = swap,
+        }
+        settings.save();
+        settings.into()
+    }
+}
+
+pub type SettingsContext = UseReducerHandle<Settings>;
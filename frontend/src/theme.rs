@@ -0,0 +1,135 @@
+use crate::settings::{SettingsContext, ThemePreference};
+use crate::utils::media::use_media_query;
+use js_sys::Object;
+use monaco::sys::editor::{BuiltinTheme, IStandaloneThemeData};
+use std::cell::Cell;
+use wasm_bindgen::JsCast;
+use yew::prelude::*;
+
+/// Diagnostics colours of the colourblind-safe palette, from Okabe and Ito's, with
+/// errors also underlined twice so they don't rely on the colour alone.
+const COLORBLIND_COLORS: &[(&str, &str)] = &[
+    ("editorError.foreground", "#d55e00"),
+    ("editorError.border", "#d55e00"),
+    ("editorWarning.foreground", "#e69f00"),
+    ("editorInfo.foreground", "#56b4e9"),
+];
+
+thread_local! {
+    static COLORBLIND_THEMES_DEFINED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Name of the editor theme with the colourblind-safe diagnostics on top of `base`.
+fn colorblind_theme_name(base: BuiltinTheme) -> String {
+    format!("{}-colorblind", base.to_value())
+}
+
+/// Monaco only knows its built-in themes, the colourblind variants of them are defined
+/// the first time one is asked for.
+fn define_colorblind_themes() {
+    if COLORBLIND_THEMES_DEFINED.with(|it| it.replace(true)) {
+        return;
+    }
+    let colors = Object::new();
+    for (key, color) in COLORBLIND_COLORS {
+        let _ = js_sys::Reflect::set(&colors, &(*key).into(), &(*color).into());
+    }
+    for base in [
+        BuiltinTheme::Vs,
+        BuiltinTheme::VsDark,
+        BuiltinTheme::HcBlack,
+    ] {
+        let data: IStandaloneThemeData = Object::new().unchecked_into();
+        data.set_base(base);
+        data.set_inherit(true);
+        data.set_rules(&js_sys::Array::new());
+        data.set_colors(&colors);
+        monaco::sys::editor::define_theme(&colorblind_theme_name(base), &data).unwrap();
+    }
+}
+
+/// The colour scheme in effect, provided as a context by the root component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+    /// The dark theme in black and white, with outlines around the controls.
+    HighContrast,
+}
+
+impl Theme {
+    /// Name of Monaco's theme for it, `colorblind` swaps in the colourblind-safe
+    /// diagnostics.
+    pub fn editor_theme(self, colorblind: bool) -> String {
+        let base = match self {
+            Self::Light => BuiltinTheme::Vs,
+            Self::Dark => BuiltinTheme::VsDark,
+            Self::HighContrast => BuiltinTheme::HcBlack,
+        };
+        if colorblind {
+            define_colorblind_themes();
+            colorblind_theme_name(base)
+        } else {
+            base.to_value().to_string()
+        }
+    }
+
+    /// High contrast goes to light, like dark.
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark | Self::HighContrast => Self::Light,
+        }
+    }
+
+    /// Whether Tailwind's `dark` variants apply.
+    pub fn is_dark(self) -> bool {
+        self != Self::Light
+    }
+}
+
+/// Resolves the theme preference in the settings and applies it to the page by
+/// toggling Tailwind's `dark` class on the root element, along with the
+/// `high-contrast` and `colorblind` ones the global styles key off.
+#[hook]
+fn use_theme() -> Theme {
+    let settings = use_context::<SettingsContext>().unwrap();
+    // browsers that can't tell keep the dark theme the playground always had
+    let prefers_dark = use_media_query("(prefers-color-scheme: dark)", true);
+    let theme = match settings.theme {
+        ThemePreference::System if prefers_dark => Theme::Dark,
+        ThemePreference::System => Theme::Light,
+        ThemePreference::Light => Theme::Light,
+        ThemePreference::Dark => Theme::Dark,
+        ThemePreference::HighContrast => Theme::HighContrast,
+    };
+
+    use_effect_with_deps(
+        |(theme, colorblind)| {
+            if let Some(root) = gloo::utils::document().document_element() {
+                let classes = root.class_list();
+                let _ = classes.toggle_with_force("dark", theme.is_dark());
+                let _ = classes.toggle_with_force("high-contrast", *theme == Theme::HighContrast);
+                let _ = classes.toggle_with_force("colorblind", *colorblind);
+            }
+        },
+        (theme, settings.colorblind_diagnostics),
+    );
+    theme
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ThemeProviderProps {
+    pub children: Children,
+}
+
+/// Provides the [`Theme`] resolved from the settings to its children.
+#[function_component]
+pub fn ThemeProvider(props: &ThemeProviderProps) -> Html {
+    let theme = use_theme();
+    html! {
+        <ContextProvider<Theme> context={theme}>
+            { for props.children.iter() }
+        </ContextProvider<Theme>>
+    }
+}
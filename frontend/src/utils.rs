@@ -1,5 +1,16 @@
+pub mod ansi;
+pub mod connection;
+pub mod deep_link;
+pub mod fragment;
+pub mod media;
 pub mod query;
+pub mod service_worker;
 
+use gloo::file::{Blob, ObjectUrl};
+use gloo::timers::callback::Timeout;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlAnchorElement;
 use yew::virtual_dom::VNode;
 use yew::Classes;
 
@@ -11,5 +22,45 @@ pub fn html_to_element(html: &str, classes: Option<Classes>) -> VNode {
         node.set_attribute("class", &classes.to_string())
             .expect("failed to set classes");
     }
+    // the icons are decoration, whatever they're on is labelled
+    node.set_attribute("aria-hidden", "true")
+        .expect("failed to hide the icon");
     VNode::VRef(node.into())
 }
+
+pub async fn copy_to_clipboard(text: &str) -> Result<(), JsValue> {
+    let clipboard = gloo::utils::window().navigator().clipboard();
+    JsFuture::from(clipboard.write_text(text)).await.map(drop)
+}
+
+/// Changes the address in the address bar without navigating, nothing on the page
+/// reacts to it.
+pub fn replace_address(url: &str) {
+    let replaced = gloo::utils::window()
+        .history()
+        .and_then(|it| it.replace_state_with_url(&JsValue::NULL, "", Some(url)));
+    if let Err(e) = replaced {
+        tracing::warn!(?e, "failed to change the address");
+    }
+}
+
+/// Makes the browser save `bytes` as a file called `name`.
+pub fn download(bytes: &[u8], name: &str, mime_type: &str) -> Result<(), JsValue> {
+    let url = ObjectUrl::from(Blob::new_with_options(bytes, Some(mime_type)));
+    download_url(&url, name)?;
+    // the download may not have started yet when `click` returns
+    Timeout::new(10_000, move || drop(url)).forget();
+    Ok(())
+}
+
+/// Makes the browser save what's at `url`, a `data:` one for example, as a file called
+/// `name`.
+pub fn download_url(url: &str, name: &str) -> Result<(), JsValue> {
+    let link: HtmlAnchorElement = gloo::utils::document()
+        .create_element("a")?
+        .unchecked_into();
+    link.set_href(url);
+    link.set_download(name);
+    link.click();
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Just enough of ANSI's SGR escape codes to render cargo's colored diagnostics.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn from_code(code: u32) -> Option<Self> {
+        let color = match code % 10 {
+            0 => Self::Black,
+            1 => Self::Red,
+            2 => Self::Green,
+            3 => Self::Yellow,
+            4 => Self::Blue,
+            5 => Self::Magenta,
+            6 => Self::Cyan,
+            7 => Self::White,
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    pub fn classes(self) -> &'static str {
+        match self {
+            Self::Black => "text-gray-500",
+            Self::Red => "text-red-600 dark:text-red-400",
+            Self::Green => "text-green-700 dark:text-green-400",
+            Self::Yellow => "text-yellow-700 dark:text-yellow-300",
+            Self::Blue => "text-blue-600 dark:text-blue-400",
+            Self::Magenta => "text-purple-600 dark:text-purple-400",
+            Self::Cyan => "text-cyan-700 dark:text-cyan-400",
+            Self::White => "text-gray-900 dark:text-white",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub color: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    fn apply(&mut self, params: &str) {
+        let mut codes = params.split(';').map(|it| it.parse::<u32>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                30..=37 | 90..=97 => self.color = Color::from_code(code),
+                39 => self.color = None,
+                // 256 colors, of which only the basic and bright ones are mapped
+                38 if codes.next() == Some(5) => {
+                    self.color = codes
+                        .next()
+                        .filter(|it| *it < 16)
+                        .and_then(|it| Color::from_code(it % 8));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Splits `text` into runs of equally styled text, dropping the escape codes.
+pub fn parse(text: &str) -> Vec<(Style, String)> {
+    let mut spans = Vec::new();
+    let mut style = Style::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' || chars.peek() != Some(&'[') {
+            current.push(c);
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut end = None;
+        for c in chars.by_ref() {
+            if ('@'..='~').contains(&c) {
+                end = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        // other control sequences, like cursor movements, are dropped
+        if end == Some('m') {
+            if !current.is_empty() {
+                spans.push((style, std::mem::take(&mut current)));
+            }
+            style.apply(&params);
+        }
+    }
+    if !current.is_empty() {
+        spans.push((style, current));
+    }
+    spans
+}
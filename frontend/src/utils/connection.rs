@@ -0,0 +1,86 @@
+use crate::api;
+use gloo::events::EventListener;
+use gloo::timers::callback::Timeout;
+use yew::prelude::*;
+
+/// How often the backend is checked while it's reachable.
+const POLL_INTERVAL_MS: u32 = 30_000;
+/// How often it's checked again while it isn't.
+const RETRY_INTERVAL_MS: u32 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connection {
+    /// The backend answered the last check, or wasn't checked yet.
+    Online,
+    /// The browser has no network.
+    Offline,
+    /// The backend didn't answer.
+    Unreachable,
+    /// The backend answered but can't reach the compiler.
+    Unavailable,
+}
+
+impl Connection {
+    /// Message id of why code can't be run, `None` if it can.
+    pub fn problem(self) -> Option<&'static str> {
+        match self {
+            Connection::Online => None,
+            Connection::Offline => Some("connection-offline"),
+            Connection::Unreachable => Some("connection-unreachable"),
+            Connection::Unavailable => Some("connection-unavailable"),
+        }
+    }
+}
+
+async fn probe() -> Connection {
+    if !gloo::utils::window().navigator().on_line() {
+        return Connection::Offline;
+    }
+    match api::client().health().await {
+        Ok(true) => Connection::Online,
+        Ok(false) => Connection::Unavailable,
+        Err(e) => {
+            tracing::warn!(?e, "failed to reach the backend");
+            Connection::Unreachable
+        }
+    }
+}
+
+/// Whether the backend can run code, checked right away, then periodically, more often
+/// while it can't, and whenever the browser goes on- or offline.
+#[hook]
+pub fn use_connection() -> Connection {
+    let connection = use_state_eq(|| Connection::Online);
+    // bumped after every check, which schedules the next one
+    let checks = use_state(|| 0_u32);
+    {
+        let deps = (*connection, *checks);
+        let (connection, checks) = (connection.clone(), checks.clone());
+        use_effect_with_deps(
+            move |(current, count)| {
+                let count = *count;
+                let check = move || {
+                    let (connection, checks) = (connection.clone(), checks.clone());
+                    wasm_bindgen_futures::spawn_local(async move {
+                        connection.set(probe().await);
+                        checks.set(count + 1);
+                    });
+                };
+                let delay = match (count, current) {
+                    (0, _) => 0,
+                    (_, Connection::Online) => POLL_INTERVAL_MS,
+                    _ => RETRY_INTERVAL_MS,
+                };
+                let timeout = Timeout::new(delay, check.clone());
+                let window = gloo::utils::window();
+                let listeners = ["online", "offline"].map(|event| {
+                    let check = check.clone();
+                    EventListener::new(&window, event, move |_| check())
+                });
+                move || drop((timeout, listeners))
+            },
+            deps,
+        );
+    }
+    *connection
+}
@@ -0,0 +1,121 @@
+//! Links that open the playground with a snippet made up from their parameters, for
+//! editors, the Yew docs and bots to build without depending on the playground's own
+//! query, which changes along with it:
+//!
+//! `/new?code=<code>&version=<version>&deps=<dependencies>&title=<title>&autorun`
+//!
+//! - `code` is the text of `src/main.rs`, percent-encoded like any parameter.
+//! - `version` is the Yew version to build with, e.g. `0.21`. Patch releases like
+//!   `0.21.0` stand for theirs, versions the playground doesn't build are ignored.
+//! - `deps` are `name@version` entries separated by commas, each with its features
+//!   after `+`, e.g. `serde@1+derive,gloo-net@0.4`. A missing version is `*`, entries
+//!   that aren't valid are left out.
+//! - `title` and `description` are shown above the code, as in shared links.
+//! - `autorun` runs the code as soon as it's loaded.
+//!
+//! Every parameter is optional, others are ignored.
+
+use super::query::{flag, Embed, Metadata, Query};
+use crate::dependencies::{self, Dependency};
+use common::{BuildOptions, YewVersion};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct DeepLink {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub deps: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "flag")]
+    pub autorun: bool,
+}
+
+/// Crate names and features are ASCII letters, digits, `-` and `_`, features may
+/// name a dependency's with `/`.
+fn is_name(name: &str, extra: &[char]) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || extra.contains(&c))
+}
+
+/// Version requirements, kept from closing the TOML string they go into.
+fn is_version(version: &str) -> bool {
+    !version.is_empty()
+        && version.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '.' | '*' | '^' | '~' | '=' | '<' | '>' | '-')
+        })
+}
+
+fn parse_dependency(entry: &str) -> Option<Dependency> {
+    // an unescaped `+` arrives as a space
+    let mut parts = entry.trim().split(['+', ' ']).filter(|it| !it.is_empty());
+    let crate_ = parts.next()?;
+    let (name, version) = crate_.split_once('@').unwrap_or((crate_, "*"));
+    let features = parts.map(str::to_string).collect::<Vec<_>>();
+    let valid =
+        is_name(name, &[]) && is_version(version) && features.iter().all(|it| is_name(it, &['/']));
+    valid.then(|| Dependency {
+        name: name.to_string(),
+        version: version.to_string(),
+        features,
+    })
+}
+
+impl DeepLink {
+    /// The Yew version the playground builds that `version` stands for.
+    fn yew_version(&self) -> Option<YewVersion> {
+        let version = self.version.as_deref()?.trim();
+        YewVersion::ALL.into_iter().find(|it| {
+            version == it.as_str()
+                || version
+                    .strip_prefix(it.as_str())
+                    .is_some_and(|patch| patch.starts_with('.'))
+        })
+    }
+
+    fn dependencies(&self) -> Vec<Dependency> {
+        self.deps
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|it| !it.trim().is_empty())
+            .filter_map(parse_dependency)
+            .collect()
+    }
+
+    /// The playground's query for the snippet, along with its code, which goes into
+    /// the fragment like the code of links shared without storing it.
+    pub fn into_query(self) -> (Query, Option<String>) {
+        let options = BuildOptions {
+            yew: self.yew_version().unwrap_or_default(),
+            dependencies: dependencies::to_toml(&self.dependencies()),
+            ..BuildOptions::default()
+        };
+        let query = Query {
+            shared: None,
+            revision: None,
+            code: None,
+            gist: None,
+            example: None,
+            room: None,
+            options,
+            embed: Embed {
+                autorun: self.autorun,
+                ..Embed::default()
+            },
+            metadata: Metadata {
+                title: self.title,
+                description: self.description,
+                tags: Vec::new(),
+            },
+        };
+        (query, self.code)
+    }
+}
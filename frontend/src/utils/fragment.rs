@@ -0,0 +1,51 @@
+//! Code kept in the URL fragment, compressed with deflate and encoded as URL-safe
+//! base64. Links like that share a snippet without storing it anywhere, and the
+//! fragment never even reaches a server.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use yew::prelude::*;
+use yew_router::hooks::use_location;
+
+const PREFIX: &str = "#code=";
+/// Longer fragments are shared through the backend instead, some browsers and chat
+/// apps cut off long links.
+pub const MAX_SHARED_LEN: usize = 2000;
+/// Decompressed code larger than this is rejected rather than inflated.
+const MAX_CODE_SIZE: usize = 1024 * 1024;
+
+/// The fragment holding `code`, including the `#`.
+pub fn encode(code: &str) -> String {
+    let compressed = compress_to_vec(code.as_bytes(), 9);
+    format!("{}{}", PREFIX, URL_SAFE_NO_PAD.encode(compressed))
+}
+
+/// Decodes the code from a fragment created by [`encode`], `None` if the fragment
+/// holds no code.
+pub fn decode(fragment: &str) -> Option<Result<String>> {
+    let encoded = fragment.strip_prefix(PREFIX)?;
+    let decoded = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .context("code is not valid base64")
+        .and_then(|it| {
+            decompress_to_vec_with_limit(&it, MAX_CODE_SIZE)
+                .map_err(|e| anyhow::anyhow!("code is not valid deflate data: {:?}", e.status))
+        })
+        .and_then(|it| String::from_utf8(it).context("code is not valid UTF-8"));
+    Some(decoded)
+}
+
+pub fn holds_code(fragment: &str) -> bool {
+    fragment.starts_with(PREFIX)
+}
+
+/// The current URL's fragment, including the `#`, or an empty string if it has none.
+#[hook]
+pub fn use_fragment() -> String {
+    use_location()
+        .map(|it| it.hash().to_string())
+        .unwrap_or_default()
+}
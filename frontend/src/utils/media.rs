@@ -0,0 +1,29 @@
+use gloo::events::EventListener;
+use web_sys::MediaQueryList;
+use yew::prelude::*;
+
+fn media_query_list(query: &str) -> Option<MediaQueryList> {
+    gloo::utils::window().match_media(query).ok().flatten()
+}
+
+/// Whether the media `query` matches, updated when that changes. Browsers that
+/// can't evaluate the query report `default`.
+#[hook]
+pub fn use_media_query(query: &'static str, default: bool) -> bool {
+    let matches = use_state_eq(|| media_query_list(query).map_or(default, |it| it.matches()));
+    {
+        let matches = matches.clone();
+        use_effect_with_deps(
+            move |query| {
+                let listener = media_query_list(query).map(|list| {
+                    EventListener::new(&list.clone(), "change", move |_| {
+                        matches.set(list.matches())
+                    })
+                });
+                move || drop(listener)
+            },
+            query,
+        );
+    }
+    *matches
+}
@@ -1,11 +1,163 @@
-use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use base64::alphabet::URL_SAFE;
+use base64::engine::general_purpose::GeneralPurpose;
+use base64::engine::{DecodePaddingMode, GeneralPurposeConfig};
+use base64::Engine;
+use common::BuildOptions;
+use serde::{Deserialize, Deserializer, Serialize};
 use yew::prelude::*;
 use yew_router::hooks::use_location;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Query {
     pub shared: Option<String>,
+    /// ID of a named revision of the `shared` paste, opened instead of the paste as it
+    /// was shared.
+    pub revision: Option<String>,
+    /// Base64 encoded code.
     pub code: Option<String>,
+    /// ID of a GitHub gist to load the code from.
+    pub gist: Option<String>,
+    /// ID of one of the backend's examples to start from.
+    pub example: Option<String>,
+    /// ID of a room of the backend to edit the code in together with others, the room
+    /// holds the code.
+    pub room: Option<String>,
+    #[serde(flatten)]
+    pub options: BuildOptions,
+    #[serde(flatten)]
+    pub embed: Embed,
+    #[serde(flatten)]
+    pub metadata: Metadata,
+}
+
+/// What the snippet is about, set before sharing it. Links holding the code carry it
+/// as parameters, stored pastes keep it along with the code.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// What the snippet is searched by, only stored pastes keep them.
+    #[serde(skip)]
+    pub tags: Vec<String>,
+}
+
+/// Per snippet.
+pub const MAX_TAGS: usize = 5;
+/// In characters.
+const MAX_TAG_LEN: usize = 30;
+
+impl Metadata {
+    /// Blank fields are left out, `tags` are separated by commas or spaces.
+    pub fn new(title: &str, description: &str, tags: &str) -> Self {
+        let field = |value: &str| Some(value.trim().to_string()).filter(|it| !it.is_empty());
+        Self {
+            title: field(title),
+            description: field(description),
+            tags: parse_tags(tags),
+        }
+    }
+
+    /// The page title, the playground's own without one.
+    pub fn page_title(&self) -> String {
+        match &self.title {
+            Some(title) => format!("{} - Yew Playground", title),
+            None => "Yew Playground".to_string(),
+        }
+    }
+
+    /// The page description, the one of `index.html` without one.
+    pub fn page_description(&self) -> &str {
+        self.description
+            .as_deref()
+            .unwrap_or("Write, run and share Yew apps in the browser.")
+    }
+}
+
+/// Tags the way they're searched for: lowercase letters, digits and dashes, the rest is
+/// dropped, each tag once and no more than `MAX_TAGS` of them.
+pub fn parse_tags(tags: &str) -> Vec<String> {
+    let mut parsed = Vec::new();
+    for tag in tags.split(|c: char| c == ',' || c.is_whitespace()) {
+        let tag = tag
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '-')
+            .take(MAX_TAG_LEN)
+            .collect::<String>();
+        if !tag.is_empty() && !parsed.contains(&tag) && parsed.len() < MAX_TAGS {
+            parsed.push(tag);
+        }
+    }
+    parsed
+}
+
+/// `?embed=1` shows just the editor and the preview, sized to the page, to put the
+/// playground in an iframe of a blog post or the docs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Embed {
+    #[serde(default, deserialize_with = "flag", skip_serializing_if = "is_false")]
+    pub embed: bool,
+    /// Only with `embed`, keeps the code from being edited.
+    #[serde(default, deserialize_with = "flag", skip_serializing_if = "is_false")]
+    pub readonly: bool,
+    /// Runs the code as soon as it's loaded, also outside of embeds.
+    #[serde(default, deserialize_with = "flag", skip_serializing_if = "is_false")]
+    pub autorun: bool,
+    /// `?present` shows just the code and the preview, read-only and in large type, for
+    /// talks and screencasts. Works outside of embeds.
+    #[serde(default, deserialize_with = "flag", skip_serializing_if = "is_false")]
+    pub present: bool,
+}
+
+impl Embed {
+    pub fn read_only(&self) -> bool {
+        (self.embed && self.readonly) || self.present
+    }
+
+    /// Whether the playground's own controls are left out.
+    pub fn chromeless(&self) -> bool {
+        self.embed || self.present
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// A parameter that's set with `1` or `true`, or by being there without a value.
+pub(super) fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    match value.as_str() {
+        "" | "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        _ => Err(serde::de::Error::custom(format!(
+            "invalid flag `{}`",
+            value
+        ))),
+    }
+}
+
+/// Decodes both the standard and the URL-safe alphabet, padded or not.
+const CODE_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+impl Query {
+    /// The code passed in the `code` parameter.
+    pub fn decoded_code(&self) -> Option<Result<String>> {
+        let code = self.code.as_ref()?;
+        // an unescaped `+` arrives as a space
+        let code = code.trim_end().replace([' ', '+'], "-").replace('/', "_");
+        let decoded = CODE_ENGINE
+            .decode(code)
+            .context("code is not valid base64")
+            .and_then(|it| String::from_utf8(it).context("code is not valid UTF-8"));
+        Some(decoded)
+    }
 }
 
 #[hook]
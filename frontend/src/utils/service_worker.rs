@@ -0,0 +1,22 @@
+//! Registers `sw.js`, which caches the app so it opens, and the last session can be
+//! picked up, without a connection.
+
+use wasm_bindgen_futures::JsFuture;
+
+pub fn register() {
+    // `trunk serve` rebuilds would be hidden behind the cached ones
+    if cfg!(debug_assertions) {
+        return;
+    }
+    let navigator = gloo::utils::window().navigator();
+    // missing outside of secure contexts
+    if !js_sys::Reflect::has(&navigator, &"serviceWorker".into()).unwrap_or(false) {
+        return;
+    }
+    let container = navigator.service_worker();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = JsFuture::from(container.register("/sw.js")).await {
+            tracing::warn!(?e, "failed to register the service worker");
+        }
+    });
+}
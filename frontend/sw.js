@@ -0,0 +1,70 @@
+// Keeps the playground's shell around so it opens offline. Runs and everything else
+// the backend does still need the network, the app tells the user so.
+
+// bumped when the caching below changes, older caches are dropped on activation
+const CACHE = "yew-playground-v1";
+// precached so there's a page to open offline even before anything was cached at runtime
+const SHELL = ["/", "/static/favicon.svg", "/static/manifest.webmanifest"];
+// the modules of the import map
+const CDN_ORIGINS = ["https://unpkg.com", "https://esm.sh"];
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(caches.open(CACHE).then((cache) => cache.addAll(SHELL)));
+    self.skipWaiting();
+});
+
+self.addEventListener("activate", (event) => {
+    event.waitUntil(
+        caches
+            .keys()
+            .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+            .then(() => self.clients.claim()),
+    );
+});
+
+async function put(request, response) {
+    if (response.ok || response.type === "opaque") {
+        const cache = await caches.open(CACHE);
+        await cache.put(request, response.clone());
+    }
+    return response;
+}
+
+// for what changes: the page, whose scripts change names with every release, and the
+// examples
+async function networkFirst(request, fallback) {
+    try {
+        return await put(request, await fetch(request));
+    } catch (e) {
+        const cached = (await caches.match(request)) || (fallback && (await caches.match(fallback)));
+        if (cached) {
+            return cached;
+        }
+        throw e;
+    }
+}
+
+// for what doesn't: the hashed scripts, styles and wasm trunk builds, and the pinned
+// versions of the CDN modules
+async function cacheFirst(request) {
+    const cached = await caches.match(request);
+    return cached || put(request, await fetch(request));
+}
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    if (request.method !== "GET") {
+        return;
+    }
+    const url = new URL(request.url);
+    if (request.mode === "navigate") {
+        // every route is the same page
+        event.respondWith(networkFirst(request, "/"));
+    } else if (url.pathname.includes("/api/examples")) {
+        event.respondWith(networkFirst(request));
+    } else if (url.pathname.includes("/api/")) {
+        // runs, shares and the rest of the backend are left alone
+    } else if (url.origin === self.location.origin || CDN_ORIGINS.includes(url.origin)) {
+        event.respondWith(cacheFirst(request));
+    }
+});
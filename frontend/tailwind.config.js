@@ -1,4 +1,5 @@
 module.exports = {
+  darkMode: "class",
   content: ["./src/**/*.rs", "./index.html"],
   theme: {
     extend: {},
@@ -0,0 +1,126 @@
+//! Command-line client of the playground, for scripting it and for reproducing issues
+//! from CI: builds local files, prints their diagnostics and shares them.
+
+mod run;
+mod share;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+use common::{BuildOptions, Edition, Profile, Snippet, Toolchain, YewVersion};
+use serde::de::{DeserializeOwned, IntoDeserializer};
+
+#[derive(Parser)]
+#[command(name = "yew-playground", version, about)]
+struct Cli {
+    /// The playground's API.
+    #[arg(
+        long,
+        env = "PLAYGROUND_API",
+        default_value = "https://api.play.yew.rs/api",
+        global = true
+    )]
+    api: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Builds a file and prints its diagnostics, exits with 1 if it doesn't compile.
+    Run(run::RunArgs),
+    /// Shares a file and prints the link to it.
+    Share(share::ShareArgs),
+}
+
+/// The files making up the snippet.
+#[derive(Args)]
+pub struct SnippetArgs {
+    /// The snippet's `src/main.rs`.
+    file: PathBuf,
+    /// Style sheet of the preview's page.
+    #[arg(long)]
+    style: Option<PathBuf>,
+    /// Markup for the head of the preview's page.
+    #[arg(long)]
+    head: Option<PathBuf>,
+}
+
+fn read(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+impl SnippetArgs {
+    pub fn read(&self) -> Result<Snippet> {
+        let mut snippet = Snippet::single(read(&self.file)?);
+        let page_files = [(Snippet::STYLE, &self.style), (Snippet::HEAD, &self.head)];
+        for (name, path) in page_files {
+            if let Some(path) = path {
+                snippet.files.insert(name.to_string(), read(path)?);
+            }
+        }
+        Ok(snippet)
+    }
+}
+
+/// Options the snippet is built with, left to the compiler's defaults when not given.
+/// The same ones the playground keeps in its links.
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Yew version to build against, e.g. `0.21`.
+    #[arg(long, value_parser = option::<YewVersion>)]
+    yew: Option<YewVersion>,
+    /// `stable`, `beta` or `nightly`.
+    #[arg(long, value_parser = option::<Toolchain>)]
+    toolchain: Option<Toolchain>,
+    /// Rust edition, e.g. `2021`.
+    #[arg(long, value_parser = option::<Edition>)]
+    edition: Option<Edition>,
+    /// `release`, `debug` or `min-size`.
+    #[arg(long, value_parser = option::<Profile>)]
+    profile: Option<Profile>,
+    /// Comma separated `RUSTFLAGS` presets, e.g. `codegen-units=1`.
+    #[arg(long)]
+    rustflags: Option<String>,
+    /// File with extra `[dependencies]` entries.
+    #[arg(long)]
+    dependencies: Option<PathBuf>,
+}
+
+/// Reads an option the way the backend does from its query.
+fn option<T: DeserializeOwned>(value: &str) -> Result<T, serde::de::value::Error> {
+    T::deserialize(value.into_deserializer())
+}
+
+impl BuildArgs {
+    pub fn options(&self) -> Result<BuildOptions> {
+        Ok(BuildOptions {
+            yew: self.yew.unwrap_or_default(),
+            toolchain: self.toolchain.unwrap_or_default(),
+            edition: self.edition.unwrap_or_default(),
+            profile: self.profile.unwrap_or_default(),
+            rustflags: self.rustflags.clone(),
+            dependencies: self.dependencies.as_deref().map(read).transpose()?,
+            ..BuildOptions::default()
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run(args) => run::run(&cli.api, args).await,
+        Command::Share(args) => share::share(&cli.api, args).await,
+    };
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            // apart from the 1 of code that doesn't compile
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use common::client::{PlaygroundClient, ReqwestTransport};
+use common::{strip_ansi, RunReport};
+
+use crate::{BuildArgs, SnippetArgs};
+
+#[derive(Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    snippet: SnippetArgs,
+    #[command(flatten)]
+    build: BuildArgs,
+    /// Open the built page in the browser.
+    #[arg(long)]
+    open: bool,
+    /// Print the diagnostics as JSON rather than cargo's output, `[]` if it compiled.
+    #[arg(long)]
+    json: bool,
+}
+
+fn kilobytes(bytes: u64) -> String {
+    format!("{:.1} kB", bytes as f64 / 1000.0)
+}
+
+/// Builds the snippet like the playground's run button does.
+pub async fn run(api: &str, args: RunArgs) -> Result<ExitCode> {
+    let snippet = args.snippet.read()?;
+    let client = PlaygroundClient::new(api, ReqwestTransport::default());
+    let report = client
+        .run_report(&snippet, &args.build.options()?)
+        .await
+        .context("the playground failed to build the snippet")?;
+
+    let (stats, build_id) = match report {
+        RunReport::Ok { stats, build_id } => (stats, build_id),
+        RunReport::Error(error) => {
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&error.diagnostics)?);
+            } else if std::io::stderr().is_terminal() {
+                // keeps the colors for terminals, logs of CI get the plain text
+                eprint!("{}", error.stderr);
+            } else {
+                eprint!("{}", strip_ansi(&error.stderr));
+            }
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    if args.json {
+        println!("[]");
+    } else {
+        eprintln!(
+            "built in {:.1} s: {} wasm ({} gzipped), {} js",
+            stats.build_ms as f64 / 1000.0,
+            kilobytes(stats.wasm_size),
+            kilobytes(stats.wasm_gzip_size),
+            kilobytes(stats.js_size),
+        );
+    }
+    if args.open {
+        let build_id = build_id.ok_or_else(|| anyhow!("the playground didn't keep the build"))?;
+        let page = client.build_page(&build_id).await?;
+        // the page has the module inlined, it runs from a file as well
+        let name = format!("yew-playground-{}.html", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, page)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        webbrowser::open(&path.to_string_lossy()).context("failed to open the browser")?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
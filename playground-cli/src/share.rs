@@ -0,0 +1,92 @@
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use common::client::{PlaygroundClient, ReqwestTransport};
+use common::NewSnippet;
+use reqwest::Url;
+
+use crate::{BuildArgs, SnippetArgs};
+
+/// The most tags a snippet has, the playground refuses more.
+const MAX_TAGS: usize = 5;
+/// In characters.
+const MAX_TAG_LEN: usize = 30;
+
+#[derive(Args)]
+pub struct ShareArgs {
+    #[command(flatten)]
+    snippet: SnippetArgs,
+    #[command(flatten)]
+    build: BuildArgs,
+    #[arg(long)]
+    title: Option<String>,
+    #[arg(long)]
+    description: Option<String>,
+    /// Separated by commas or spaces, e.g. `router,hooks`.
+    #[arg(long, default_value = "")]
+    tags: String,
+    /// The playground the link opens.
+    #[arg(long, env = "PLAYGROUND_URL", default_value = "https://play.yew.rs")]
+    playground: String,
+    /// Open the link in the browser as well.
+    #[arg(long)]
+    open: bool,
+}
+
+/// Lower case letters, digits and dashes, the same as the playground's share dialog
+/// keeps of them.
+fn parse_tags(tags: &str) -> Vec<String> {
+    let mut parsed = Vec::new();
+    for tag in tags.split(|c: char| c == ',' || c.is_whitespace()) {
+        let tag = tag
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '-')
+            .take(MAX_TAG_LEN)
+            .collect::<String>();
+        if !tag.is_empty() && !parsed.contains(&tag) && parsed.len() < MAX_TAGS {
+            parsed.push(tag);
+        }
+    }
+    parsed
+}
+
+/// Shares the snippet and prints the playground's link to it.
+pub async fn share(api: &str, args: ShareArgs) -> Result<ExitCode> {
+    let snippet = args.snippet.read()?;
+    let new = NewSnippet {
+        content: snippet.main().unwrap_or_default().to_string(),
+        title: args.title,
+        description: args.description,
+        tags: parse_tags(&args.tags),
+        forked_from: None,
+        style: snippet.style().map(str::to_string),
+        head: snippet.head().map(str::to_string),
+    };
+    let client = PlaygroundClient::new(api, ReqwestTransport::default());
+    let shared = client
+        .share(&new)
+        .await
+        .context("the playground failed to share the snippet")?;
+
+    let mut url = Url::parse(&format!("{}/", args.playground.trim_end_matches('/')))
+        .context("invalid playground URL")?;
+    let mut query = format!("shared={}", shared.id);
+    let options = args.build.options()?.query();
+    if !options.is_empty() {
+        query.push('&');
+        query.push_str(&options);
+    }
+    url.set_query(Some(&query));
+    println!("{}", url);
+    // the playground keeps just its hash, it can't be had again
+    eprintln!(
+        "owner token, to save revisions of it: {}",
+        shared.owner_token
+    );
+    if args.open {
+        webbrowser::open(url.as_str()).context("failed to open the browser")?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use serde::de::DeserializeOwned;
+use tokio::fs;
+use tokio::process::Command;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{AnalyzeResponse, BuildOptions, CustomSection, SizeReport};
+
+use crate::progress::Progress;
+use crate::{build, process_group, workspace, Build, TWIGGY_BIN};
+
+/// Number of items listed by `twiggy top`.
+const TOP_ITEMS: &str = "50";
+/// How deep and how wide the `twiggy dominators` tree goes.
+const DOMINATORS_DEPTH: &str = "4";
+const DOMINATORS_ROWS: &str = "30";
+
+/// Builds the snippet and profiles the size of the resulting wasm with twiggy.
+/// Release builds have their names section stripped by wasm-opt and stripped
+/// builds have none at all, so debug builds give the more readable report.
+pub async fn analyze(
+    Query(options): Query<BuildOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<AnalyzeResponse>, ApiError> {
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let workspace = match build(&options, &snippet, &Progress::none()).await? {
+        Build::Built { workspace, .. } => workspace,
+        Build::CompileError(e) => return Ok(Negotiate(AnalyzeResponse::CompileError(e.stderr))),
+    };
+    let report = report(&workspace.join("dist/app_bg.wasm")).await?;
+    Ok(Negotiate(AnalyzeResponse::Report(report)))
+}
+
+/// Runs twiggy's analyses on a wasm module.
+pub async fn report(wasm: &Path) -> Result<SizeReport, ApiError> {
+    let module = fs::read(wasm).await.map_err(|e| {
+        error!(?e, "failed to read app_bg.wasm");
+        ApiError::IoError(e)
+    })?;
+    let top = twiggy(wasm, &["top", "-n", TOP_ITEMS]).await?;
+    let dominators = twiggy(
+        wasm,
+        &["dominators", "-d", DOMINATORS_DEPTH, "-r", DOMINATORS_ROWS],
+    )
+    .await?;
+
+    Ok(SizeReport {
+        wasm_size: module.len() as u64,
+        custom_sections: custom_sections(&module),
+        top,
+        dominators,
+    })
+}
+
+/// Runs a twiggy analysis with JSON output.
+async fn twiggy<T: DeserializeOwned>(wasm: &Path, args: &[&str]) -> Result<T, ApiError> {
+    let mut cmd = Command::new(&*TWIGGY_BIN);
+    let cmd = cmd.args(args).arg("-f").arg("json").arg(wasm);
+    debug!(?cmd, "running command");
+
+    let output = process_group::output(cmd).await.map_err(|e| {
+        error!(?e, "running twiggy failed");
+        ApiError::IoError(e)
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        error!(%stderr, "twiggy failed");
+        return Err(ApiError::Unknown(anyhow::anyhow!(
+            "twiggy failed: {}",
+            stderr
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        error!(?e, "failed to parse twiggy output");
+        ApiError::Unknown(e.into())
+    })
+}
+
+/// Lists the custom sections of a wasm module. Stops at the first malformed
+/// section rather than failing, the report is informational.
+fn custom_sections(module: &[u8]) -> Vec<CustomSection> {
+    let mut sections = Vec::new();
+    // skip the magic number and version
+    let mut rest = module.get(8..).unwrap_or_default();
+    while let Some((&id, after_id)) = rest.split_first() {
+        let Some((size, after_size)) = read_leb128(after_id) else {
+            break;
+        };
+        let Some(contents) = after_size.get(..size as usize) else {
+            break;
+        };
+        if id == 0 {
+            if let Some((len, name)) = read_leb128(contents) {
+                let name = name.get(..len as usize).unwrap_or_default();
+                sections.push(CustomSection {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    size,
+                });
+            }
+        }
+        rest = &after_size[size as usize..];
+    }
+    sections
+}
+
+/// Reads an unsigned LEB128 number, returning it and the bytes after it.
+fn read_leb128(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, &bytes[i + 1..]));
+        }
+    }
+    None
+}
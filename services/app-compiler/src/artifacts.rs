@@ -0,0 +1,185 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use axum::extract::Path as UrlPath;
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{debug, error, info};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{AnalyzeResponse, BuildManifest, BuildTimings, Integrity, Response};
+
+use crate::workspace::PARTIAL_SUFFIX;
+use crate::{analyze, ARTIFACTS_DIR, ARTIFACT_RETENTION};
+
+/// Files of a build that are kept around.
+const FILES: &[&str] = &["index.html", "app.js", "app_bg.wasm"];
+/// File the build's [`BuildManifest`] is kept in, as JSON.
+const MANIFEST: &str = "manifest.json";
+/// File the [`Integrity`] hashes taken when the build finished are kept in, as JSON.
+const INTEGRITY: &str = "integrity.json";
+
+/// Copies a successful build's output out of its workspace so it can be fetched
+/// again later, and returns its build ID. Builds are identified by the SHA-256 of
+/// their wasm, so rebuilding the same snippet doesn't take up another slot. Only
+/// the [`ARTIFACT_RETENTION`] most recent builds are kept.
+pub async fn store(
+    dist: &Path,
+    wasm: &[u8],
+    manifest: &BuildManifest,
+    integrity: &Integrity,
+) -> Result<String, ApiError> {
+    let id = Sha256::digest(wasm)
+        .iter()
+        .map(|it| format!("{:02x}", it))
+        .collect::<String>();
+
+    let root = artifacts_root().await?;
+    let dir = root.join(&id);
+    if fs::metadata(&dir).await.is_ok() {
+        // the stored copy is kept as it is, it's only marked as the newest
+        let marked = std::fs::File::open(&dir).and_then(|it| it.set_modified(SystemTime::now()));
+        if let Err(e) = marked {
+            error!(?e, %id, "failed to mark stored build artifacts as used");
+        }
+        evict(&root).await?;
+        return Ok(id);
+    }
+    // other replicas may share the directory, so the build is copied under a name
+    // of its own first and then moved into place
+    let partial = root.join(format!(
+        "{}.{:016x}{}",
+        id,
+        RandomState::new().build_hasher().finish(),
+        PARTIAL_SUFFIX
+    ));
+    fs::create_dir_all(&partial)
+        .await
+        .map_err(ApiError::IoError)?;
+    for file in FILES {
+        fs::copy(dist.join(file), partial.join(file))
+            .await
+            .map_err(|e| {
+                error!(?e, file, "failed to store build artifact");
+                ApiError::IoError(e)
+            })?;
+    }
+    let manifest = serde_json::to_vec(manifest).map_err(|e| ApiError::Unknown(e.into()))?;
+    fs::write(partial.join(MANIFEST), manifest)
+        .await
+        .map_err(ApiError::IoError)?;
+    let integrity = serde_json::to_vec(integrity).map_err(|e| ApiError::Unknown(e.into()))?;
+    fs::write(partial.join(INTEGRITY), integrity)
+        .await
+        .map_err(ApiError::IoError)?;
+    if fs::rename(&partial, &dir).await.is_err() {
+        // another replica stored the same build in the meantime
+        let _ = fs::remove_dir_all(&partial).await;
+    }
+    debug!(%id, "stored build artifacts");
+
+    evict(&root).await?;
+    Ok(id)
+}
+
+async fn evict(root: &Path) -> Result<(), ApiError> {
+    let mut builds = Vec::new();
+    let mut entries = fs::read_dir(root).await.map_err(ApiError::IoError)?;
+    while let Some(entry) = entries.next_entry().await.map_err(ApiError::IoError)? {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .ends_with(PARTIAL_SUFFIX)
+        {
+            continue;
+        }
+        let modified = entry
+            .metadata()
+            .await
+            .and_then(|it| it.modified())
+            .map_err(ApiError::IoError)?;
+        builds.push((modified, entry.path()));
+    }
+
+    builds.sort_by_key(|(modified, _)| Reverse(*modified));
+    for (_, path) in builds.into_iter().skip(*ARTIFACT_RETENTION) {
+        info!(?path, "evicting build artifacts");
+        fs::remove_dir_all(&path).await.map_err(ApiError::IoError)?;
+    }
+    Ok(())
+}
+
+async fn artifacts_root() -> Result<PathBuf, ApiError> {
+    fs::create_dir_all(&*ARTIFACTS_DIR)
+        .await
+        .map_err(ApiError::IoError)?;
+    fs::canonicalize(&*ARTIFACTS_DIR)
+        .await
+        .map_err(ApiError::IoError)
+}
+
+/// The directory of a retained build.
+async fn build_dir(id: &str) -> Result<PathBuf, ApiError> {
+    // IDs are hex hashes, anything else can't be a build and could escape the directory
+    let is_id = id.len() == 64 && id.chars().all(|it| it.is_ascii_hexdigit());
+    let dir = artifacts_root().await?.join(id);
+    if !is_id || fs::metadata(&dir).await.is_err() {
+        return Err(ApiError::BuildNotFound(id.to_string()));
+    }
+    Ok(dir)
+}
+
+/// Serves a retained build the way `/run` served it, minus the timings.
+pub async fn fetch(UrlPath(id): UrlPath<String>) -> Result<Negotiate<Response>, ApiError> {
+    let dir = build_dir(&id).await?;
+    let index_html = fs::read_to_string(dir.join("index.html"))
+        .await
+        .map_err(ApiError::IoError)?;
+    let js = fs::read_to_string(dir.join("app.js"))
+        .await
+        .map_err(ApiError::IoError)?;
+    let wasm = fs::read(dir.join("app_bg.wasm"))
+        .await
+        .map_err(ApiError::IoError)?;
+
+    // served as hashed when the build finished, so tampering with the stored files shows
+    let integrity = fs::read(dir.join(INTEGRITY))
+        .await
+        .map_err(ApiError::IoError)?;
+    let integrity = serde_json::from_slice(&integrity).map_err(|e| ApiError::Unknown(e.into()))?;
+    let manifest = read_manifest(&dir).await.ok().map(Box::new);
+
+    Ok(Negotiate(Response::Output {
+        index_html,
+        js,
+        wasm,
+        timings: BuildTimings::default(),
+        build_id: Some(id),
+        integrity,
+        manifest,
+    }))
+}
+
+/// Serves what a retained build was made with.
+pub async fn manifest(UrlPath(id): UrlPath<String>) -> Result<Negotiate<BuildManifest>, ApiError> {
+    let dir = build_dir(&id).await?;
+    Ok(Negotiate(read_manifest(&dir).await?))
+}
+
+async fn read_manifest(dir: &Path) -> Result<BuildManifest, ApiError> {
+    let manifest = fs::read(dir.join(MANIFEST))
+        .await
+        .map_err(ApiError::IoError)?;
+    serde_json::from_slice(&manifest).map_err(|e| ApiError::Unknown(e.into()))
+}
+
+/// Profiles the size of a retained build without rebuilding it.
+pub async fn analyze(UrlPath(id): UrlPath<String>) -> Result<Negotiate<AnalyzeResponse>, ApiError> {
+    let dir = build_dir(&id).await?;
+    let report = analyze::report(&dir.join("app_bg.wasm")).await?;
+    Ok(Negotiate(AnalyzeResponse::Report(report)))
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use tokio::fs;
+use tracing::error;
+
+use common::errors::ApiError;
+use common::Snippet;
+
+/// Re-renders a benchmark may ask for, it runs in the preview and holds up the page.
+const MAX_RENDERS: u32 = 10_000;
+
+/// Where the snippet's `src/main.rs` goes, for the harness to include.
+const SNIPPET: &str = "src/snippet.rs";
+
+/// Binary that mounts the snippet's `App` function component, re-renders it and posts
+/// the timings through the bridge script of the preview. Each step is timed until the
+/// scheduler has worked through the renders it queued, effects included. The snippet
+/// is included in a module of the harness the same as for server-side rendering, so
+/// modules declared by the snippet aren't supported.
+const HARNESS: &str = r#"#[allow(dead_code)]
+mod snippet {
+    include!("snippet.rs");
+
+    pub mod playground_bench {
+        use wasm_bindgen::prelude::*;
+
+        const RENDERS: usize = /*RENDERS_GO_HERE*/;
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = performance)]
+            fn now() -> f64;
+            #[wasm_bindgen(js_name = __playgroundBenchmark)]
+            fn report(mount_ms: f64, render_ms: &[f64]);
+        }
+
+        /// Yew renders in a task of its own, which runs before this one is woken again.
+        async fn settle() {
+            let resolved = js_sys::Promise::resolve(&JsValue::UNDEFINED);
+            let _ = wasm_bindgen_futures::JsFuture::from(resolved).await;
+        }
+
+        pub async fn run() {
+            let start = now();
+            let app = yew::Renderer::<super::App>::new().render();
+            settle().await;
+            let mount_ms = now() - start;
+
+            let mut render_ms = Vec::with_capacity(RENDERS);
+            for _ in 0..RENDERS {
+                let start = now();
+                app.send_message(());
+                settle().await;
+                render_ms.push(now() - start);
+            }
+            report(mount_ms, &render_ms);
+        }
+    }
+}
+
+fn main() {
+    wasm_bindgen_futures::spawn_local(snippet::playground_bench::run());
+}
+"#;
+
+/// Turns the prepared workspace into a benchmark re-rendering the snippet's `App`
+/// `renders` times, with the harness in place of the snippet's `src/main.rs`.
+pub async fn install(app_dir: &Path, renders: u32) -> Result<(), ApiError> {
+    if renders == 0 || renders > MAX_RENDERS {
+        return Err(ApiError::InvalidBenchmark(format!(
+            "it must re-render 1 to {} times",
+            MAX_RENDERS
+        )));
+    }
+
+    fs::rename(app_dir.join(Snippet::MAIN), app_dir.join(SNIPPET))
+        .await
+        .map_err(ApiError::IoError)?;
+    let harness = HARNESS.replace("/*RENDERS_GO_HERE*/", &renders.to_string());
+    if let Err(e) = fs::write(app_dir.join(Snippet::MAIN), harness).await {
+        error!(?e, "failed to write benchmark harness");
+        return Err(ApiError::IoError(e));
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tokio::process::Command;
+use tracing::{debug, error, warn};
+
+use common::errors::ApiError;
+
+use crate::{process_group, workspace, CARGO_BIN, WASM_BINDGEN_DIR};
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+}
+
+/// The version of the wasm-bindgen crate the workspace resolves to.
+async fn resolve_version(dir: &Path) -> Result<Option<String>, ApiError> {
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("metadata")
+        .arg("--format-version")
+        .arg("1")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"));
+    debug!(?cmd, "running command");
+
+    let output = process_group::output(cmd).await.map_err(|e| {
+        error!(?e, "running cargo metadata failed");
+        ApiError::IoError(e)
+    })?;
+    if !output.status.success() {
+        // let the build itself report why the manifest doesn't resolve
+        return Ok(None);
+    }
+
+    let metadata: Metadata = serde_json::from_slice(&output.stdout).map_err(|e| {
+        error!(?e, "failed to parse cargo metadata");
+        ApiError::Unknown(e.into())
+    })?;
+    Ok(metadata
+        .packages
+        .into_iter()
+        .find(|it| it.name == "wasm-bindgen")
+        .map(|it| it.version))
+}
+
+/// Puts the wasm-bindgen CLI matching the workspace's wasm-bindgen crate first on
+/// the command's `PATH`. Trunk uses a wasm-bindgen from `PATH` when its version
+/// matches the lockfile, so this avoids the CLI/crate version mismatch error when
+/// dependencies change. Several CLI versions are installed as
+/// `<WASM_BINDGEN_DIR>/<version>/bin/wasm-bindgen`.
+pub async fn select_cli(cmd: &mut Command, dir: &Path) -> Result<(), ApiError> {
+    let Some(version) = resolve_version(dir).await? else {
+        return Ok(());
+    };
+
+    let bin_dir = PathBuf::from(&*WASM_BINDGEN_DIR).join(&version).join("bin");
+    if tokio::fs::metadata(bin_dir.join("wasm-bindgen"))
+        .await
+        .is_err()
+    {
+        warn!(%version, "no wasm-bindgen CLI installed for version, leaving it to trunk");
+        return Ok(());
+    }
+
+    let mut path = OsString::from(bin_dir);
+    if let Some(current) = std::env::var_os("PATH") {
+        path.push(":");
+        path.push(current);
+    }
+    debug!(%version, "using installed wasm-bindgen CLI");
+    cmd.env("PATH", path);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{BuildOptions, ClippyResponse, Snippet};
+
+use crate::{diagnostics, process_group, sandbox, workspace, CARGO_BIN};
+
+/// Runs clippy against the snippet in the app directory, whose target directory
+/// is kept warm by the image build, and returns the lints pointing into the snippet.
+pub async fn clippy(
+    Query(options): Query<BuildOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<ClippyResponse>, ApiError> {
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let app_dir = workspace::prepare(&options, &snippet).await?;
+
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("clippy")
+        .arg("--manifest-path")
+        .arg(app_dir.join("Cargo.toml"))
+        .arg("--target")
+        .arg("wasm32-unknown-unknown")
+        .arg("--message-format=json");
+    workspace::apply_options(cmd, &options);
+    sandbox::apply(cmd, &app_dir)?;
+    debug!(?cmd, "running command");
+
+    let output = match process_group::output(cmd).await {
+        Ok(o) => o,
+        Err(e) => {
+            error!(?e, "running cargo clippy failed");
+            return Err(ApiError::IoError(e));
+        }
+    };
+
+    let diagnostics = diagnostics::read(&output.stdout, &snippet)
+        .into_iter()
+        .filter(|it| it.path == Snippet::MAIN)
+        .map(|it| it.diagnostic)
+        .collect();
+
+    Ok(Negotiate(ClippyResponse { diagnostics }))
+}
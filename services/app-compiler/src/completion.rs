@@ -0,0 +1,293 @@
+//! Completions from a long-lived rust-analyzer, spoken to over the language server
+//! protocol on its stdio.
+//!
+//! Indexing yew and its dependencies takes a while, so the server is started once
+//! on a workspace of its own and kept around. The snippet is only ever sent to it
+//! as the contents of an open document, nothing is written to disk.
+
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{CompletionItem, CompletionKind, CompletionResponse, Position};
+
+use crate::process_group::{self, GroupGuard};
+use crate::workspace::{self, Workspace};
+use crate::RUST_ANALYZER_BIN;
+
+/// Key of the workspace rust-analyzer is started on.
+const WORKSPACE_KEY: &str = "analysis";
+
+static SESSION: Mutex<Option<Session>> = Mutex::const_new(None);
+
+/// Returns completions for the cursor position in the snippet's `src/main.rs`.
+pub async fn complete(
+    Query(position): Query<Position>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<CompletionResponse>, ApiError> {
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let code = snippet.main().unwrap_or_default();
+
+    let mut session = SESSION.lock().await;
+    if session.is_none() {
+        *session = Some(Session::start().await?);
+    }
+    let items = session
+        .as_mut()
+        .expect("session was just started")
+        .complete(code, position)
+        .await;
+    if let Err(e) = &items {
+        // start over with a fresh server on the next request
+        error!(?e, "rust-analyzer failed, shutting it down");
+        *session = None;
+    }
+    Ok(Negotiate(CompletionResponse { items: items? }))
+}
+
+/// Stops rust-analyzer, releasing its workspace. It's started again on the next
+/// completion request.
+pub async fn shutdown() {
+    if SESSION.lock().await.take().is_some() {
+        info!("stopped rust-analyzer");
+    }
+}
+
+struct Session {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    uri: String,
+    next_id: u64,
+    version: u64,
+    // kills the cargo processes the server started, before the server itself
+    _group: GroupGuard,
+    // dropped last so the workspace stays locked until the server is gone
+    _child: Child,
+    _workspace: Workspace,
+}
+
+impl Session {
+    async fn start() -> Result<Self, ApiError> {
+        let workspace = workspace::checkout(WORKSPACE_KEY).await?;
+        let mut cmd = workspace::command(&RUST_ANALYZER_BIN);
+        let cmd = cmd
+            .current_dir(&*workspace)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true);
+        process_group::isolate(cmd);
+        debug!(?cmd, "running command");
+        let mut child = cmd.spawn().map_err(|e| {
+            error!(?e, "failed to start rust-analyzer");
+            ApiError::IoError(e)
+        })?;
+        let group = GroupGuard::new(&child);
+
+        let root = format!("file://{}", workspace.display());
+        let mut session = Self {
+            stdin: child.stdin.take().expect("stdin is piped"),
+            stdout: BufReader::new(child.stdout.take().expect("stdout is piped")),
+            uri: format!("{}/src/main.rs", root),
+            next_id: 0,
+            version: 0,
+            _group: group,
+            _child: child,
+            _workspace: workspace,
+        };
+        session
+            .request(
+                "initialize",
+                json!({
+                    "processId": std::process::id(),
+                    "rootUri": root,
+                    "capabilities": {
+                        "textDocument": {
+                            "completion": { "completionItem": { "snippetSupport": false } }
+                        }
+                    },
+                    "initializationOptions": {
+                        "cargo": { "target": "wasm32-unknown-unknown", "features": "all" },
+                        "checkOnSave": false
+                    }
+                }),
+            )
+            .await?;
+        session.notify("initialized", json!({})).await?;
+        session
+            .notify(
+                "textDocument/didOpen",
+                json!({
+                    "textDocument": {
+                        "uri": session.uri,
+                        "languageId": "rust",
+                        "version": session.version,
+                        "text": ""
+                    }
+                }),
+            )
+            .await?;
+        info!("started rust-analyzer");
+        Ok(session)
+    }
+
+    async fn complete(
+        &mut self,
+        code: &str,
+        position: Position,
+    ) -> Result<Vec<CompletionItem>, ApiError> {
+        self.version += 1;
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": self.uri, "version": self.version },
+                "contentChanges": [{ "text": code }]
+            }),
+        )
+        .await?;
+        let result = self
+            .request(
+                "textDocument/completion",
+                json!({
+                    "textDocument": { "uri": self.uri },
+                    "position": {
+                        "line": position.line.saturating_sub(1),
+                        "character": position.column.saturating_sub(1)
+                    }
+                }),
+            )
+            .await?;
+
+        // the result is either a list of items or an object holding them
+        let items = match result {
+            Value::Array(items) => items,
+            Value::Object(mut list) => match list.remove("items") {
+                Some(Value::Array(items)) => items,
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        Ok(items.iter().filter_map(completion_item).collect())
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) -> Result<(), ApiError> {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await
+    }
+
+    /// Sends a request and waits for its response, skipping the notifications the
+    /// server sends in the meantime.
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value, ApiError> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+
+        loop {
+            let mut message = self.receive().await?;
+            match (message.get("id"), message.get("method")) {
+                (Some(it), None) if *it == id => {
+                    if let Some(e) = message.get("error") {
+                        return Err(ApiError::Unknown(anyhow::anyhow!(
+                            "rust-analyzer failed to answer {}: {}",
+                            method,
+                            e
+                        )));
+                    }
+                    return Ok(message["result"].take());
+                }
+                // requests from the server, none of which need a real answer
+                (Some(request_id), Some(_)) => {
+                    let reply = json!({ "jsonrpc": "2.0", "id": request_id, "result": null });
+                    self.send(reply).await?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn send(&mut self, message: Value) -> Result<(), ApiError> {
+        let body = message.to_string();
+        let frame = format!("Content-Length: {}\r\n\r\n{}", body.len(), body);
+        self.stdin
+            .write_all(frame.as_bytes())
+            .await
+            .map_err(ApiError::IoError)?;
+        self.stdin.flush().await.map_err(ApiError::IoError)
+    }
+
+    async fn receive(&mut self) -> Result<Value, ApiError> {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            let read = self
+                .stdout
+                .read_line(&mut header)
+                .await
+                .map_err(ApiError::IoError)?;
+            if read == 0 {
+                return Err(ApiError::Unknown(anyhow::anyhow!(
+                    "rust-analyzer exited unexpectedly"
+                )));
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+        let length = length.ok_or_else(|| {
+            ApiError::Unknown(anyhow::anyhow!("rust-analyzer sent a message without a length"))
+        })?;
+
+        let mut body = vec![0; length];
+        self.stdout
+            .read_exact(&mut body)
+            .await
+            .map_err(ApiError::IoError)?;
+        serde_json::from_slice(&body).map_err(|e| ApiError::Unknown(e.into()))
+    }
+}
+
+fn completion_item(item: &Value) -> Option<CompletionItem> {
+    let label = item.get("label")?.as_str()?.to_string();
+    let insert_text = item
+        .pointer("/textEdit/newText")
+        .or_else(|| item.get("insertText"))
+        .and_then(Value::as_str)
+        .unwrap_or(&label)
+        .to_string();
+    Some(CompletionItem {
+        kind: completion_kind(item.get("kind").and_then(Value::as_u64)),
+        detail: item.get("detail").and_then(Value::as_str).map(str::to_string),
+        insert_text,
+        label,
+    })
+}
+
+/// Maps an LSP `CompletionItemKind` to ours.
+fn completion_kind(kind: Option<u64>) -> CompletionKind {
+    match kind {
+        Some(2..=4) => CompletionKind::Function,
+        Some(5 | 10) => CompletionKind::Field,
+        Some(6 | 12 | 21) => CompletionKind::Variable,
+        Some(7 | 22) => CompletionKind::Struct,
+        Some(8) => CompletionKind::Trait,
+        Some(9) => CompletionKind::Module,
+        Some(13) => CompletionKind::Enum,
+        Some(14) => CompletionKind::Keyword,
+        Some(15) => CompletionKind::Snippet,
+        Some(20) => CompletionKind::EnumMember,
+        Some(25) => CompletionKind::TypeParameter,
+        _ => CompletionKind::Other,
+    }
+}
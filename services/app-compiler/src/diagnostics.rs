@@ -0,0 +1,117 @@
+//! The diagnostics cargo reports with `--message-format=json`, for the places in the
+//! snippet they point at.
+
+use std::path::Path;
+
+use serde::Deserialize;
+use tracing::{debug, error};
+
+use common::{BuildOptions, Diagnostic, FileDiagnostic, Level, Snippet, Span};
+
+use crate::{process_group, sandbox, workspace, CARGO_BIN};
+
+#[derive(Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum CargoMessage {
+    CompilerMessage {
+        message: CompilerMessage,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct CompilerMessage {
+    message: String,
+    code: Option<CompilerCode>,
+    level: String,
+    spans: Vec<CompilerSpan>,
+}
+
+#[derive(Deserialize)]
+struct CompilerCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct CompilerSpan {
+    file_name: String,
+    line_start: u32,
+    column_start: u32,
+    line_end: u32,
+    column_end: u32,
+    is_primary: bool,
+}
+
+/// The diagnostics among cargo's messages, one per line of `stdout`, whose primary span
+/// is in one of the snippet's files.
+pub fn read(stdout: &[u8], snippet: &Snippet) -> Vec<FileDiagnostic> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(CargoMessage::CompilerMessage { message }) => into_diagnostic(message, snippet),
+            _ => None,
+        })
+        .collect()
+}
+
+fn into_diagnostic(message: CompilerMessage, snippet: &Snippet) -> Option<FileDiagnostic> {
+    let level = match message.level.as_str() {
+        "error" => Level::Error,
+        "warning" => Level::Warning,
+        "note" => Level::Note,
+        "help" => Level::Help,
+        _ => return None,
+    };
+    let span = message
+        .spans
+        .into_iter()
+        .find(|span| span.is_primary && snippet.files.contains_key(&span.file_name))?;
+
+    Some(FileDiagnostic {
+        path: span.file_name,
+        diagnostic: Diagnostic {
+            level,
+            message: message.message,
+            code: message.code.map(|it| it.code),
+            span: Some(Span {
+                line: span.line_start,
+                column: span.column_start,
+                end_line: span.line_end,
+                end_column: span.column_end,
+            }),
+        },
+    })
+}
+
+/// Checks the snippet in its workspace, for the diagnostics of a build that failed.
+/// Trunk renders cargo's, so they're asked for again, the dependencies are built by
+/// then. Failing to check it leaves the build without any.
+pub async fn check(
+    app_dir: &Path,
+    options: &BuildOptions,
+    snippet: &Snippet,
+) -> Vec<FileDiagnostic> {
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("check")
+        .arg("--manifest-path")
+        .arg(app_dir.join("Cargo.toml"))
+        .arg("--target")
+        .arg("wasm32-unknown-unknown")
+        .arg("--message-format=json");
+    workspace::apply_options(cmd, options);
+    if let Err(e) = sandbox::apply(cmd, app_dir) {
+        error!(?e, "failed to sandbox cargo check");
+        return Vec::new();
+    }
+    debug!(?cmd, "running command");
+
+    match process_group::output(cmd).await {
+        Ok(output) => read(&output.stdout, snippet),
+        Err(e) => {
+            error!(?e, "running cargo check failed");
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{BuildOptions, ExpandResponse};
+
+use crate::{process_group, sandbox, workspace, CARGO_BIN};
+
+/// Runs `cargo expand` on the snippet and returns the expanded source.
+pub async fn expand(
+    Query(options): Query<BuildOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<ExpandResponse>, ApiError> {
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let app_dir = workspace::prepare(&options, &snippet).await?;
+
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("expand")
+        .arg("--manifest-path")
+        .arg(app_dir.join("Cargo.toml"))
+        .arg("--target")
+        .arg("wasm32-unknown-unknown")
+        .arg("--color")
+        .arg("never");
+    workspace::apply_options(cmd, &options);
+    sandbox::apply(cmd, &app_dir)?;
+    debug!(?cmd, "running command");
+
+    let output = match process_group::output(cmd).await {
+        Ok(o) => o,
+        Err(e) => {
+            error!(?e, "running cargo expand failed");
+            return Err(ApiError::IoError(e));
+        }
+    };
+
+    if !output.status.success() {
+        return Ok(Negotiate(ExpandResponse::CompileError(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        )));
+    }
+
+    Ok(Negotiate(ExpandResponse::Expanded(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )))
+}
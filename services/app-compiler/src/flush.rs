@@ -0,0 +1,131 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{error, info};
+
+use common::errors::ApiError;
+
+use crate::{completion, health, workspace, APP_DIR, ARTIFACTS_DIR};
+
+/// File in the workspaces directory recording what the caches were filled with.
+const FINGERPRINT: &str = ".toolchain-fingerprint";
+
+/// Describes everything a cached build depends on besides the snippet: the
+/// toolchain versions and the app template's manifest and lockfile.
+async fn fingerprint() -> String {
+    let toolchain = health::toolchain_versions().await;
+    let mut pins = Sha256::new();
+    for file in ["Cargo.toml", "Cargo.lock"] {
+        if let Ok(contents) = fs::read(Path::new(&*APP_DIR).join(file)).await {
+            pins.update(contents);
+        }
+    }
+    format!(
+        "{}\n{:x}\n",
+        serde_json::to_string(&toolchain).expect("toolchain versions serialize"),
+        pins.finalize()
+    )
+}
+
+/// Flushes the caches if they were filled by a different toolchain or with
+/// different dependency pins than the current ones, so an upgrade never serves
+/// artifacts built by the old rustc. Run at startup.
+pub async fn flush_if_stale() {
+    let result = async {
+        let root = workspace::workspaces_root().await?;
+        let current = fingerprint().await;
+        let recorded = fs::read_to_string(root.join(FINGERPRINT))
+            .await
+            .unwrap_or_default();
+        if recorded != current {
+            info!("toolchain or dependency pins changed, flushing caches");
+            flush_caches().await?;
+        }
+        Ok::<_, ApiError>(())
+    }
+    .await;
+    if let Err(e) = result {
+        error!(?e, "failed to check caches against the toolchain");
+    }
+}
+
+/// Flushes the workspace pool and the retained artifacts. Meant to be called by
+/// deployment tooling after changing the toolchain or dependency pins.
+pub async fn flush() -> Result<StatusCode, ApiError> {
+    flush_caches().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Re-runs the check of [`flush_if_stale`] whenever the process gets a `SIGHUP`.
+#[cfg(unix)]
+pub fn flush_on_sighup() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(it) => it,
+            Err(e) => {
+                error!(?e, "failed to listen for SIGHUP");
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            info!("got SIGHUP, flushing caches");
+            if let Err(e) = flush_caches().await {
+                error!(?e, "failed to flush caches");
+            }
+        }
+    });
+}
+
+async fn flush_caches() -> Result<(), ApiError> {
+    let root = workspace::workspaces_root().await?;
+    let artifacts = PathBuf::from(&*ARTIFACTS_DIR);
+    let current = fingerprint().await;
+    // rust-analyzer holds on to its workspace for as long as it runs
+    completion::shutdown().await;
+    tokio::task::spawn_blocking(move || {
+        let (workspaces, builds) = (flush_workspaces(&root)?, remove_dirs(&artifacts)?);
+        info!(workspaces, builds, "flushed caches");
+        std::fs::write(root.join(FINGERPRINT), current)
+    })
+    .await
+    .expect("flushing caches panicked")
+    .map_err(|e| {
+        error!(?e, "failed to flush caches");
+        ApiError::IoError(e)
+    })
+}
+
+/// Removes every workspace, waiting for the jobs using them to finish first.
+fn flush_workspaces(root: &Path) -> io::Result<usize> {
+    let mut flushed = 0;
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let _lock = workspace::lock(&path)?;
+        std::fs::remove_dir_all(&path)?;
+        flushed += 1;
+    }
+    Ok(flushed)
+}
+
+fn remove_dirs(root: &Path) -> io::Result<usize> {
+    if !root.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
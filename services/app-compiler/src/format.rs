@@ -0,0 +1,99 @@
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use tokio::process::Command;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{Diagnostic, Edition, FormatResponse, Level, Span};
+
+use crate::{process_group, sandbox, workspace, RUSTFMT_BIN};
+
+/// Key of the empty workspace rustfmt is confined to.
+const WORKSPACE_KEY: &str = "format";
+
+#[derive(Deserialize)]
+pub struct FormatOptions {
+    #[serde(default)]
+    edition: Edition,
+}
+
+/// Formats the snippet with rustfmt in the sandbox. The snippet is passed through
+/// stdin so formatting never touches the app directory, rustfmt is confined to a
+/// workspace of its own.
+pub async fn format(
+    Query(options): Query<FormatOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<FormatResponse>, ApiError> {
+    let snippet = workspace::read_snippet(&headers, body).await?;
+
+    let mut cmd = Command::new(&*RUSTFMT_BIN);
+    let cmd = cmd
+        .arg("--edition")
+        .arg(options.edition.as_str())
+        .arg("--color")
+        .arg("never");
+    let workspace = workspace::scratch(WORKSPACE_KEY).await?;
+    sandbox::apply(cmd, &workspace)?;
+    debug!(?cmd, "running command");
+
+    let main = snippet.main().unwrap_or_default();
+    let output = process_group::output_with_input(cmd, main.as_bytes())
+        .await
+        .map_err(|e| {
+            error!(?e, "running rustfmt failed");
+            ApiError::IoError(e)
+        })?;
+
+    if !output.status.success() {
+        let rendered = String::from_utf8_lossy(&output.stderr).to_string();
+        let diagnostics = parse_errors(&rendered);
+        return Ok(Negotiate(FormatResponse::ParseError {
+            rendered,
+            diagnostics,
+        }));
+    }
+
+    Ok(Negotiate(FormatResponse::Formatted(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )))
+}
+
+/// Extracts the errors and their `<stdin>:line:column` locations from rustfmt's output.
+fn parse_errors(stderr: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for line in stderr.lines() {
+        if let Some(rest) = line.strip_prefix("error") {
+            let (code, message) = match rest.strip_prefix('[') {
+                Some(rest) => match rest.split_once("]: ") {
+                    Some((code, message)) => (Some(code.to_string()), message),
+                    None => (None, rest),
+                },
+                None => (None, rest.trim_start_matches(": ")),
+            };
+            diagnostics.push(Diagnostic {
+                level: Level::Error,
+                message: message.to_string(),
+                code,
+                span: None,
+            });
+        } else if let Some(location) = line.trim_start().strip_prefix("--> <stdin>:") {
+            let mut parts = location.split(':').map(|it| it.parse::<u32>().ok());
+            if let (Some(Some(line)), Some(Some(column)), Some(last)) =
+                (parts.next(), parts.next(), diagnostics.last_mut())
+            {
+                if last.span.is_none() {
+                    last.span = Some(Span {
+                        line,
+                        column,
+                        end_line: line,
+                        end_column: column,
+                    });
+                }
+            }
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::Ordering;
+use std::time::SystemTime;
+
+use axum::Json;
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+
+use common::errors::ApiError;
+use common::{CompilerHealth, ToolchainVersions, WorkspaceStatus};
+
+use crate::janitor::DISK_USAGE;
+use crate::workspace::{self, PARTIAL_SUFFIX};
+use crate::{
+    CARGO_BIN, MAX_DISK_USAGE, QUEUE, RUSTC_BIN, TRUNK_BIN, WASM_BINDGEN_BIN, WASM_OPT_BIN,
+};
+
+static TOOLCHAIN: OnceCell<ToolchainVersions> = OnceCell::const_new();
+
+pub async fn tool_version(bin: &str) -> Option<String> {
+    let output = Command::new(bin).arg("--version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The versions of the toolchain's tools, looked up once per process.
+pub async fn toolchain_versions() -> ToolchainVersions {
+    TOOLCHAIN.get_or_init(toolchain).await.clone()
+}
+
+async fn toolchain() -> ToolchainVersions {
+    ToolchainVersions {
+        rustc: tool_version(&RUSTC_BIN).await,
+        cargo: tool_version(&CARGO_BIN).await,
+        trunk: tool_version(&TRUNK_BIN).await,
+        wasm_bindgen: tool_version(&WASM_BINDGEN_BIN).await,
+        wasm_opt: tool_version(&WASM_OPT_BIN).await,
+    }
+}
+
+/// Reports toolchain versions, workspace pool and queue state, and disk headroom.
+///
+/// This route is not behind the build queue so it answers while a build is running.
+pub async fn health() -> Result<Json<CompilerHealth>, ApiError> {
+    let toolchain = toolchain_versions().await;
+
+    let root = workspace::workspaces_root().await?;
+    let mut workspaces = Vec::new();
+    let mut entries = tokio::fs::read_dir(&root)
+        .await
+        .map_err(ApiError::IoError)?;
+    while let Some(entry) = entries.next_entry().await.map_err(ApiError::IoError)? {
+        let key = entry.file_name().to_string_lossy().into_owned();
+        // lock and fingerprint files live next to the workspaces
+        let is_dir = entry.file_type().await.is_ok_and(|it| it.is_dir());
+        if !is_dir || key.ends_with(PARTIAL_SUFFIX) {
+            continue;
+        }
+        let idle_secs = SystemTime::now()
+            .duration_since(workspace::last_used(&entry.path()))
+            .map(|it| it.as_secs())
+            .unwrap_or_default();
+        workspaces.push(WorkspaceStatus { key, idle_secs });
+    }
+
+    Ok(Json(CompilerHealth {
+        toolchain,
+        workspaces,
+        queue_depth: QUEUE.depth(),
+        busy: QUEUE.is_busy(),
+        disk_usage: DISK_USAGE.load(Ordering::Relaxed),
+        disk_quota: *MAX_DISK_USAGE,
+    }))
+}
@@ -0,0 +1,17 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use common::Integrity;
+
+/// Hashes the artifacts of a build.
+pub fn integrity(js: &str, wasm: &[u8]) -> Integrity {
+    Integrity {
+        js: sri(js.as_bytes()),
+        wasm: sri(wasm),
+    }
+}
+
+fn sri(bytes: &[u8]) -> String {
+    format!("sha256-{}", STANDARD.encode(Sha256::digest(bytes)))
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::{error, info};
+
+use common::Priority;
+
+use crate::workspace::{self, PARTIAL_SUFFIX};
+use crate::{JANITOR_INTERVAL, MAX_DISK_USAGE, QUEUE};
+
+/// Disk used by the workspaces as of the last sweep, in bytes.
+pub static DISK_USAGE: AtomicU64 = AtomicU64::new(0);
+
+/// Periodically cleans up the workspaces directory.
+///
+/// The janitor runs as a batch job in the build queue, and only touches
+/// workspaces it can lock, so no build on this or another replica sharing the
+/// directory uses them while it does. Any half-copied workspace or `dist`
+/// directory it can lock is therefore left over from an earlier, interrupted job.
+pub fn spawn() {
+    tokio::spawn(async {
+        let mut interval = tokio::time::interval(*JANITOR_INTERVAL);
+        loop {
+            interval.tick().await;
+            let _permit = QUEUE.acquire(Priority::Batch).await;
+
+            let root = match workspace::workspaces_root().await {
+                Ok(root) => root,
+                Err(e) => {
+                    error!(?e, "janitor failed to open workspaces directory");
+                    continue;
+                }
+            };
+            let result = tokio::task::spawn_blocking(move || sweep(&root, *MAX_DISK_USAGE))
+                .await
+                .expect("janitor sweep panicked");
+            if let Err(e) = result {
+                error!(?e, "janitor sweep failed");
+            }
+        }
+    });
+}
+
+fn sweep(root: &Path, quota: u64) -> io::Result<()> {
+    let mut usage = 0;
+    let mut workspaces = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        // other replicas sharing the directory may be using it, only touch what we can lock
+        let Some(lock) = workspace::try_lock(&path)? else {
+            usage += dir_size(&path)?;
+            continue;
+        };
+        if path.to_string_lossy().ends_with(PARTIAL_SUFFIX) {
+            info!(?path, "removing abandoned workspace copy");
+            std::fs::remove_dir_all(&path)?;
+            continue;
+        }
+
+        let dist = path.join("dist");
+        if dist.exists() {
+            std::fs::remove_dir_all(&dist)?;
+        }
+        let size = dir_size(&path)?;
+        usage += size;
+        workspaces.push((workspace::last_used(&path), size, path, lock));
+    }
+
+    workspaces.sort_by_key(|(last_used, _, _, _)| *last_used);
+    for (_, size, path, _lock) in workspaces {
+        if usage <= quota {
+            break;
+        }
+        info!(
+            ?path,
+            size, usage, quota, "evicting least recently used workspace"
+        );
+        std::fs::remove_dir_all(&path)?;
+        usage -= size;
+    }
+    DISK_USAGE.store(usage, Ordering::Relaxed);
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
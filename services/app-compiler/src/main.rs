@@ -1,89 +1,233 @@
+mod analyze;
+mod artifacts;
+mod bench;
+mod bindgen;
+mod clippy;
+mod completion;
+mod diagnostics;
+mod expand;
+mod flush;
+mod format;
+mod health;
+mod integrity;
+mod janitor;
+mod manifest;
+mod policy;
+mod process_group;
+mod progress;
+mod queue;
+mod sandbox;
+mod ssr;
+mod timings;
+mod workspace;
+
 use std::net::SocketAddr;
+use std::path::Path;
 use std::time::Duration;
 
 use axum::error_handling::HandleErrorLayer;
-use axum::extract::RawBody;
-use axum::routing::post;
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use axum::middleware;
+use axum::routing::{get, post};
 use axum::Router;
-use serde::Serialize;
 use tokio::fs;
 use tokio::process::Command;
-use tower::limit::GlobalConcurrencyLimitLayer;
 use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 use tracing::{debug, info, error};
 
-use common::errors::{timeout_or_500, ApiError};
-use common::init_tracing;
-use common::response::Bson;
+use common::errors::{timeout_or_500, with_request_id, ApiError};
+use common::{
+    init_tracing, BuildManifest, BuildOptions, BuildStage, BuildTimings, CompileError, Profile,
+    Response, Snippet,
+};
+use common::response::{negotiate, Negotiate};
 use lazy_static::lazy_static;
+use progress::Progress;
+use queue::BuildQueue;
+use workspace::Workspace;
 
 lazy_static! {
     static ref APP_DIR: String =
         std::env::var("APP_DIR").unwrap_or_else(|_| "../../app".to_string());
+    // the workspaces and artifacts directories may be volumes shared by several replicas
+    static ref WORKSPACES_DIR: String = std::env::var("WORKSPACES_DIR").unwrap_or_else(|_| {
+        let dir = std::env::temp_dir().join("playground-workspaces");
+        dir.display().to_string()
+    });
     static ref TRUNK_BIN: String =
         std::env::var("TRUNK_BIN").unwrap_or_else(|_| "trunk".to_string());
+    static ref CARGO_BIN: String =
+        std::env::var("CARGO_BIN").unwrap_or_else(|_| "cargo".to_string());
+    static ref RUSTC_BIN: String =
+        std::env::var("RUSTC_BIN").unwrap_or_else(|_| "rustc".to_string());
+    static ref WASM_BINDGEN_BIN: String =
+        std::env::var("WASM_BINDGEN_BIN").unwrap_or_else(|_| "wasm-bindgen".to_string());
+    static ref WASM_BINDGEN_DIR: String =
+        std::env::var("WASM_BINDGEN_DIR").unwrap_or_else(|_| "/opt/wasm-bindgen".to_string());
+    static ref WASM_OPT_BIN: String =
+        std::env::var("WASM_OPT_BIN").unwrap_or_else(|_| "wasm-opt".to_string());
+    static ref WASM_STRIP_BIN: String =
+        std::env::var("WASM_STRIP_BIN").unwrap_or_else(|_| "wasm-strip".to_string());
+    static ref TWIGGY_BIN: String =
+        std::env::var("TWIGGY_BIN").unwrap_or_else(|_| "twiggy".to_string());
+    static ref RUST_ANALYZER_BIN: String =
+        std::env::var("RUST_ANALYZER_BIN").unwrap_or_else(|_| "rust-analyzer".to_string());
+    static ref RUSTFMT_BIN: String =
+        std::env::var("RUSTFMT_BIN").unwrap_or_else(|_| "rustfmt".to_string());
+    static ref ARTIFACTS_DIR: String = std::env::var("ARTIFACTS_DIR").unwrap_or_else(|_| {
+        let dir = std::env::temp_dir().join("playground-artifacts");
+        dir.display().to_string()
+    });
+    static ref ARTIFACT_RETENTION: usize = std::env::var("ARTIFACT_RETENTION")
+        .ok()
+        .and_then(|it| it.parse().ok())
+        .unwrap_or(20);
+    static ref OFFLINE_BUILDS: bool = std::env::var("OFFLINE_BUILDS").is_ok();
+    static ref SANDBOX_BUILDS: bool = std::env::var("SANDBOX_BUILDS").is_ok();
+    static ref ALLOW_CUSTOM_DEPENDENCIES: bool =
+        std::env::var("ALLOW_CUSTOM_DEPENDENCIES").is_ok();
     static ref PORT: u16 = std::env::var("PORT")
         .ok()
         .and_then(|it| it.parse().ok())
         .unwrap_or(4000);
+    static ref QUEUE: BuildQueue = BuildQueue::new(Duration::from_secs(
+        std::env::var("QUEUE_AGING_SECS")
+            .ok()
+            .and_then(|it| it.parse().ok())
+            .unwrap_or(30)
+    ));
+    static ref JANITOR_INTERVAL: Duration = Duration::from_secs(
+        std::env::var("JANITOR_INTERVAL_SECS")
+            .ok()
+            .and_then(|it| it.parse().ok())
+            .unwrap_or(300)
+    );
+    static ref MAX_DISK_USAGE: u64 = std::env::var("MAX_DISK_USAGE_MB")
+        .ok()
+        .and_then(|it| it.parse::<u64>().ok())
+        .unwrap_or(8192)
+        * 1024
+        * 1024;
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Response {
-    Output {
-        index_html: String,
-        js: String,
-        wasm: Vec<u8>,
+/// Release profile overrides for the min-size preset.
+const MIN_SIZE_OVERRIDES: &[(&str, &str)] = &[
+    ("CARGO_PROFILE_RELEASE_OPT_LEVEL", "z"),
+    ("CARGO_PROFILE_RELEASE_LTO", "fat"),
+    ("CARGO_PROFILE_RELEASE_CODEGEN_UNITS", "1"),
+    ("CARGO_PROFILE_RELEASE_PANIC", "abort"),
+    ("CARGO_PROFILE_RELEASE_STRIP", "symbols"),
+];
+
+/// Outcome of building a snippet with trunk.
+enum Build {
+    /// The output is in the workspace's `dist` directory. The workspace stays
+    /// checked out so no other job overwrites it before it's read.
+    Built {
+        workspace: Workspace,
+        timings: BuildTimings,
+        manifest: Box<BuildManifest>,
     },
-    CompileError(String),
+    CompileError(CompileError),
 }
 
-async fn run(RawBody(body): RawBody) -> Result<Bson<Response>, ApiError> {
-    let body = hyper::body::to_bytes(body).await.unwrap();
-    if body.is_empty() {
-        return Err(ApiError::NoBody);
+/// Builds the snippet with trunk into its workspace's `dist` directory.
+async fn build(
+    options: &BuildOptions,
+    snippet: &Snippet,
+    progress: &Progress,
+) -> Result<Build, ApiError> {
+    progress.report(BuildStage::CompilingDependencies);
+    let app_dir = workspace::prepare(options, snippet).await?;
+    if let Some(renders) = options.bench {
+        bench::install(&app_dir, renders).await?;
     }
-    let body = String::from_utf8_lossy(&body);
-    let app_dir = match fs::canonicalize(&*APP_DIR).await {
-        Ok(v) => v,
-        Err(e) => {
-            error!(?e, "failed to canonicalize app_dir path");
-            return Err(ApiError::IoError(e))
-        },
-    };
 
-    match fs::write(app_dir.join("src/main.rs"), &*body).await {
-        Ok(_) => {},
-        Err(e) => {
-            error!(?e, "failed to write main.rs");
-            return Err(ApiError::IoError(e))
-        },
-    };
-
-    let mut cmd = Command::new(&*TRUNK_BIN);
+    let mut cmd = workspace::command(&TRUNK_BIN);
     let cmd = cmd
         .arg("--config")
         .arg(app_dir.join("Trunk.toml"))
-        .arg("build");
+        .arg("build")
+        // the playground renders cargo's colors in its compile error panel
+        .env("CARGO_TERM_COLOR", "always");
+    match options.profile {
+        Profile::Debug => {}
+        Profile::Release => {
+            cmd.arg("--release");
+        }
+        Profile::MinSize => {
+            cmd.arg("--release").envs(MIN_SIZE_OVERRIDES.iter().copied());
+        }
+    }
+    bindgen::select_cli(cmd, &app_dir).await?;
+    workspace::apply_options(cmd, options);
+    sandbox::apply(cmd, &app_dir)?;
     debug!(?cmd, "running command");
 
-    let output = match cmd.output().await {
+    let output = timings::output_with_timings(cmd, |line| progress.observe(line)).await;
+    let (output, timings) = match output {
         Ok(o) => o,
         Err(e) => {
             error!(?e, "running trunk failed");
             return Err(ApiError::IoError(e))
         },
     };
+    debug!(?timings, "build finished");
+
+    if !output.status.success() {
+        return Ok(Build::CompileError(CompileError {
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            diagnostics: diagnostics::check(&app_dir, options, snippet).await,
+        }));
+    }
 
+    if options.strip {
+        strip(&app_dir.join("dist/app_bg.wasm")).await?;
+    }
+    let manifest = Box::new(manifest::record(&app_dir, options).await?);
+
+    Ok(Build::Built {
+        workspace: app_dir,
+        timings,
+        manifest,
+    })
+}
+
+/// Removes the names and all other custom sections from a wasm module in place.
+async fn strip(wasm: &Path) -> Result<(), ApiError> {
+    let mut cmd = Command::new(&*WASM_STRIP_BIN);
+    let cmd = cmd.arg(wasm);
+    debug!(?cmd, "running command");
+
+    let output = process_group::output(cmd).await.map_err(|e| {
+        error!(?e, "running wasm-strip failed");
+        ApiError::IoError(e)
+    })?;
     if !output.status.success() {
-        return Ok(Bson(Response::CompileError(
-            String::from_utf8_lossy(&output.stderr).to_string(),
-        )));
+        return Err(ApiError::BuildFailed(output));
     }
+    Ok(())
+}
+
+async fn run(
+    Query(options): Query<BuildOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<Response>, ApiError> {
+    let progress = Progress::from_headers(&headers);
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let (workspace, timings, manifest) = match build(&options, &snippet, &progress).await? {
+        Build::Built {
+            workspace,
+            timings,
+            manifest,
+        } => (workspace, timings, manifest),
+        Build::CompileError(e) => return Ok(Negotiate(Response::CompileError(e))),
+    };
+    let dist = workspace.join("dist");
 
-    let dist = app_dir.join("dist");
     let index_html = fs::read_to_string(dist.join("index.html")).await.map_err(|e| {
         error!(?e, "failed to read index.html");
         ApiError::IoError(e)
@@ -96,24 +240,24 @@ async fn run(RawBody(body): RawBody) -> Result<Bson<Response>, ApiError> {
         error!(?e, "failed to read app_bg.wasm");
         ApiError::IoError(e)
     })?;
-    
+    let integrity = integrity::integrity(&js, &wasm);
+    // losing the ability to fetch the build again is no reason to fail it
+    let build_id = artifacts::store(&dist, &wasm, &manifest, &integrity)
+        .await
+        .map_err(|e| error!(?e, "failed to retain build"))
+        .ok();
 
-    Ok(Bson(Response::Output {
+    Ok(Negotiate(Response::Output {
         index_html,
         js,
         wasm,
+        timings,
+        build_id,
+        integrity,
+        manifest: Some(manifest),
     }))
 }
 
-async fn trunk_version() -> String {
-    Command::new(&*TRUNK_BIN)
-        .arg("--version")
-        .output()
-        .await
-        .map(|v| String::from_utf8_lossy(&v.stdout).trim().to_string())
-        .unwrap_or_else(|_| "failed to get trunk version".to_string())
-}
-
 #[tokio::main]
 async fn main() {
     let app_dir = &*APP_DIR;
@@ -122,18 +266,50 @@ async fn main() {
     init_tracing();
 
     debug!(?app_dir);
-    let trunk_version = trunk_version().await;
+    let trunk_version = health::tool_version(trunk_path)
+        .await
+        .unwrap_or_else(|| "failed to get trunk version".to_string());
     debug!(trunk_bin_path = ?trunk_path, trunk_version = ?trunk_version);
 
+    flush::flush_if_stale().await;
+    #[cfg(unix)]
+    flush::flush_on_sighup();
+    janitor::spawn();
+
     // build our application with a single route
     let app = Router::new()
         .route("/run", post(run))
+        .route("/analyze", post(analyze::analyze))
+        .route("/expand", post(expand::expand))
+        .route("/format", post(format::format))
+        .route("/clippy", post(clippy::clippy))
+        .route("/ssr", post(ssr::ssr))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(timeout_or_500))
                 .timeout(Duration::from_secs(10)),
         )
-        .layer(GlobalConcurrencyLimitLayer::new(1))
+        .layer(middleware::from_fn(queue::prioritize))
+        .layer(middleware::from_fn(progress::track))
+        // completions are answered by rust-analyzer rather than a build, so they
+        // skip the queue
+        .route(
+            "/complete",
+            post(completion::complete).layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(timeout_or_500))
+                    .timeout(Duration::from_secs(10)),
+            ),
+        )
+        // follows a build for as long as it takes, past the timeout
+        .route("/progress/:id", get(progress::follow))
+        .route("/health", get(health::health))
+        .route("/admin/flush", post(flush::flush))
+        .route("/artifacts/:id", get(artifacts::fetch))
+        .route("/artifacts/:id/analyze", get(artifacts::analyze))
+        .route("/artifacts/:id/manifest", get(artifacts::manifest))
+        .layer(middleware::from_fn(negotiate))
+        .layer(middleware::from_fn(with_request_id))
         .layer(TraceLayer::new_for_http());
 
     let addr = SocketAddr::new("0.0.0.0".parse().unwrap(), *PORT);
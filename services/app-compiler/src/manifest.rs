@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::error;
+
+use common::errors::ApiError;
+use common::{BuildManifest, BuildOptions, LockedPackage};
+
+use crate::{health, workspace};
+
+/// Records what the build in `dir` was made with.
+pub async fn record(dir: &Path, options: &BuildOptions) -> Result<BuildManifest, ApiError> {
+    let lockfile = fs::read_to_string(dir.join("Cargo.lock"))
+        .await
+        .map_err(|e| {
+            error!(?e, "failed to read Cargo.lock");
+            ApiError::IoError(e)
+        })?;
+
+    Ok(BuildManifest {
+        toolchain: health::toolchain_versions().await,
+        options: options.clone(),
+        rustflags: workspace::rustflags(options),
+        lockfile_sha256: format!("{:x}", Sha256::digest(&lockfile)),
+        packages: packages(&lockfile),
+    })
+}
+
+/// Lists the `[[package]]` entries of a `Cargo.lock`.
+fn packages(lockfile: &str) -> Vec<LockedPackage> {
+    let mut packages = Vec::new();
+    for entry in lockfile.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            entry.lines().find_map(|line| {
+                let (name, value) = line.split_once(" = ")?;
+                (name.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+            })
+        };
+        if let (Some(name), Some(version)) = (field("name"), field("version")) {
+            packages.push(LockedPackage {
+                name,
+                version,
+                source: field("source"),
+            });
+        }
+    }
+    packages
+}
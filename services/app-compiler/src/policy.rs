@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+
+use crate::{process_group, workspace, CARGO_BIN};
+
+/// Workspaces that passed are remembered up to this many, then forgotten all at once.
+const MAX_PASSED: usize = 1024;
+
+lazy_static! {
+    /// Fingerprints of the workspaces that passed, see [`fingerprint`].
+    static ref PASSED: Mutex<HashSet<[u8; 32]>> = Mutex::new(HashSet::new());
+}
+
+/// Hashes the workspace's manifest and lockfile, which are all that decides what
+/// it's built from. The manifests of the extra crates are the compiler's and don't
+/// declare any dependencies.
+async fn fingerprint(dir: &Path) -> Result<[u8; 32], ApiError> {
+    let manifest = fs::read(dir.join("Cargo.toml"))
+        .await
+        .map_err(ApiError::IoError)?;
+    // there's none before cargo resolved the workspace the first time
+    let lockfile = fs::read(dir.join("Cargo.lock")).await.unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update((manifest.len() as u64).to_le_bytes());
+    hasher.update(&manifest);
+    hasher.update(&lockfile);
+    Ok(hasher.finalize().into())
+}
+
+/// Runs cargo-deny against a workspace, using the `deny.toml` shipped with the app
+/// template. Licenses outside the allowlist, yanked crates, advisories and non
+/// crates.io sources fail the check. Workspaces that passed before aren't checked
+/// again.
+pub async fn check(dir: &Path) -> Result<(), ApiError> {
+    let before = fingerprint(dir).await?;
+    if PASSED.lock().unwrap().contains(&before) {
+        return Ok(());
+    }
+
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("deny")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"))
+        .arg("--color")
+        .arg("never")
+        .arg("check")
+        .arg("--config")
+        .arg(dir.join("deny.toml"))
+        .arg("licenses")
+        .arg("bans")
+        .arg("advisories")
+        .arg("sources");
+    debug!(?cmd, "running command");
+
+    let output = match process_group::output(cmd).await {
+        Ok(o) => o,
+        Err(e) => {
+            error!(?e, "running cargo deny failed");
+            return Err(ApiError::IoError(e));
+        }
+    };
+
+    if !output.status.success() {
+        return Err(ApiError::PolicyViolation(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    // cargo-deny writes the lockfile if the manifest changed
+    let after = fingerprint(dir).await?;
+    let mut passed = PASSED.lock().unwrap();
+    if passed.len() + 2 > MAX_PASSED {
+        passed.clear();
+    }
+    passed.extend([before, after]);
+    Ok(())
+}
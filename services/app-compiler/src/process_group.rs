@@ -0,0 +1,81 @@
+//! Lets the whole tree of processes a job started be killed when the request for it
+//! goes away, e.g. because the user cancelled the run. Killing just the tool the job
+//! ran would leave the cargo and rustc processes it spawned taking up the worker.
+
+use std::io;
+use std::process::{Output, Stdio};
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+use tracing::debug;
+
+/// Puts the process `cmd` spawns, and everything that one spawns, in a process group
+/// of its own.
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    cmd.process_group(0);
+}
+
+/// Kills the process group of a child spawned from an [`isolate`]d command when
+/// dropped, unless the child was [released](GroupGuard::release) after it exited.
+pub struct GroupGuard {
+    pgid: Option<u32>,
+}
+
+impl GroupGuard {
+    pub fn new(child: &Child) -> Self {
+        Self { pgid: child.id() }
+    }
+
+    /// Leaves the group alone, for once the child exited by itself.
+    pub fn release(mut self) {
+        self.pgid = None;
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        let Some(pgid) = self.pgid else {
+            return;
+        };
+        debug!(pgid, "killing the processes of a dropped job");
+        // SAFETY: only sends a signal. The group's leader is our child and hasn't been
+        // waited for, so its id can't have been reused.
+        #[cfg(target_os = "linux")]
+        unsafe {
+            libc::kill(-(pgid as libc::pid_t), libc::SIGKILL);
+        }
+    }
+}
+
+/// Runs the command like [`Command::output`], except that dropping the future kills
+/// everything it started.
+pub async fn output(cmd: &mut Command) -> io::Result<Output> {
+    run(cmd, None).await
+}
+
+/// Like [`output`], with `input` written to the command's stdin.
+pub async fn output_with_input(cmd: &mut Command, input: &[u8]) -> io::Result<Output> {
+    run(cmd, Some(input)).await
+}
+
+async fn run(cmd: &mut Command, input: Option<&[u8]>) -> io::Result<Output> {
+    isolate(cmd);
+    let stdin = match input {
+        Some(_) => Stdio::piped(),
+        None => Stdio::null(),
+    };
+    let mut child = cmd
+        .stdin(stdin)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let group = GroupGuard::new(&child);
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input).await?;
+    }
+    let output = child.wait_with_output().await?;
+    group.release();
+    Ok(output)
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use axum::extract::Path;
+use axum::http::{HeaderMap, Request};
+use axum::middleware::Next;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::Response;
+use futures::stream::{self, Stream};
+use lazy_static::lazy_static;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use common::errors::ApiError;
+use common::{BuildStage, PROGRESS_HEADER};
+
+/// How long a client waits for the build it follows to reach the compiler. Clients
+/// subscribe before starting the run, so their subscription usually comes first.
+const FOLLOW_GRACE: Duration = Duration::from_secs(5);
+/// How often a waiting client checks whether its build arrived.
+const FOLLOW_POLL: Duration = Duration::from_millis(100);
+
+lazy_static! {
+    /// Channels of the builds in flight, with the number of [`Progress`]es reporting
+    /// to each.
+    static ref CHANNELS: Mutex<HashMap<String, (watch::Sender<BuildStage>, usize)>> =
+        Mutex::new(HashMap::new());
+}
+
+fn channel(id: &str) -> watch::Sender<BuildStage> {
+    let mut channels = CHANNELS.lock().unwrap();
+    let (sender, reporters) = channels
+        .entry(id.to_string())
+        .or_insert_with(|| (watch::channel(BuildStage::Queued).0, 0));
+    *reporters += 1;
+    sender.clone()
+}
+
+/// Reports the stages of a build to whoever follows it. Does nothing for builds
+/// nobody asked to follow, and reports the build done when dropped.
+pub struct Progress {
+    channel: Option<(String, watch::Sender<BuildStage>)>,
+}
+
+impl Progress {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let id = headers.get(PROGRESS_HEADER).and_then(|it| it.to_str().ok());
+        Self {
+            channel: id.map(|it| (it.to_string(), channel(it))),
+        }
+    }
+
+    pub fn none() -> Self {
+        Self { channel: None }
+    }
+
+    pub fn report(&self, stage: BuildStage) {
+        if let Some((_, sender)) = &self.channel {
+            sender.send_if_modified(|current| {
+                // a late line must not rewind the stage
+                let advanced = stage > *current;
+                if advanced {
+                    *current = stage;
+                }
+                advanced
+            });
+        }
+    }
+
+    /// Advances the stage from a line trunk printed.
+    pub fn observe(&self, line: &str) {
+        // with colored output, cargo's status words are followed by an escape code
+        // rather than a space
+        if line.contains("wasm-opt") {
+            self.report(BuildStage::Optimizing);
+        } else if line.contains("wasm-bindgen") {
+            self.report(BuildStage::Bindgen);
+        } else if line.contains("Compiling") && line.contains(" app v") {
+            self.report(BuildStage::CompilingSnippet);
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.report(BuildStage::Done);
+        // the build is no longer in flight once nothing reports to its channel
+        if let Some((id, _)) = &self.channel {
+            let mut channels = CHANNELS.lock().unwrap();
+            if let Some((_, reporters)) = channels.get_mut(id) {
+                *reporters -= 1;
+                if *reporters == 0 {
+                    channels.remove(id);
+                }
+            }
+        }
+    }
+}
+
+/// Middleware that has the build a request runs followable while it waits in the
+/// queue, before its handler took over reporting.
+pub async fn track<B>(req: Request<B>, next: Next<B>) -> Response {
+    let _progress = Progress::from_headers(req.headers());
+    next.run(req).await
+}
+
+/// Streams the stages of the build in flight with the given progress id as
+/// server-sent events, ending once it's done.
+pub async fn follow(
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let deadline = Instant::now() + FOLLOW_GRACE;
+    let receiver = loop {
+        let receiver = CHANNELS
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|(sender, _)| sender.subscribe());
+        match receiver {
+            Some(receiver) => break receiver,
+            None if Instant::now() < deadline => tokio::time::sleep(FOLLOW_POLL).await,
+            None => return Err(ApiError::ProgressNotFound(id)),
+        }
+    };
+    let stages = stream::unfold(Some((receiver, true)), |state| async move {
+        let (mut receiver, first) = state?;
+        if !first && receiver.changed().await.is_err() {
+            return None;
+        }
+        let stage = *receiver.borrow_and_update();
+        let next = (stage != BuildStage::Done).then_some((receiver, false));
+        let event = Event::default().json_data(stage).expect("stages serialize");
+        Some((Ok(event), next))
+    });
+    Ok(Sse::new(stages).keep_alive(KeepAlive::default()))
+}
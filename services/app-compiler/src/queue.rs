@@ -0,0 +1,134 @@
+use std::mem;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use common::{Priority, PRIORITY_HEADER};
+
+use crate::QUEUE;
+
+/// Runs one job at a time, preferring interactive jobs over batch ones.
+///
+/// A waiting job's score is the time it has waited, plus the aging interval if it is
+/// interactive. A batch job therefore overtakes an interactive one once it has waited
+/// an aging interval longer, which keeps batch jobs from starving under load.
+pub struct BuildQueue {
+    state: Mutex<State>,
+    aging: Duration,
+}
+
+struct State {
+    running: bool,
+    waiting: Vec<Waiter>,
+}
+
+struct Waiter {
+    priority: Priority,
+    enqueued_at: Instant,
+    wake: oneshot::Sender<Permit>,
+}
+
+impl Waiter {
+    fn score(&self, now: Instant, aging: Duration) -> Duration {
+        let waited = now.duration_since(self.enqueued_at);
+        match self.priority {
+            Priority::Interactive => waited + aging,
+            Priority::Batch => waited,
+        }
+    }
+}
+
+/// Exclusive right to run a job. The next job is started when this is dropped.
+pub struct Permit {
+    queue: &'static BuildQueue,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+impl BuildQueue {
+    pub fn new(aging: Duration) -> Self {
+        Self {
+            state: Mutex::new(State {
+                running: false,
+                waiting: Vec::new(),
+            }),
+            aging,
+        }
+    }
+
+    pub async fn acquire(&'static self, priority: Priority) -> Permit {
+        let rx = {
+            let mut state = self.state.lock().unwrap();
+            if !state.running {
+                state.running = true;
+                return Permit { queue: self };
+            }
+            let (tx, rx) = oneshot::channel();
+            state.waiting.push(Waiter {
+                priority,
+                enqueued_at: Instant::now(),
+                wake: tx,
+            });
+            debug!(?priority, depth = state.waiting.len(), "job queued");
+            rx
+        };
+        // the sender is only dropped after a permit has been sent through it
+        rx.await.expect("queue dropped a waiter")
+    }
+
+    /// Number of jobs waiting to run.
+    pub fn depth(&self) -> usize {
+        self.state.lock().unwrap().waiting.len()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    fn release(&'static self) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        loop {
+            let next = state
+                .waiting
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, waiter)| waiter.score(now, self.aging))
+                .map(|(index, _)| index);
+            let Some(next) = next else {
+                state.running = false;
+                return;
+            };
+            let waiter = state.waiting.swap_remove(next);
+            match waiter.wake.send(Permit { queue: self }) {
+                Ok(()) => return,
+                // the request gave up waiting; don't let the returned permit release
+                // recursively while we hold the lock, just try the next waiter
+                Err(permit) => mem::forget(permit),
+            }
+        }
+    }
+}
+
+/// Middleware that holds a queue permit for the duration of the request. The
+/// priority is read from the priority header and defaults to interactive.
+pub async fn prioritize<B>(req: Request<B>, next: Next<B>) -> Response {
+    let priority = req
+        .headers()
+        .get(PRIORITY_HEADER)
+        .and_then(|it| it.to_str().ok())
+        .and_then(Priority::from_header)
+        .unwrap_or(Priority::Interactive);
+
+    let _permit = QUEUE.acquire(priority).await;
+    next.run(req).await
+}
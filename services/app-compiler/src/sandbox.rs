@@ -0,0 +1,166 @@
+//! Confines the build tools a job spawns, and everything they spawn in turn such
+//! as rustc, build scripts and proc-macros, as an extra layer on top of the
+//! container.
+//!
+//! A seccomp filter denies creating any socket but Unix domain ones, so nothing
+//! in the build can reach the network, and a Landlock ruleset makes everything
+//! but the job's workspace, its temporary directory and a few devices read-only.
+//! The toolchains, cargo's registry, the tools trunk caches and the other jobs'
+//! workspaces and artifacts can't be tampered with, but they can still be read
+//! like the rest of the filesystem. Dependencies are fetched before a build, by
+//! the unsandboxed steps that prepare its workspace.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command;
+use tracing::warn;
+
+use common::errors::ApiError;
+
+use crate::SANDBOX_BUILDS;
+
+/// Cargo's package cache locks, which it takes even when building offline.
+const CARGO_LOCKS: [&str; 3] = [".package-cache", ".package-cache-mutate", ".global-cache"];
+/// Devices the build tools write to, the rest of `/dev` is read-only.
+const DEVICES: [&str; 3] = ["/dev/null", "/dev/urandom", "/dev/tty"];
+
+/// Whether [`apply`] confines commands, which takes sandboxing to be enabled and a
+/// system that supports it.
+pub fn active() -> bool {
+    *SANDBOX_BUILDS && cfg!(target_os = "linux")
+}
+
+/// Applies the sandbox to `cmd` if sandboxing is enabled. `workspace` is the only
+/// directory of the job the command may write to, besides a temporary directory of its
+/// own that `TMPDIR` is set to. The system's is shared with the other jobs and holds
+/// their workspaces unless `WORKSPACES_DIR` says otherwise.
+pub fn apply(cmd: &mut Command, workspace: &Path) -> Result<(), ApiError> {
+    if !*SANDBOX_BUILDS {
+        return Ok(());
+    }
+    let temp_dir = temp_dir(workspace).map_err(sandbox_error)?;
+    // the registry is read-only, so cargo must make do with what's been fetched
+    cmd.env("TMPDIR", &temp_dir)
+        .env("CARGO_NET_OFFLINE", "true");
+    imp::apply(cmd, &writable_paths(workspace, &temp_dir))
+}
+
+/// The job's temporary directory in its workspace, emptied for every job.
+fn temp_dir(workspace: &Path) -> io::Result<PathBuf> {
+    let dir = workspace.join(".tmp");
+    match fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    fs::create_dir(&dir)?;
+    Ok(dir)
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|it| PathBuf::from(it).join(".cargo")))
+}
+
+/// What the build tools may write to. Of cargo's home that's only the locks, its
+/// `registry` holds the sources and its `bin` the tools every job uses.
+fn writable_paths(workspace: &Path, temp_dir: &Path) -> Vec<PathBuf> {
+    let mut paths = vec![workspace.to_path_buf(), temp_dir.to_path_buf()];
+    paths.extend(DEVICES.map(PathBuf::from));
+    if let Some(cargo_home) = cargo_home() {
+        for lock in CARGO_LOCKS {
+            let lock = cargo_home.join(lock);
+            // the job can't create them in cargo's home itself
+            if let Err(e) = OpenOptions::new().create(true).append(true).open(&lock) {
+                warn!(?e, ?lock, "failed to create cargo's lock file");
+            }
+            paths.push(lock);
+        }
+    }
+    paths.into_iter().filter(|it| it.exists()).collect()
+}
+
+fn sandbox_error(e: impl std::error::Error + Send + Sync + 'static) -> ApiError {
+    ApiError::Unknown(anyhow::Error::new(e).context("failed to set up build sandbox"))
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::collections::BTreeMap;
+    use std::io;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, Ruleset, RulesetAttr, RulesetCreatedAttr, ABI,
+    };
+    use seccompiler::{
+        BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,
+        SeccompRule,
+    };
+
+    use super::*;
+
+    pub fn apply(cmd: &mut Command, writable: &[PathBuf]) -> Result<(), ApiError> {
+        // everything is prepared before forking, the child only has to install it
+        let filter = network_filter()?;
+        let abi = ABI::V2;
+        let ruleset = Ruleset::default()
+            .handle_access(AccessFs::from_all(abi))
+            .and_then(|it| it.create())
+            .and_then(|it| it.add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi))))
+            .and_then(|it| it.add_rules(path_beneath_rules(writable, AccessFs::from_all(abi))))
+            .map_err(sandbox_error)?;
+        let ruleset = Mutex::new(Some(ruleset));
+
+        // SAFETY: the closure only makes the prctl, landlock and seccomp syscalls
+        // needed to restrict the child and doesn't allocate
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(ruleset) = ruleset.lock().ok().and_then(|mut it| it.take()) {
+                    // kernels without Landlock support leave the ruleset unenforced
+                    ruleset.restrict_self().map_err(io::Error::other)?;
+                }
+                seccompiler::apply_filter(&filter).map_err(io::Error::other)
+            });
+        }
+        Ok(())
+    }
+
+    /// Denies `socket(2)` for every domain but `AF_UNIX`, which cargo's jobserver
+    /// and the tools' own IPC may use.
+    fn network_filter() -> Result<BpfProgram, ApiError> {
+        let not_unix = SeccompCondition::new(
+            0,
+            SeccompCmpArgLen::Dword,
+            SeccompCmpOp::Ne,
+            libc::AF_UNIX as u64,
+        )
+        .map_err(sandbox_error)?;
+        let rules = BTreeMap::from([(
+            libc::SYS_socket,
+            vec![SeccompRule::new(vec![not_unix]).map_err(sandbox_error)?],
+        )]);
+        let arch = std::env::consts::ARCH.try_into().map_err(sandbox_error)?;
+        let filter = SeccompFilter::new(
+            rules,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EACCES as u32),
+            arch,
+        )
+        .map_err(sandbox_error)?;
+        filter.try_into().map_err(sandbox_error)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::*;
+
+    pub fn apply(_cmd: &mut Command, _writable: &[PathBuf]) -> Result<(), ApiError> {
+        warn!("build sandboxing is only supported on Linux, building unconfined");
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use axum::extract::{Query, RawBody};
+use axum::http::HeaderMap;
+use tokio::fs;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Negotiate;
+use common::{BuildOptions, Profile, SsrResponse};
+
+use crate::{process_group, sandbox, workspace, CARGO_BIN};
+
+/// Binary that renders the snippet's `App` component to a string. The snippet is
+/// included in a module of the harness so its private items stay reachable.
+/// Modules declared by the snippet would resolve relative to the harness, so only
+/// single-file snippets and extra crates are supported.
+const HARNESS: &str = r#"#[allow(dead_code)]
+mod snippet {
+    include!("../main.rs");
+
+    pub async fn render() -> String {
+        yew::ServerRenderer::<App>::new().render().await
+    }
+}
+
+fn main() {
+    print!("{}", futures::executor::block_on(snippet::render()));
+}
+"#;
+
+/// Builds the snippet with Yew's `ssr` feature for the native target and returns
+/// the HTML its `App` component renders to.
+///
+/// Refused unless builds are sandboxed: the snippet runs natively on the compiler's
+/// host rather than in a browser.
+pub async fn ssr(
+    Query(options): Query<BuildOptions>,
+    headers: HeaderMap,
+    RawBody(body): RawBody,
+) -> Result<Negotiate<SsrResponse>, ApiError> {
+    if !sandbox::active() {
+        return Err(ApiError::SsrUnavailable);
+    }
+    let snippet = workspace::read_snippet(&headers, body).await?;
+    let app_dir = workspace::prepare_ssr(&options, &snippet).await?;
+
+    let harness = app_dir.join("src/bin/ssr.rs");
+    fs::create_dir_all(app_dir.join("src/bin"))
+        .await
+        .map_err(ApiError::IoError)?;
+    if let Err(e) = fs::write(&harness, HARNESS).await {
+        error!(?e, "failed to write ssr harness");
+        return Err(ApiError::IoError(e));
+    }
+
+    let mut cmd = workspace::command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("run")
+        .arg("--manifest-path")
+        .arg(app_dir.join("Cargo.toml"))
+        .arg("--bin")
+        .arg("ssr")
+        .arg("--features")
+        .arg("ssr")
+        .arg("--color")
+        .arg("never");
+    if options.profile != Profile::Debug {
+        cmd.arg("--release");
+    }
+    workspace::apply_options(cmd, &options);
+    sandbox::apply(cmd, &app_dir)?;
+    debug!(?cmd, "running command");
+
+    // the snippet runs natively here, dropping the future kills it along with cargo so
+    // it doesn't outlive a timed out request
+    let output = match process_group::output(cmd).await {
+        Ok(o) => o,
+        Err(e) => {
+            error!(?e, "running ssr build failed");
+            return Err(ApiError::IoError(e));
+        }
+    };
+
+    if !output.status.success() {
+        return Ok(Negotiate(SsrResponse::CompileError(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        )));
+    }
+
+    Ok(Negotiate(SsrResponse::Rendered(
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )))
+}
@@ -0,0 +1,144 @@
+use std::io;
+use std::process::{Output, Stdio};
+use std::time::Instant;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader, Lines};
+use tokio::process::Command;
+
+use common::BuildTimings;
+
+use crate::process_group::{self, GroupGuard};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Phase {
+    Resolving,
+    Compiling,
+    Bindgen,
+    Optimizing,
+}
+
+/// Tracks which phase a trunk build is in from the lines it prints.
+struct PhaseTracker {
+    phase: Phase,
+    since: Instant,
+    timings: BuildTimings,
+}
+
+impl PhaseTracker {
+    fn new(start: Instant) -> Self {
+        Self {
+            phase: Phase::Resolving,
+            since: start,
+            timings: BuildTimings::default(),
+        }
+    }
+
+    fn observe(&mut self, line: &str) {
+        // with colored output, cargo's status words are followed by an escape code
+        // rather than a space
+        let next = if line.contains("wasm-opt") {
+            Phase::Optimizing
+        } else if line.contains("wasm-bindgen") {
+            Phase::Bindgen
+        } else if line.contains("Compiling") || line.contains("Finished") {
+            Phase::Compiling
+        } else {
+            return;
+        };
+        // phases only move forward, a late cargo line must not rewind to compiling
+        if next > self.phase {
+            self.enter(next, Instant::now());
+        }
+    }
+
+    fn enter(&mut self, phase: Phase, now: Instant) {
+        let elapsed = now.duration_since(self.since).as_millis() as u64;
+        match self.phase {
+            Phase::Resolving => self.timings.dependency_resolution_ms += elapsed,
+            Phase::Compiling => self.timings.rustc_ms += elapsed,
+            Phase::Bindgen => self.timings.wasm_bindgen_ms += elapsed,
+            Phase::Optimizing => self.timings.wasm_opt_ms += elapsed,
+        }
+        self.phase = phase;
+        self.since = now;
+    }
+
+    fn finish(mut self, start: Instant) -> BuildTimings {
+        let now = Instant::now();
+        self.enter(self.phase, now);
+        self.timings.total_ms = now.duration_since(start).as_millis() as u64;
+        self.timings
+    }
+}
+
+async fn next_line<R: AsyncRead + Unpin>(
+    lines: &mut Option<Lines<BufReader<R>>>,
+) -> Option<io::Result<Option<String>>> {
+    match lines {
+        Some(lines) => Some(lines.next_line().await),
+        None => None,
+    }
+}
+
+/// Runs a build command, timing its phases as its output is streamed. Every line
+/// is also handed to `on_line`.
+pub async fn output_with_timings(
+    cmd: &mut Command,
+    mut on_line: impl FnMut(&str),
+) -> io::Result<(Output, BuildTimings)> {
+    let start = Instant::now();
+    let mut tracker = PhaseTracker::new(start);
+    let output = stream_output(cmd, |line| {
+        tracker.observe(line);
+        on_line(line);
+    })
+    .await?;
+    Ok((output, tracker.finish(start)))
+}
+
+/// Runs a command, handing every line it prints on stdout or stderr to `on_line`
+/// as soon as it's printed. Dropping the future kills the command and everything it
+/// started.
+pub async fn stream_output(cmd: &mut Command, mut on_line: impl FnMut(&str)) -> io::Result<Output> {
+    process_group::isolate(cmd);
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let group = GroupGuard::new(&child);
+
+    let mut stdout_lines = child.stdout.take().map(|it| BufReader::new(it).lines());
+    let mut stderr_lines = child.stderr.take().map(|it| BufReader::new(it).lines());
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+
+    while stdout_lines.is_some() || stderr_lines.is_some() {
+        tokio::select! {
+            Some(line) = next_line(&mut stdout_lines) => match line? {
+                Some(line) => {
+                    on_line(&line);
+                    stdout.push_str(&line);
+                    stdout.push('\n');
+                }
+                None => stdout_lines = None,
+            },
+            Some(line) = next_line(&mut stderr_lines) => match line? {
+                Some(line) => {
+                    on_line(&line);
+                    stderr.push_str(&line);
+                    stderr.push('\n');
+                }
+                None => stderr_lines = None,
+            },
+        }
+    }
+
+    let status = child.wait().await?;
+    group.release();
+    Ok(Output {
+        status,
+        stdout: stdout.into_bytes(),
+        stderr: stderr.into_bytes(),
+    })
+}
@@ -0,0 +1,551 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::ops::Deref;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap};
+use tokio::fs;
+use tokio::process::Command;
+use toml::Value;
+use tracing::{debug, error};
+
+use common::errors::ApiError;
+use common::response::Format;
+use common::{
+    BuildOptions, Snippet, Toolchain, YewVersion, ALLOWED_CRATES, PREINSTALLED_CRATES,
+    RUSTFLAGS_PRESETS,
+};
+
+use crate::{
+    policy, process_group, sandbox, ALLOW_CUSTOM_DEPENDENCIES, APP_DIR, CARGO_BIN, OFFLINE_BUILDS,
+    WORKSPACES_DIR,
+};
+
+/// Name of the file whose modification time records when a workspace was last used.
+const LAST_USED: &str = ".last-used";
+/// Suffix of a workspace that is still being copied from the app template.
+pub const PARTIAL_SUFFIX: &str = ".partial";
+/// Suffix of the file locked while a workspace is in use. Lock files live next to
+/// the workspaces rather than inside them so they outlive evictions.
+const LOCK_SUFFIX: &str = ".lock";
+
+/// Versions of the crates that have to match the Yew release a snippet picked. The
+/// app template pins the ones going along with the default release.
+const YEW_PINS: &[(YewVersion, &[(&str, &str)])] = &[(
+    YewVersion::V0_20,
+    &[
+        ("yew", "0.20"),
+        ("yew-router", "0.17"),
+        ("yew-hooks", "0.2"),
+        ("stylist", "0.12"),
+    ],
+)];
+
+/// A workspace checked out for a job.
+///
+/// The workspaces directory may be a volume shared by several compiler replicas,
+/// so the workspace is guarded by an exclusive file lock rather than in-process
+/// state. Jobs on any replica that need the same workspace wait until this one is
+/// dropped.
+pub struct Workspace {
+    dir: PathBuf,
+    _lock: std::fs::File,
+}
+
+impl Deref for Workspace {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Reads the snippet from the request body. A JSON, BSON or CBOR body holds a
+/// multi-file [`Snippet`], anything else is taken as the contents of `src/main.rs`.
+pub async fn read_snippet(headers: &HeaderMap, body: Body) -> Result<Snippet, ApiError> {
+    let body = hyper::body::to_bytes(body).await.unwrap();
+    if body.is_empty() {
+        return Err(ApiError::NoBody);
+    }
+
+    let format = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|it| it.to_str().ok())
+        .and_then(Format::from_mime);
+    let format = match format {
+        Some(format) => format,
+        None => return Ok(Snippet::single(String::from_utf8_lossy(&body).into_owned())),
+    };
+
+    let snippet: Snippet = format
+        .decode(&body)
+        .map_err(|e| ApiError::InvalidSnippet(e.to_string()))?;
+    for path in snippet.files.keys() {
+        let allowed = path.starts_with("src/") || path.starts_with("crates/");
+        let escapes = Path::new(path)
+            .components()
+            .any(|it| !matches!(it, Component::Normal(_)));
+        if !allowed || escapes {
+            return Err(ApiError::InvalidSnippet(format!(
+                "files must be under src/ or crates/, got {}",
+                path
+            )));
+        }
+    }
+    if snippet.main().is_none() {
+        return Err(ApiError::InvalidSnippet(format!(
+            "{} is missing",
+            Snippet::MAIN
+        )));
+    }
+    // the compiler writes the manifests of the extra crates, what they'd declare
+    // themselves would get around the allowed crates and the policy
+    for path in snippet.files.keys() {
+        let name = Path::new(path).file_name().unwrap_or_default();
+        if name == "Cargo.toml" || name == "build.rs" {
+            return Err(ApiError::InvalidSnippet(format!(
+                "{} is written by the playground, it can't be part of a snippet",
+                path
+            )));
+        }
+    }
+    if let Some(name) = snippet
+        .crates()
+        .into_iter()
+        .find(|it| !Snippet::is_crate_name(it))
+    {
+        return Err(ApiError::InvalidSnippet(format!(
+            "{:?} isn't a valid crate name",
+            name
+        )));
+    }
+    Ok(snippet)
+}
+
+/// Creates a command for a build tool that runs against a workspace.
+///
+/// When offline builds are enabled, cargo resolves dependencies only from the
+/// vendored registry baked into the image and never reaches out to crates.io.
+pub fn command(bin: &str) -> Command {
+    let mut cmd = Command::new(bin);
+    if *OFFLINE_BUILDS {
+        cmd.env("CARGO_NET_OFFLINE", "true");
+    }
+    cmd
+}
+
+/// Enables the toolchain, pre-installed crates and `RUSTFLAGS` presets requested by
+/// `options` on a cargo or trunk command. `options` must have been validated by
+/// [`prepare`].
+pub fn apply_options(cmd: &mut Command, options: &BuildOptions) {
+    // stable is whatever the image installed as its default toolchain
+    if options.toolchain != Toolchain::Stable {
+        cmd.env("RUSTUP_TOOLCHAIN", options.toolchain.as_str());
+    }
+
+    let features = options.features().collect::<Vec<_>>();
+    if !features.is_empty() {
+        cmd.arg("--features").arg(features.join(","));
+    }
+
+    let rustflags = rustflags(options);
+    if !rustflags.is_empty() {
+        cmd.env("RUSTFLAGS", rustflags);
+    }
+}
+
+/// The `RUSTFLAGS` the presets selected by `options` expand to.
+pub fn rustflags(options: &BuildOptions) -> String {
+    options
+        .rustflags()
+        .filter_map(|name| RUSTFLAGS_PRESETS.iter().find(|(it, _)| *it == name))
+        .map(|(_, flags)| *flags)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Writes the snippet's files into the workspace for `options` and returns the
+/// checked out workspace.
+pub async fn prepare(options: &BuildOptions, snippet: &Snippet) -> Result<Workspace, ApiError> {
+    prepare_in(&key(options), options, snippet).await
+}
+
+/// Like [`prepare`], but in a workspace of its own for native server-side
+/// rendering builds, so they don't share a target directory with the web builds.
+pub async fn prepare_ssr(options: &BuildOptions, snippet: &Snippet) -> Result<Workspace, ApiError> {
+    prepare_in(&format!("ssr-{}", key(options)), options, snippet).await
+}
+
+async fn prepare_in(
+    key: &str,
+    options: &BuildOptions,
+    snippet: &Snippet,
+) -> Result<Workspace, ApiError> {
+    if options.dependencies.is_some() && !*ALLOW_CUSTOM_DEPENDENCIES {
+        return Err(ApiError::CustomDependenciesDisabled);
+    }
+    let dependencies = options
+        .dependencies
+        .as_deref()
+        .map(custom_dependencies)
+        .transpose()?;
+    if let Some(unknown) = options
+        .features()
+        .find(|it| !PREINSTALLED_CRATES.contains(it))
+    {
+        return Err(ApiError::UnknownFeature(unknown.to_string()));
+    }
+    if let Some(unknown) = options
+        .rustflags()
+        .find(|name| !RUSTFLAGS_PRESETS.iter().any(|(it, _)| it == name))
+    {
+        return Err(ApiError::UnknownRustflagsPreset(unknown.to_string()));
+    }
+    // only the crates of the default release are vendored
+    if options.yew != YewVersion::default() && *OFFLINE_BUILDS {
+        return Err(ApiError::YewVersionUnavailable(options.yew.as_str()));
+    }
+
+    let dir = checkout(key).await?;
+    write_manifest(&dir, options, dependencies.as_deref(), snippet).await?;
+
+    // files of the previous job must not leak into this one
+    for stale in ["src", "crates"] {
+        if fs::metadata(dir.join(stale)).await.is_ok() {
+            fs::remove_dir_all(dir.join(stale))
+                .await
+                .map_err(ApiError::IoError)?;
+        }
+    }
+    for (path, contents) in &snippet.files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(ApiError::IoError)?;
+        }
+        if let Err(e) = fs::write(&path, contents).await {
+            error!(?e, ?path, "failed to write snippet file");
+            return Err(ApiError::IoError(e));
+        }
+    }
+    for name in snippet.crates() {
+        let path = dir.join("crates").join(name).join("Cargo.toml");
+        if let Err(e) = fs::write(&path, member_manifest(name, options)).await {
+            error!(?e, ?path, "failed to write the manifest of an extra crate");
+            return Err(ApiError::IoError(e));
+        }
+    }
+    // once the extra crates are in place, cargo-deny resolves them along with the rest
+    policy::check(&dir).await?;
+    fetch(&dir).await?;
+
+    Ok(dir)
+}
+
+/// Downloads the workspace's dependencies into cargo's registry, which sandboxed
+/// builds can't write to. Offline builds take them from the vendored registry.
+async fn fetch(dir: &Path) -> Result<(), ApiError> {
+    if !sandbox::active() || *OFFLINE_BUILDS {
+        return Ok(());
+    }
+    let mut cmd = command(&CARGO_BIN);
+    let cmd = cmd
+        .arg("fetch")
+        .arg("--manifest-path")
+        .arg(dir.join("Cargo.toml"));
+    debug!(?cmd, "running command");
+
+    let output = process_group::output(cmd).await.map_err(|e| {
+        error!(?e, "running cargo fetch failed");
+        ApiError::IoError(e)
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ApiError::Unknown(anyhow::anyhow!(
+            "cargo fetch failed: {}",
+            stderr
+        )));
+    }
+    Ok(())
+}
+
+/// Reads custom dependencies, `name = "version"` or `name = { version, features,
+/// default-features }` entries of the [`ALLOWED_CRATES`], and writes them back for the
+/// manifest. Nothing else cargo reads there gets through, `path`, `git` and `package`
+/// would have it build something other than the allowed crates.
+fn custom_dependencies(toml: &str) -> Result<String, ApiError> {
+    let entries: toml::Table = toml
+        .parse()
+        .map_err(|e: toml::de::Error| ApiError::InvalidDependency(e.message().to_string()))?;
+    let mut dependencies = Vec::with_capacity(entries.len());
+    for (name, spec) in entries {
+        if !ALLOWED_CRATES.iter().any(|(it, _)| *it == name) {
+            return Err(ApiError::CrateNotAllowed(name));
+        }
+        let invalid = |reason: String| ApiError::InvalidDependency(format!("{}: {}", name, reason));
+        let spec = match spec {
+            Value::String(version) => {
+                toml::Table::from_iter([("version".to_string(), Value::String(version))])
+            }
+            Value::Table(spec) => spec,
+            _ => return Err(invalid("must be a version or a table".to_string())),
+        };
+        let mut fields = Vec::with_capacity(spec.len());
+        for (key, value) in spec {
+            let valid = match (key.as_str(), &value) {
+                ("version", Value::String(_)) | ("default-features", Value::Boolean(_)) => true,
+                ("features", Value::Array(features)) => features.iter().all(Value::is_str),
+                ("version" | "features" | "default-features", _) => false,
+                _ => return Err(invalid(format!("{} is not allowed", key))),
+            };
+            if !valid {
+                return Err(invalid(format!("{} has the wrong type", key)));
+            }
+            fields.push(format!("{} = {}", key, value));
+        }
+        dependencies.push(format!("{} = {{ {} }}", name, fields.join(", ")));
+    }
+    Ok(dependencies.join("\n"))
+}
+
+/// Writes the workspace's `Cargo.toml` from the app template, with the requested
+/// edition and Yew release, the custom dependencies [`custom_dependencies`] wrote and
+/// the snippet's extra crates. The edition only affects the snippet's crate so it
+/// doesn't need a workspace of its own.
+async fn write_manifest(
+    dir: &Path,
+    options: &BuildOptions,
+    dependencies: Option<&str>,
+    snippet: &Snippet,
+) -> Result<(), ApiError> {
+    let template = fs::read_to_string(Path::new(&*APP_DIR).join("Cargo.toml"))
+        .await
+        .map_err(ApiError::IoError)?;
+    let crates = snippet.crates();
+    let mut manifest = String::with_capacity(template.len());
+    for line in template.lines() {
+        if line.starts_with("edition") {
+            manifest.push_str(&format!("edition = \"{}\"\n", options.edition));
+            continue;
+        }
+        if let Some(pinned) = pin_version(line, options.yew) {
+            manifest.push_str(&pinned);
+            manifest.push('\n');
+            continue;
+        }
+        manifest.push_str(line);
+        manifest.push('\n');
+        if line.trim() == "[dependencies]" {
+            if let Some(dependencies) = dependencies {
+                manifest.push_str(dependencies);
+                manifest.push('\n');
+            }
+            for name in &crates {
+                manifest.push_str(&format!("{} = {{ path = \"crates/{}\" }}\n", name, name));
+            }
+        }
+    }
+    if !crates.is_empty() {
+        manifest.push_str("\n[workspace]\nmembers = [\"crates/*\"]\n");
+    }
+    fs::write(dir.join("Cargo.toml"), manifest)
+        .await
+        .map_err(|e| {
+            error!(?e, "failed to write Cargo.toml");
+            ApiError::IoError(e)
+        })
+}
+
+/// The manifest of one of the snippet's extra crates: a library without dependencies,
+/// build script or proc-macros, in the snippet's edition.
+fn member_manifest(name: &str, options: &BuildOptions) -> String {
+    format!(
+        r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "{}"
+build = false
+
+[lib]
+path = "src/lib.rs"
+"#,
+        name, options.edition
+    )
+}
+
+/// Rewrites a dependency line of the app template to the version in [`YEW_PINS`],
+/// if it declares one of the crates pinned along with the Yew release.
+fn pin_version(line: &str, yew: YewVersion) -> Option<String> {
+    let (_, pins) = YEW_PINS.iter().find(|(it, _)| *it == yew)?;
+    let (name, rest) = line.split_once(" = ")?;
+    let (_, version) = pins.iter().find(|(it, _)| *it == name.trim())?;
+    let start = rest.find("version = \"")? + "version = \"".len();
+    let end = start + rest[start..].find('"')?;
+    Some(format!(
+        "{} = {}{}{}",
+        name,
+        &rest[..start],
+        version,
+        &rest[end..]
+    ))
+}
+
+/// Builds with different options get their own workspace, so switching between
+/// them doesn't invalidate each other's target cache. Changing `RUSTFLAGS`, the
+/// toolchain or the Yew release rebuilds everything, so they're part of the key
+/// as well.
+fn key(options: &BuildOptions) -> String {
+    let profile = options.profile.as_str();
+    let mut presets = options.rustflags().collect::<Vec<_>>();
+    let defaults =
+        options.toolchain == Toolchain::default() && options.yew == YewVersion::default();
+    if options.dependencies.is_none() && presets.is_empty() && defaults {
+        return profile.to_string();
+    }
+
+    presets.sort_unstable();
+    presets.dedup();
+    let mut hasher = DefaultHasher::new();
+    options.dependencies.hash(&mut hasher);
+    presets.hash(&mut hasher);
+    options.toolchain.hash(&mut hasher);
+    options.yew.hash(&mut hasher);
+    format!("{}-{:016x}", profile, hasher.finish())
+}
+
+/// Returns the workspace for `key`, copying it from the app template on first use.
+///
+/// Workspaces keep their target directory between jobs, so everything but the
+/// snippet itself stays compiled.
+pub async fn checkout(key: &str) -> Result<Workspace, ApiError> {
+    let root = workspaces_root().await?;
+    let dir = root.join(key);
+    let lock = lock_key(&root, key).await?;
+
+    if fs::metadata(&dir).await.is_err() {
+        let template = fs::canonicalize(&*APP_DIR).await.map_err(|e| {
+            error!(?e, "failed to canonicalize app_dir path");
+            ApiError::IoError(e)
+        })?;
+        let partial = root.join(format!("{}{}", key, PARTIAL_SUFFIX));
+        debug!(?template, ?dir, "creating workspace");
+
+        let copy_to = partial.clone();
+        tokio::task::spawn_blocking(move || copy_dir(&template, &copy_to))
+            .await
+            .expect("workspace copy panicked")
+            .map_err(|e| {
+                error!(?e, "failed to copy app template");
+                ApiError::IoError(e)
+            })?;
+        fs::rename(&partial, &dir)
+            .await
+            .map_err(ApiError::IoError)?;
+    }
+
+    fs::write(dir.join(LAST_USED), []).await.map_err(|e| {
+        error!(?e, "failed to mark workspace as used");
+        ApiError::IoError(e)
+    })?;
+
+    Ok(Workspace { dir, _lock: lock })
+}
+
+/// Returns an empty workspace for `key`, for tools that don't build anything but are
+/// sandboxed all the same, like rustfmt reading the snippet from stdin.
+pub async fn scratch(key: &str) -> Result<Workspace, ApiError> {
+    let root = workspaces_root().await?;
+    let dir = root.join(key);
+    let lock = lock_key(&root, key).await?;
+    fs::create_dir_all(&dir).await.map_err(ApiError::IoError)?;
+    fs::write(dir.join(LAST_USED), []).await.map_err(|e| {
+        error!(?e, "failed to mark workspace as used");
+        ApiError::IoError(e)
+    })?;
+    Ok(Workspace { dir, _lock: lock })
+}
+
+async fn lock_key(root: &Path, key: &str) -> Result<std::fs::File, ApiError> {
+    let lock_path = root.join(format!("{}{}", key, LOCK_SUFFIX));
+    tokio::task::spawn_blocking(move || {
+        let file = open_lock(&lock_path)?;
+        file.lock()?;
+        Ok::<_, io::Error>(file)
+    })
+    .await
+    .expect("locking workspace panicked")
+    .map_err(|e| {
+        error!(?e, "failed to lock workspace");
+        ApiError::IoError(e)
+    })
+}
+
+fn open_lock(path: &Path) -> io::Result<std::fs::File> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+}
+
+/// Locks the workspace or partial copy at `dir` without waiting, for the janitor.
+/// Returns `None` if a job, possibly on another replica, is using it.
+pub fn try_lock(dir: &Path) -> io::Result<Option<std::fs::File>> {
+    let file = open_lock(&lock_path_of(dir))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(std::fs::TryLockError::WouldBlock) => Ok(None),
+        Err(std::fs::TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// Locks the workspace or partial copy at `dir`, waiting for the job using it.
+pub fn lock(dir: &Path) -> io::Result<std::fs::File> {
+    let file = open_lock(&lock_path_of(dir))?;
+    file.lock()?;
+    Ok(file)
+}
+
+fn lock_path_of(dir: &Path) -> PathBuf {
+    let name = dir.to_string_lossy();
+    let key = name.strip_suffix(PARTIAL_SUFFIX).unwrap_or(&name);
+    PathBuf::from(format!("{}{}", key, LOCK_SUFFIX))
+}
+
+pub async fn workspaces_root() -> Result<PathBuf, ApiError> {
+    fs::create_dir_all(&*WORKSPACES_DIR)
+        .await
+        .map_err(ApiError::IoError)?;
+    fs::canonicalize(&*WORKSPACES_DIR)
+        .await
+        .map_err(ApiError::IoError)
+}
+
+/// When the workspace at `dir` was last checked out.
+pub fn last_used(dir: &Path) -> SystemTime {
+    std::fs::metadata(dir.join(LAST_USED))
+        .and_then(|it| it.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Recursively copies `from` to `to`, leaving out build output.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == "dist" {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Rooms for editing a snippet together. Every client keeps a Yjs document of the code
+//! and sends the updates of its edits here, where they're merged into the room's copy
+//! and passed on to the others in the room. Clients joining later start from the room's
+//! copy. Rooms are kept in memory and dropped once the last client leaves.
+//!
+//! Next to the updates, which are binary messages, clients send their presence as text:
+//! a JSON object of their name, color and selection that the room doesn't look into.
+//! It's passed on as `{"peer": <id>, "presence": <object>}`, with a `null` presence
+//! once the peer left.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::Path as UrlPath;
+use axum::response::Response;
+use common::errors::ApiError;
+use lazy_static::lazy_static;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+use serde_json::{json, Map, Value};
+use yrs::updates::decoder::Decode;
+use yrs::{Doc, ReadTxn, StateVector, Transact, Update};
+
+/// Rooms are for pairing on a snippet, not for broadcasting it.
+const MAX_PEERS: usize = 16;
+/// Of a single update, a whole snippet pasted in at once is well below it.
+const MAX_UPDATE_SIZE: usize = 256 * 1024;
+/// Of a room's document, it stops taking updates past it.
+const MAX_ROOM_SIZE: usize = 16 * 1024 * 1024;
+/// Rooms open at once, each may take up to [`MAX_ROOM_SIZE`].
+const MAX_ROOMS: usize = 256;
+/// Of the documents of all rooms together, updates that would go past it are rejected.
+const MAX_TOTAL_SIZE: usize = 1024 * 1024 * 1024;
+/// Of a presence, a name and a selection take a small fraction of it.
+const MAX_PRESENCE_SIZE: usize = 4 * 1024;
+/// Messages a client may fall behind on before it's disconnected, it catches up on
+/// everything it missed when it reconnects.
+const BACKLOG: usize = 256;
+
+#[derive(Clone)]
+enum Event {
+    Update(Arc<[u8]>),
+    /// Ready to be sent, see the module docs.
+    Presence(Arc<str>),
+}
+
+impl From<Event> for Message {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::Update(update) => Message::Binary(update.to_vec()),
+            Event::Presence(presence) => Message::Text(presence.to_string()),
+        }
+    }
+}
+
+struct Room {
+    doc: Doc,
+    /// Events along with the peer they came from, which doesn't need them back.
+    events: broadcast::Sender<(u64, Event)>,
+    /// The last presence of every peer that sent one, for those joining later.
+    presences: HashMap<u64, Arc<str>>,
+    peers: usize,
+    /// Bytes of the document when it was last encoded, plus those of the updates taken
+    /// in since.
+    size: usize,
+}
+
+impl Room {
+    fn new() -> Self {
+        Self {
+            doc: Doc::new(),
+            events: broadcast::channel(BACKLOG).0,
+            presences: HashMap::new(),
+            peers: 0,
+            size: 0,
+        }
+    }
+}
+
+lazy_static! {
+    static ref ROOMS: Mutex<HashMap<String, Room>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_PEER: AtomicU64 = AtomicU64::new(0);
+
+/// Ids are picked by the client that opens the room, they're unguessable UUIDs.
+fn is_room_id(id: &str) -> bool {
+    (16..=64).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// A place in a room, taken before the connection is upgraded so that no more than
+/// [`MAX_PEERS`] are let in at once, and no more than [`MAX_ROOMS`] are opened. It's
+/// given back when dropped, which is also when an upgrade that didn't go through is
+/// dropped.
+struct Seat {
+    room: String,
+    peer: u64,
+}
+
+impl Seat {
+    fn take(room: &str) -> Result<Self, ApiError> {
+        let mut rooms = ROOMS.lock().unwrap();
+        if !rooms.contains_key(room) && rooms.len() >= MAX_ROOMS {
+            return Err(ApiError::TooManyRooms);
+        }
+        let entry = rooms.entry(room.to_string()).or_insert_with(Room::new);
+        if entry.peers >= MAX_PEERS {
+            return Err(ApiError::RoomFull);
+        }
+        entry.peers += 1;
+        Ok(Self {
+            room: room.to_string(),
+            peer: NEXT_PEER.fetch_add(1, Ordering::Relaxed),
+        })
+    }
+}
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        let (id, peer) = (&self.room, self.peer);
+        let mut rooms = ROOMS.lock().unwrap();
+        if let Some(room) = rooms.get_mut(id) {
+            room.peers -= 1;
+            if room.peers == 0 {
+                rooms.remove(id);
+            } else if room.presences.remove(&peer).is_some() {
+                let left = json!({ "peer": peer, "presence": null }).to_string();
+                let _ = room.events.send((peer, Event::Presence(Arc::from(left))));
+            }
+        }
+    }
+}
+
+/// Joins the room with the given id, opening it if nobody's in it. The first message
+/// is the room's document as a Yjs update, followed by the presences of the others and
+/// then by their edits and presences as they come.
+pub async fn join(
+    UrlPath(id): UrlPath<String>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    if !is_room_id(&id) {
+        return Err(ApiError::InvalidRoomId(id));
+    }
+    let seat = Seat::take(&id)?;
+    Ok(ws
+        .max_message_size(MAX_UPDATE_SIZE)
+        .on_upgrade(move |socket| connect(seat, socket)))
+}
+
+async fn connect(seat: Seat, mut socket: WebSocket) {
+    let (id, peer) = (seat.room.clone(), seat.peer);
+    let (backlog, mut events) = {
+        let mut rooms = ROOMS.lock().unwrap();
+        let room = rooms.entry(id.clone()).or_insert_with(Room::new);
+        let state = room
+            .doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+        let mut backlog = vec![Event::Update(Arc::from(state))];
+        backlog.extend(room.presences.values().cloned().map(Event::Presence));
+        (backlog, room.events.subscribe())
+    };
+    debug!(room = id, peer, "peer joined");
+
+    let mut caught_up = true;
+    for event in backlog {
+        if socket.send(event.into()).await.is_err() {
+            caught_up = false;
+            break;
+        }
+    }
+    if caught_up {
+        loop {
+            tokio::select! {
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Binary(update))) => {
+                        if let Err(e) = apply(&id, peer, update) {
+                            warn!(room = id, peer, ?e, "rejected an update");
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Text(presence))) => {
+                        if let Err(e) = set_presence(&id, peer, &presence) {
+                            warn!(room = id, peer, ?e, "rejected a presence");
+                            break;
+                        }
+                    }
+                    // pings are answered by axum, there's nothing else to expect
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                },
+                event = events.recv() => match event {
+                    Ok((from, event)) if from != peer => {
+                        if socket.send(event.into()).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(missed)) => {
+                        debug!(room = id, peer, missed, "peer fell behind");
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            }
+        }
+    }
+
+    drop(seat);
+    debug!(room = id, peer, "peer left");
+}
+
+/// Merges an update into the room's document and passes it on.
+fn apply(id: &str, peer: u64, update: Vec<u8>) -> anyhow::Result<()> {
+    let decoded = Update::decode_v1(&update)?;
+    let mut rooms = ROOMS.lock().unwrap();
+    let total = rooms.values().map(|it| it.size).sum::<usize>();
+    let Some(room) = rooms.get_mut(id) else {
+        return Ok(());
+    };
+    if total + update.len() > MAX_TOTAL_SIZE {
+        anyhow::bail!("the rooms' documents are over {} bytes", MAX_TOTAL_SIZE);
+    }
+    if room.size + update.len() > MAX_ROOM_SIZE {
+        // much of it is edits that were taken in more than once, like the whole document
+        // every client sends after reconnecting, encoded it's just what the room keeps
+        room.size = room
+            .doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default())
+            .len();
+        if room.size + update.len() > MAX_ROOM_SIZE {
+            anyhow::bail!("the room's document is over {} bytes", MAX_ROOM_SIZE);
+        }
+    }
+    room.size += update.len();
+    room.doc.transact_mut().apply_update(decoded)?;
+    // nobody else around is fine
+    let _ = room.events.send((peer, Event::Update(Arc::from(update))));
+    Ok(())
+}
+
+/// Keeps the presence of a peer for those joining later and passes it on.
+fn set_presence(id: &str, peer: u64, presence: &str) -> anyhow::Result<()> {
+    if presence.len() > MAX_PRESENCE_SIZE {
+        anyhow::bail!("the presence is over {} bytes", MAX_PRESENCE_SIZE);
+    }
+    let presence = serde_json::from_str::<Map<String, Value>>(presence)?;
+    let message = Arc::<str>::from(json!({ "peer": peer, "presence": presence }).to_string());
+    let mut rooms = ROOMS.lock().unwrap();
+    let Some(room) = rooms.get_mut(id) else {
+        return Ok(());
+    };
+    room.presences.insert(peer, message.clone());
+    let _ = room.events.send((peer, Event::Presence(message)));
+    Ok(())
+}
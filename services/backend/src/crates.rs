@@ -0,0 +1,116 @@
+//! The crates snippets may depend on, for the playground's dependency picker. Their
+//! releases are looked up on crates.io and kept for a while.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path as UrlPath, Query};
+use axum::http::header;
+use axum::Json;
+use common::errors::ApiError;
+use common::{CrateInfo, CrateSummary, CrateVersion, ALLOWED_CRATES};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::CLINET;
+
+/// How long a crate's releases are served from memory before asking crates.io again.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Releases offered per crate, older ones are rarely what anyone wants.
+const MAX_VERSIONS: usize = 20;
+/// crates.io asks API clients to identify themselves.
+const USER_AGENT: &str = "yew-playground (https://play.yew.rs)";
+
+lazy_static! {
+    static ref CRATES_IO_URL: String =
+        std::env::var("CRATES_IO_URL").unwrap_or_else(|_| "https://crates.io/api/v1".to_string());
+    static ref CACHE: Mutex<HashMap<String, (Instant, CrateInfo)>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Deserialize)]
+pub struct Search {
+    #[serde(default)]
+    q: String,
+}
+
+/// The allowed crates whose name or description contains the query.
+pub async fn search(Query(search): Query<Search>) -> Json<Vec<CrateSummary>> {
+    let query = search.q.trim().to_lowercase();
+    let crates = ALLOWED_CRATES
+        .iter()
+        .filter(|(name, description)| {
+            name.contains(&query) || description.to_lowercase().contains(&query)
+        })
+        .map(|(name, description)| CrateSummary {
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+        .collect();
+    Json(crates)
+}
+
+#[derive(Deserialize)]
+struct CratesIoResponse {
+    versions: Vec<CratesIoVersion>,
+}
+
+#[derive(Deserialize)]
+struct CratesIoVersion {
+    num: String,
+    yanked: bool,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+}
+
+/// The recent stable releases of an allowed crate along with their features.
+pub async fn info(UrlPath(name): UrlPath<String>) -> Result<Json<CrateInfo>, ApiError> {
+    if !ALLOWED_CRATES.iter().any(|(it, _)| *it == name) {
+        return Err(ApiError::CrateNotAllowed(name));
+    }
+    let cached = CACHE
+        .lock()
+        .unwrap()
+        .get(&name)
+        .filter(|(fetched, _)| fetched.elapsed() < CACHE_TTL)
+        .map(|(_, info)| info.clone());
+    if let Some(info) = cached {
+        return Ok(Json(info));
+    }
+
+    let res = CLINET
+        .get(format!("{}/crates/{}", *CRATES_IO_URL, name))
+        .header(header::USER_AGENT, USER_AGENT)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(ApiError::Upstream {
+            service: "crates.io",
+            status: res.status(),
+            detail: format!("crate {}", name),
+        });
+    }
+    let res: CratesIoResponse = res.json().await.map_err(anyhow::Error::from)?;
+    let versions = res
+        .versions
+        .into_iter()
+        // pre-releases aren't offered, they'd have to be asked for explicitly anyway
+        .filter(|it| !it.yanked && !it.num.contains('-'))
+        .take(MAX_VERSIONS)
+        .map(|it| CrateVersion {
+            num: it.num,
+            features: it
+                .features
+                .into_keys()
+                .filter(|it| it != "default")
+                .collect(),
+        })
+        .collect();
+    let info = CrateInfo { name, versions };
+    CACHE
+        .lock()
+        .unwrap()
+        .insert(info.name.clone(), (Instant::now(), info.clone()));
+    Ok(Json(info))
+}
@@ -0,0 +1,77 @@
+//! Starter snippets for the playground's examples gallery.
+
+use axum::extract::Path as UrlPath;
+use axum::http::header;
+use axum::Json;
+use common::errors::ApiError;
+use common::ExampleSummary;
+
+struct Example {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    code: &'static str,
+    thumbnail: &'static str,
+}
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        id: "hello",
+        title: "Hello world",
+        description: "The smallest Yew app, a function component rendering some text.",
+        code: include_str!("examples/hello.rs"),
+        thumbnail: include_str!("examples/hello.svg"),
+    },
+    Example {
+        id: "counter",
+        title: "Counter",
+        description: "Keeps state with `use_state` and updates it from event handlers.",
+        code: include_str!("examples/counter.rs"),
+        thumbnail: include_str!("examples/counter.svg"),
+    },
+    Example {
+        id: "todo",
+        title: "Todo list",
+        description: "Reads a form input through a node ref and renders a list from state.",
+        code: include_str!("examples/todo.rs"),
+        thumbnail: include_str!("examples/todo.svg"),
+    },
+    Example {
+        id: "timer",
+        title: "Timer",
+        description: "Starts an interval in an effect and stops it again in the cleanup.",
+        code: include_str!("examples/timer.rs"),
+        thumbnail: include_str!("examples/timer.svg"),
+    },
+];
+
+fn find(id: &str) -> Result<&'static Example, ApiError> {
+    EXAMPLES
+        .iter()
+        .find(|it| it.id == id)
+        .ok_or_else(|| ApiError::ExampleNotFound(id.to_string()))
+}
+
+pub async fn list() -> Json<Vec<ExampleSummary>> {
+    let examples = EXAMPLES
+        .iter()
+        .map(|it| ExampleSummary {
+            id: it.id.to_string(),
+            title: it.title.to_string(),
+            description: it.description.to_string(),
+        })
+        .collect();
+    Json(examples)
+}
+
+/// Responds with the example's `main.rs`.
+pub async fn code(UrlPath(id): UrlPath<String>) -> Result<&'static str, ApiError> {
+    Ok(find(&id)?.code)
+}
+
+pub async fn thumbnail(
+    UrlPath(id): UrlPath<String>,
+) -> Result<([(header::HeaderName, &'static str); 1], &'static str), ApiError> {
+    let example = find(&id)?;
+    Ok(([(header::CONTENT_TYPE, "image/svg+xml")], example.thumbnail))
+}
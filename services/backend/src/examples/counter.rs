@@ -0,0 +1,26 @@
+use yew::prelude::*;
+
+#[function_component]
+fn App() -> Html {
+    let count = use_state(|| 0);
+    let increment = {
+        let count = count.clone();
+        move |_| count.set(*count + 1)
+    };
+    let decrement = {
+        let count = count.clone();
+        move |_| count.set(*count - 1)
+    };
+
+    html! {
+        <div>
+            <button onclick={decrement}>{ "-1" }</button>
+            <span>{ *count }</span>
+            <button onclick={increment}>{ "+1" }</button>
+        </div>
+    }
+}
+
+fn main() {
+    yew::Renderer::<App>::new().render();
+}
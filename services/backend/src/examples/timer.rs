@@ -0,0 +1,26 @@
+use gloo::timers::callback::Interval;
+use yew::prelude::*;
+
+#[function_component]
+fn App() -> Html {
+    let seconds = use_state(|| 0);
+    {
+        let seconds = seconds.clone();
+        use_effect_with((), move |_| {
+            let mut elapsed = 0;
+            let interval = Interval::new(1000, move || {
+                elapsed += 1;
+                seconds.set(elapsed);
+            });
+            move || drop(interval)
+        });
+    }
+
+    html! {
+        <p>{ format!("You've been here for {} seconds", *seconds) }</p>
+    }
+}
+
+fn main() {
+    yew::Renderer::<App>::new().render();
+}
@@ -0,0 +1,60 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Clone, PartialEq)]
+struct Todo {
+    text: String,
+    done: bool,
+}
+
+#[function_component]
+fn App() -> Html {
+    let todos = use_state(Vec::<Todo>::new);
+    let input = use_node_ref();
+
+    let onsubmit = {
+        let todos = todos.clone();
+        let input = input.clone();
+        move |e: SubmitEvent| {
+            e.prevent_default();
+            let input = input.cast::<HtmlInputElement>().unwrap();
+            let text = input.value();
+            if text.trim().is_empty() {
+                return;
+            }
+            let mut new_todos = (*todos).clone();
+            new_todos.push(Todo { text, done: false });
+            todos.set(new_todos);
+            input.set_value("");
+        }
+    };
+
+    let items = todos.iter().enumerate().map(|(i, todo)| {
+        let onclick = {
+            let todos = todos.clone();
+            move |_| {
+                let mut new_todos = (*todos).clone();
+                new_todos[i].done = !new_todos[i].done;
+                todos.set(new_todos);
+            }
+        };
+        let style = if todo.done { "text-decoration: line-through" } else { "" };
+        html! {
+            <li {onclick} {style}>{ &todo.text }</li>
+        }
+    });
+
+    html! {
+        <>
+            <form {onsubmit}>
+                <input ref={input} placeholder="What needs to be done?" />
+                <button type="submit">{ "Add" }</button>
+            </form>
+            <ul>{ for items }</ul>
+        </>
+    }
+}
+
+fn main() {
+    yew::Renderer::<App>::new().render();
+}
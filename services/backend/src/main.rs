@@ -1,20 +1,40 @@
+mod collab;
+mod crates;
+mod examples;
+mod pastes;
+mod preview_head;
+mod revisions;
+mod search;
+mod share;
+mod unfurl;
+
 use std::net::SocketAddr;
 
 use anyhow::{anyhow, Error};
-use axum::extract::Query;
-use axum::response::Html;
-use axum::routing::get;
-use axum::Router;
+use axum::body::StreamBody;
+use axum::extract::{Path as UrlPath, Query};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{Html, IntoResponse};
+use axum::routing::{get, post};
+use axum::{middleware, Json, Router};
 use errors::ApiError;
 use lazy_static::lazy_static;
-use reqwest::Client;
-use response::Bson;
+use reqwest::{Client, RequestBuilder};
+use response::{Format, Negotiate};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tower_http::trace::TraceLayer;
 use tracing::{debug, error, info};
 
 use common::response;
-use common::{errors, init_tracing};
+use common::{
+    errors, init_tracing, AnalyzeResponse, BuildManifest, BuildOptions, BuildTimings,
+    ClippyResponse, CompileError, CompilerHealth, CompletionResponse, Edition, ExpandResponse,
+    FormatResponse, Position, Priority, RunReport, Snippet, SsrResponse, PRIORITY_HEADER,
+    PROGRESS_HEADER, REQUEST_ID_HEADER, WIRE_VERSION, WIRE_VERSION_HEADER,
+};
+use common::client::BuildStats;
+use common::strip_ansi;
 
 lazy_static! {
     static ref PORT: u16 = std::env::var("PORT")
@@ -29,6 +49,30 @@ lazy_static! {
 #[derive(Deserialize)]
 struct RunPayload {
     code: String,
+    #[serde(flatten)]
+    options: BuildOptions,
+    #[serde(flatten)]
+    progress: ProgressQuery,
+}
+
+/// Id the client follows the progress of the build under, see [`progress`].
+#[derive(Deserialize)]
+struct ProgressQuery {
+    progress: Option<String>,
+}
+
+impl ProgressQuery {
+    /// Ids end up in the compiler's URLs, so they're kept to letters, digits and dashes.
+    fn id(&self) -> Result<Option<&str>, ApiError> {
+        match self.progress.as_deref() {
+            Some(id) if !is_progress_id(id) => Err(ApiError::InvalidProgressId(id.to_string())),
+            id => Ok(id),
+        }
+    }
+}
+
+fn is_progress_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
 }
 
 #[derive(Serialize)]
@@ -46,6 +90,13 @@ const INDEX_HTML: &str = r#"
     <meta name="viewport" content="width=device-width, user-scalable=no, initial-scale=1.0, maximum-scale=1.0, minimum-scale=1.0">
     <meta http-equiv="X-UA-Compatible" content="ie=edge">
     <title>Document</title>
+    <script>
+    /*BRIDGE_GOES_HERE*/
+    </script>
+    /*HEAD_GOES_HERE*/
+    <style>
+    /*STYLE_GOES_HERE*/
+    </style>
 </head>
 <body>
     <script type="module">
@@ -56,64 +107,556 @@ const INDEX_HTML: &str = r#"
 </html>
 "#;
 
-async fn run(Query(body): Query<RunPayload>) -> Result<Html<String>, ApiError> {
+const COMPILE_ERROR_HTML: &str = r#"
+<!doctype html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Compile error</title>
+</head>
+<body>
+    <pre>/*ERROR_GOES_HERE*/</pre>
+    <script>
+    const message = /*MESSAGE_GOES_HERE*/;
+    const diagnostics = /*DIAGNOSTICS_GO_HERE*/;
+    parent.postMessage(JSON.stringify({ type: "compile_error", message, diagnostics }), "*");
+    </script>
+</body>
+</html>
+"#;
+
+/// Script reporting the preview's console output to the playground.
+const PREVIEW_BRIDGE_JS: &str = include_str!("preview_bridge.js");
+
+/// Sends the snippet to the given compiler route and decodes the BSON response.
+async fn call_compiler<T: DeserializeOwned>(
+    route: &str,
+    query: &impl Serialize,
+    snippet: &Snippet,
+    priority: Priority,
+) -> Result<T, ApiError> {
+    call_compiler_followed(route, query, snippet, priority, None).await
+}
+
+/// Has requests to the compiler ask for BSON, tagged with the wire version and the ID of
+/// the request they're made for, which the compiler logs its errors under too.
+fn to_compiler(request: RequestBuilder) -> RequestBuilder {
+    let request = request
+        .header(header::ACCEPT, Format::Bson.mime())
+        .header(WIRE_VERSION_HEADER, WIRE_VERSION);
+    match errors::request_id() {
+        Some(id) => request.header(REQUEST_ID_HEADER, id),
+        None => request,
+    }
+}
+
+/// Like [`call_compiler`], with the build's progress reported under the given id.
+async fn call_compiler_followed<T: DeserializeOwned>(
+    route: &str,
+    query: &impl Serialize,
+    snippet: &Snippet,
+    priority: Priority,
+    progress: Option<&str>,
+) -> Result<T, ApiError> {
     let client = &*CLINET;
 
-    let res = client
-        .post(format!("{}/run", *COMPILER_URL))
-        .body(body.code)
+    let mut request = to_compiler(client.post(format!("{}/{}", *COMPILER_URL, route)))
+        .query(query)
+        .header(PRIORITY_HEADER, priority.as_str());
+    if let Some(id) = progress {
+        request = request.header(PROGRESS_HEADER, id);
+    }
+    let res = request.json(snippet).send().await.map_err(Error::from)?;
+
+    let status = res.status();
+    debug!(status = ?status, route, "got response from compiler");
+
+    if !status.is_success() {
+        let body = res.text().await.map_err(Error::from)?;
+        return Err(match ApiError::upstream("the compiler", status, body) {
+            // compilers from before errors were structured report violations as text
+            ApiError::Upstream { status, detail, .. }
+                if status == StatusCode::UNPROCESSABLE_ENTITY =>
+            {
+                ApiError::PolicyViolation(detail)
+            }
+            error => error,
+        });
+    }
+
+    read_bson(res).await
+}
+
+/// Fetches a build the compiler retained from an earlier run.
+async fn fetch_build<T: DeserializeOwned>(id: &str, route: &str) -> Result<T, ApiError> {
+    let res = to_compiler(CLINET.get(format!("{}/artifacts/{}{}", *COMPILER_URL, id, route)))
         .send()
         .await
         .map_err(Error::from)?;
 
     let status = res.status();
-    debug!(status = ?status, "got response from compiler");
+    debug!(status = ?status, id, route, "got response from compiler");
+
+    if status == StatusCode::NOT_FOUND {
+        return Err(ApiError::BuildNotFound(id.to_string()));
+    }
 
     if !status.is_success() {
-        return Err(ApiError::Unknown(
-            anyhow!("Compiler service returned an error: {}", res.text().await.unwrap())
-        ))
+        let body = res.text().await.map_err(Error::from)?;
+        return Err(ApiError::upstream("the compiler", status, body));
     }
 
-    let run_response: common::Response = {
-        let bytes = res.bytes().await.map_err(|e| {
-            error!(?e, "failed to get bytes from compiler response");
-            ApiError::Unknown(e.into())
-        })?;
-        bson::from_slice(&bytes).map_err(|e| {
-            error!(?e, "failed to deserialize compiler response");
-            ApiError::BsonDeserializeError(e)
-        })?
+    read_bson(res).await
+}
+
+/// Reads the compiler's BSON. What it can't be read as is blamed on the compiler running
+/// a release with a different [`WIRE_VERSION`], or one from before they were sent, which happens
+/// while the two are being upgraded.
+async fn read_bson<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, ApiError> {
+    let version = res
+        .headers()
+        .get(WIRE_VERSION_HEADER)
+        .and_then(|it| it.to_str().ok())
+        .and_then(|it| it.parse::<u32>().ok());
+    let bytes = res.bytes().await.map_err(|e| {
+        error!(?e, "failed to get bytes from compiler response");
+        ApiError::Unknown(e.into())
+    })?;
+    bson::from_slice(&bytes).map_err(|e| {
+        error!(?e, ?version, "failed to deserialize compiler response");
+        match version {
+            Some(WIRE_VERSION) => ApiError::BsonDeserializeError(e),
+            _ => ApiError::IncompatibleWireVersion(version, e),
+        }
+    })
+}
+
+/// Formats build timings as a `Server-Timing` header so they show up in the browser's devtools.
+fn server_timing(timings: &BuildTimings) -> HeaderValue {
+    let value = format!(
+        "deps;dur={}, rustc;dur={}, bindgen;dur={}, opt;dur={}, total;dur={}",
+        timings.dependency_resolution_ms,
+        timings.rustc_ms,
+        timings.wasm_bindgen_ms,
+        timings.wasm_opt_ms,
+        timings.total_ms
+    );
+    HeaderValue::from_str(&value).expect("timings are valid header characters")
+}
+
+/// Estimates what `data` is served as with gzip: the deflated size plus gzip's header
+/// and trailer.
+fn gzip_size(data: &[u8]) -> usize {
+    const GZIP_FRAMING: usize = 18;
+    miniz_oxide::deflate::compress_to_vec(data, 6).len() + GZIP_FRAMING
+}
+
+async fn run(Query(body): Query<RunPayload>) -> Result<(HeaderMap, Html<String>), ApiError> {
+    render(&body.options, &Snippet::single(body.code), body.progress.id()?).await
+}
+
+/// Like [`run`] but takes a multi-file snippet as the body, in JSON, BSON or CBOR.
+async fn run_files(
+    Query(options): Query<BuildOptions>,
+    Query(progress): Query<ProgressQuery>,
+    Negotiate(snippet): Negotiate<Snippet>,
+) -> Result<(HeaderMap, Html<String>), ApiError> {
+    render(&options, &snippet, progress.id()?).await
+}
+
+/// Builds the snippet and renders the page that runs it.
+async fn render(
+    options: &BuildOptions,
+    snippet: &Snippet,
+    progress: Option<&str>,
+) -> Result<(HeaderMap, Html<String>), ApiError> {
+    let run_response: common::Response =
+        call_compiler_followed("run", options, snippet, Priority::Interactive, progress).await?;
+    page(run_response, Some(snippet))
+}
+
+/// Header of the plain-text responses, `ok` if the snippet compiled and `error` if it
+/// didn't, their status is 200 either way.
+const BUILD_STATUS_HEADER: &str = "x-build-status";
+
+/// Like [`run`] but responds with the outcome as plain text, see [`plain_text`].
+async fn run_text(Query(body): Query<RunPayload>) -> Result<(HeaderMap, String), ApiError> {
+    render_text(&body.options, &Snippet::single(body.code), body.progress.id()?).await
+}
+
+/// Like [`run_files`] but responds with the outcome as plain text, see [`plain_text`].
+async fn run_files_text(
+    Query(options): Query<BuildOptions>,
+    Query(progress): Query<ProgressQuery>,
+    Negotiate(snippet): Negotiate<Snippet>,
+) -> Result<(HeaderMap, String), ApiError> {
+    render_text(&options, &snippet, progress.id()?).await
+}
+
+async fn render_text(
+    options: &BuildOptions,
+    snippet: &Snippet,
+    progress: Option<&str>,
+) -> Result<(HeaderMap, String), ApiError> {
+    let run_response: common::Response =
+        call_compiler_followed("run", options, snippet, Priority::Interactive, progress).await?;
+    Ok(plain_text(run_response))
+}
+
+/// The outcome of a build for chat bots and scripts to paste, without any HTML: cargo's
+/// output without its colors if the snippet didn't compile, a line with the sizes of
+/// the artifacts if it did.
+fn plain_text(run_response: common::Response) -> (HeaderMap, String) {
+    let mut headers = HeaderMap::new();
+    let (status, text) = match run_response {
+        common::Response::Output {
+            js, wasm, timings, ..
+        } => {
+            headers.insert("server-timing", server_timing(&timings));
+            let kilobytes = |bytes: usize| format!("{:.1} kB", bytes as f64 / 1000.0);
+            let text = format!(
+                "Compiled in {:.1} s: {} wasm ({} gzipped), {} js\n",
+                timings.total_ms as f64 / 1000.0,
+                kilobytes(wasm.len()),
+                kilobytes(gzip_size(&wasm)),
+                kilobytes(js.len())
+            );
+            ("ok", text)
+        }
+        common::Response::CompileError(error) => ("error", strip_ansi(&error.stderr)),
     };
+    headers.insert(BUILD_STATUS_HEADER, HeaderValue::from_static(status));
+    (headers, text)
+}
 
+/// Like [`run`] but responds with the outcome as a [`RunReport`], for programs.
+async fn run_json(Query(body): Query<RunPayload>) -> Result<Json<RunReport>, ApiError> {
+    render_report(&body.options, &Snippet::single(body.code), body.progress.id()?).await
+}
 
+/// Like [`run_files`] but responds with the outcome as a [`RunReport`], for programs.
+async fn run_files_json(
+    Query(options): Query<BuildOptions>,
+    Query(progress): Query<ProgressQuery>,
+    Negotiate(snippet): Negotiate<Snippet>,
+) -> Result<Json<RunReport>, ApiError> {
+    render_report(&options, &snippet, progress.id()?).await
+}
+
+async fn render_report(
+    options: &BuildOptions,
+    snippet: &Snippet,
+    progress: Option<&str>,
+) -> Result<Json<RunReport>, ApiError> {
+    let run_response: common::Response =
+        call_compiler_followed("run", options, snippet, Priority::Interactive, progress).await?;
+    let report = match run_response {
+        common::Response::Output {
+            js,
+            wasm,
+            timings,
+            build_id,
+            ..
+        } => RunReport::Ok {
+            stats: BuildStats {
+                wasm_size: wasm.len() as u64,
+                wasm_gzip_size: gzip_size(&wasm) as u64,
+                js_size: js.len() as u64,
+                build_ms: timings.total_ms,
+            },
+            build_id,
+        },
+        common::Response::CompileError(error) => RunReport::Error(error),
+    };
+    Ok(Json(report))
+}
+
+/// Relays the compiler's server-sent events reporting the stages of the build run
+/// with the given progress id. Clients subscribe before starting the run.
+async fn progress(
+    UrlPath(id): UrlPath<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    if !is_progress_id(&id) {
+        return Err(ApiError::InvalidProgressId(id));
+    }
+    let res = to_compiler(CLINET.get(format!("{}/progress/{}", *COMPILER_URL, id)))
+        .send()
+        .await
+        .map_err(Error::from)?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.map_err(Error::from)?;
+        return Err(ApiError::upstream("the compiler", status, body));
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Ok((headers, StreamBody::new(res.bytes_stream())))
+}
+
+/// Serves the page of a build retained by the compiler, without rebuilding it.
+async fn build(UrlPath(id): UrlPath<String>) -> Result<(HeaderMap, Html<String>), ApiError> {
+    let response: common::Response = fetch_build(&id, "").await?;
+    // the snippet's style and head aren't kept along with the build
+    page(response, None)
+}
+
+/// The wasm-bindgen glue of a retained build, for the playground to show.
+async fn build_js(UrlPath(id): UrlPath<String>) -> Result<(HeaderMap, String), ApiError> {
+    match fetch_build(&id, "").await? {
+        common::Response::Output { js, .. } => {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/javascript"));
+            Ok((headers, js))
+        }
+        // only successful builds are retained
+        common::Response::CompileError(_) => Err(ApiError::BuildNotFound(id)),
+    }
+}
+
+/// Profiles the size of a retained build.
+async fn build_analysis(UrlPath(id): UrlPath<String>) -> Result<Json<AnalyzeResponse>, ApiError> {
+    let report = fetch_build(&id, "/analyze").await?;
+    Ok(Json(report))
+}
+
+/// What a retained build was made with.
+async fn build_manifest(UrlPath(id): UrlPath<String>) -> Result<Json<BuildManifest>, ApiError> {
+    let manifest = fetch_build(&id, "/manifest").await?;
+    Ok(Json(manifest))
+}
+
+/// Renders the page that runs the compiler's output, with the snippet's style sheet
+/// and the sanitized markup of its head if it has them.
+fn page(
+    run_response: common::Response,
+    snippet: Option<&Snippet>,
+) -> Result<(HeaderMap, Html<String>), ApiError> {
     match run_response {
         common::Response::Output {
             index_html: _,
             js,
             wasm,
+            timings,
+            build_id,
+            integrity,
+            manifest: _,
         } => {
-            debug!(wasm_bytes = wasm.len(), "compilation successful");
+            debug!(wasm_bytes = wasm.len(), ?timings, "compilation successful");
             let init_fn = js.split("export default").nth(1).and_then(|it| it.trim().strip_suffix(";"));
             match init_fn {
                 Some(init_fn) => {
-                    let index_html = INDEX_HTML.replace("/*JS_GOES_HERE*/", &js);
-                    let init = format!("{}((new Int8Array({:?})).buffer)", init_fn, wasm);
+                    let index_html = INDEX_HTML
+                        .replace("/*BRIDGE_GOES_HERE*/", PREVIEW_BRIDGE_JS)
+                        .replace("/*JS_GOES_HERE*/", &js);
+                    // the glue is a module, its exports are put on `wasm` for the console
+                    let init = format!(
+                        "{}((new Int8Array({:?})).buffer);\nglobalThis.wasm = {{ {} }};",
+                        init_fn,
+                        wasm,
+                        exported_names(&js).join(", ")
+                    );
                     let index_html = index_html.replace("/*INIT_GOES_HERE*/", &init);
+                    let style = style_element_text(snippet.and_then(Snippet::style));
+                    let head = preview_head::sanitize(
+                        snippet.and_then(Snippet::head).unwrap_or_default(),
+                    );
+                    // last, and only the first occurrence, which is the page's own, so
+                    // nothing the snippet puts into the page is taken for a placeholder
+                    let index_html = index_html
+                        .replacen("/*STYLE_GOES_HERE*/", &style, 1)
+                        .replacen("/*HEAD_GOES_HERE*/", &head, 1);
 
-                    Ok(Html(index_html))
-                }
-                None => {
-                    return Err(ApiError::Unknown(anyhow!("failed to find init function as default export in js")))
+                    let mut headers = HeaderMap::new();
+                    headers.insert("server-timing", server_timing(&timings));
+                    // shown under the preview so changes that bloat the build stand out
+                    for (name, size) in [
+                        ("x-wasm-size", wasm.len()),
+                        ("x-wasm-gzip-size", gzip_size(&wasm)),
+                        ("x-js-size", js.len()),
+                    ] {
+                        headers.insert(name, HeaderValue::from(size));
+                    }
+                    let build_id = build_id.and_then(|it| HeaderValue::from_str(&it).ok());
+                    if let Some(build_id) = build_id {
+                        headers.insert("x-build-id", build_id);
+                    }
+                    // lets whoever caches or re-serves the artifacts verify them
+                    for (name, hash) in [
+                        ("x-integrity-js", integrity.js),
+                        ("x-integrity-wasm", integrity.wasm),
+                    ] {
+                        if let Ok(hash) = HeaderValue::from_str(&hash) {
+                            headers.insert(name, hash);
+                        }
+                    }
+                    Ok((headers, Html(index_html)))
                 }
+                None => Err(ApiError::Unknown(anyhow!(
+                    "failed to find init function as default export in js"
+                ))),
             }
         }
-        common::Response::CompileError(e) => Ok(Html(e)),
+        common::Response::CompileError(e) => {
+            Ok((HeaderMap::new(), Html(compile_error_page(&e))))
+        }
+    }
+}
+
+/// Names of the functions and classes the wasm-bindgen glue exports for the snippet's
+/// `#[wasm_bindgen]` items, which it declares one per line.
+fn exported_names(js: &str) -> Vec<&str> {
+    js.lines()
+        .filter_map(|line| {
+            let declaration = line
+                .strip_prefix("export function ")
+                .or_else(|| line.strip_prefix("export class "))?;
+            let end = declaration
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(declaration.len());
+            Some(&declaration[..end]).filter(|it| !it.is_empty())
+        })
+        .collect()
+}
+
+/// The style sheet as the text of a `<style>` element. The text can't close the
+/// element, `\/` is the same character to CSS.
+fn style_element_text(style: Option<&str>) -> String {
+    style.unwrap_or_default().replace("</", "<\\/")
+}
+
+/// Renders the page shown for a compile error. It hands the colored output to the
+/// playground, which renders it itself along with markers where the compiler's
+/// diagnostics are, and shows it as plain text to anyone opening the page directly.
+fn compile_error_page(error: &CompileError) -> String {
+    // keeps the JSON from closing the script element
+    let script_json = |json: String| json.replace("</", "<\\/");
+    let plain = strip_ansi(&error.stderr);
+    let message = script_json(serde_json::to_string(&error.stderr).expect("strings serialize"));
+    let diagnostics = script_json(
+        serde_json::to_string(&error.diagnostics).expect("diagnostics serialize"),
+    );
+    // from the last placeholder to the first, and only the first occurrence, which is
+    // the page's own, so nothing put into the page is taken for a placeholder
+    COMPILE_ERROR_HTML
+        .replacen("/*DIAGNOSTICS_GO_HERE*/", &diagnostics, 1)
+        .replacen("/*MESSAGE_GOES_HERE*/", &message, 1)
+        .replacen("/*ERROR_GOES_HERE*/", &escape_html(&plain), 1)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+async fn analyze(
+    Query(options): Query<BuildOptions>,
+    code: String,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let report = call_compiler("analyze", &options, &snippet, Priority::Batch).await?;
+    Ok(Json(report))
+}
+
+async fn expand(
+    Query(options): Query<BuildOptions>,
+    code: String,
+) -> Result<Json<ExpandResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let expanded = call_compiler("expand", &options, &snippet, Priority::Interactive).await?;
+    Ok(Json(expanded))
+}
+
+#[derive(Deserialize, Serialize)]
+struct FormatQuery {
+    #[serde(default)]
+    edition: Edition,
+}
+
+async fn format(
+    Query(query): Query<FormatQuery>,
+    code: String,
+) -> Result<Json<FormatResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let formatted = call_compiler("format", &query, &snippet, Priority::Interactive).await?;
+    Ok(Json(formatted))
+}
+
+async fn clippy(
+    Query(options): Query<BuildOptions>,
+    code: String,
+) -> Result<Json<ClippyResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let lints = call_compiler("clippy", &options, &snippet, Priority::Interactive).await?;
+    Ok(Json(lints))
+}
+
+async fn ssr(
+    Query(options): Query<BuildOptions>,
+    code: String,
+) -> Result<Json<SsrResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let rendered = call_compiler("ssr", &options, &snippet, Priority::Interactive).await?;
+    Ok(Json(rendered))
+}
+
+async fn complete(
+    Query(position): Query<Position>,
+    code: String,
+) -> Result<Json<CompletionResponse>, ApiError> {
+    let snippet = Snippet::single(code);
+    let completions =
+        call_compiler("complete", &position, &snippet, Priority::Interactive).await?;
+    Ok(Json(completions))
+}
+
+#[derive(Serialize)]
+struct Health {
+    healthy: bool,
+    compiler: Option<CompilerHealth>,
+    compiler_error: Option<String>,
+}
+
+/// Aggregates the compiler's health report. Responds with 503 if the compiler can't be reached.
+async fn health() -> (StatusCode, Json<Health>) {
+    let compiler = async {
+        let res = CLINET
+            .get(format!("{}/health", *COMPILER_URL))
+            .send()
+            .await?
+            .error_for_status()?;
+        res.json::<CompilerHealth>().await
+    }
+    .await;
+
+    match compiler {
+        Ok(compiler) => (
+            StatusCode::OK,
+            Json(Health {
+                healthy: true,
+                compiler: Some(compiler),
+                compiler_error: None,
+            }),
+        ),
+        Err(e) => {
+            error!(?e, "compiler health check failed");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(Health {
+                    healthy: false,
+                    compiler: None,
+                    compiler_error: Some(e.to_string()),
+                }),
+            )
+        }
     }
 }
 
-async fn hello() -> Bson<RunResponse> {
-    Bson(RunResponse {
+async fn hello() -> Negotiate<RunResponse> {
+    Negotiate(RunResponse {
         index_html: "index_html".to_string(),
         js: "js".to_string(),
         wasm: "wasm".as_bytes().to_vec(),
@@ -126,10 +669,38 @@ async fn main() {
 
     let api = Router::new()
         .route("/hello", get(hello))
-        .route("/run", get(run))
+        .route("/health", get(health))
+        .route("/run", get(run).post(run_files))
+        .route("/run.txt", get(run_text).post(run_files_text))
+        .route("/run.json", get(run_json).post(run_files_json))
+        .route("/progress/:id", get(progress))
+        .route("/builds/:id", get(build))
+        .route("/builds/:id/js", get(build_js))
+        .route("/builds/:id/analyze", get(build_analysis))
+        .route("/builds/:id/manifest", get(build_manifest))
+        .route("/analyze", post(analyze))
+        .route("/expand", post(expand))
+        .route("/format", post(format))
+        .route("/clippy", post(clippy))
+        .route("/ssr", post(ssr))
+        .route("/complete", post(complete))
+        .route("/crates", get(crates::search))
+        .route("/crates/:name", get(crates::info))
+        .route("/collab/:room", get(collab::join))
+        .route("/revisions/:paste", get(revisions::list).post(revisions::add))
+        .route("/revisions/:paste/:revision", get(revisions::get))
+        .route("/snippets", get(search::snippets).post(share::create))
+        .route("/snippets/:id", get(unfurl::metadata))
+        .route("/examples", get(examples::list))
+        .route("/examples/:id", get(examples::code))
+        .route("/examples/:id/thumbnail.svg", get(examples::thumbnail))
         .layer(TraceLayer::new_for_http());
 
-    let app = Router::new().nest("/api", api);
+    // links to shared snippets for chat apps and forums, outside of the api
+    let app = Router::new()
+        .nest("/api", api)
+        .route("/s/:id", get(unfurl::page))
+        .layer(middleware::from_fn(errors::with_request_id));
 
     let addr = SocketAddr::new("0.0.0.0".parse().unwrap(), *PORT);
     info!("Server running on {}", addr);
@@ -0,0 +1,351 @@
+//! The snippets shared from the playground, which it stores in Firestore itself. What
+//! the backend keeps about a snippet, its revisions, goes into collections under the
+//! paste.
+//!
+//! Firestore's rules, see `firestore.rules`, only let the public read. The backend
+//! authenticates as the service account it runs as, which the rules don't apply to.
+
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use axum::http::StatusCode;
+use common::errors::ApiError;
+use lazy_static::lazy_static;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::CLINET;
+
+/// Where the playground shares snippets to.
+const DEFAULT_PASTES_URL: &str = concat!(
+    "https://firestore.googleapis.com/v1/projects/pastify-app",
+    "/databases/(default)/documents/pastes"
+);
+
+/// Where the service account's access tokens come from, the metadata server of the
+/// instance the backend runs on.
+const TOKEN_URL: &str = concat!(
+    "http://metadata.google.internal/computeMetadata/v1",
+    "/instance/service-accounts/default/token"
+);
+/// Tokens are fetched again this long before they expire.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref PASTES_URL: String =
+        std::env::var("PASTES_URL").unwrap_or_else(|_| DEFAULT_PASTES_URL.to_string());
+    /// Set when `PASTES_URL` is the Firestore emulator's, which has no metadata server
+    /// and takes `owner` as the token of an admin.
+    static ref FIRESTORE_EMULATOR: bool = std::env::var("FIRESTORE_EMULATOR").is_ok();
+    static ref TOKEN: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+}
+
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+    /// In seconds.
+    expires_in: u64,
+}
+
+/// The access token of the service account, kept until shortly before it expires.
+async fn token() -> Result<String, ApiError> {
+    if *FIRESTORE_EMULATOR {
+        return Ok("owner".to_string());
+    }
+    let mut cached = TOKEN.lock().await;
+    if let Some((expires_at, token)) = &*cached {
+        if Instant::now() < *expires_at {
+            return Ok(token.clone());
+        }
+    }
+    let res = CLINET
+        .get(TOKEN_URL)
+        .header("Metadata-Flavor", "Google")
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(ApiError::Upstream {
+            service: "the metadata server",
+            status: res.status(),
+            detail: "an access token".to_string(),
+        });
+    }
+    let token: AccessToken = res.json().await.map_err(anyhow::Error::from)?;
+    let lifetime = Duration::from_secs(token.expires_in).saturating_sub(TOKEN_MARGIN);
+    *cached = Some((Instant::now() + lifetime, token.access_token.clone()));
+    Ok(token.access_token)
+}
+
+/// Firestore's ids are 20 letters and digits, anything else can't be a paste.
+pub fn is_paste_id(id: &str) -> bool {
+    (1..=64).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StringValue {
+    #[serde(rename = "stringValue")]
+    pub string_value: String,
+}
+
+impl From<&str> for StringValue {
+    fn from(value: &str) -> Self {
+        Self {
+            string_value: value.to_string(),
+        }
+    }
+}
+
+/// Stamped on the pastes the backend shares, Firestore can't order documents by the
+/// time they were created otherwise.
+pub const CREATED_AT: &str = "createdAt";
+
+#[derive(Serialize, Deserialize)]
+pub struct TimestampValue {
+    #[serde(rename = "timestampValue")]
+    pub timestamp_value: String,
+}
+
+impl TimestampValue {
+    pub fn now() -> Result<Self, ApiError> {
+        let now = bson::DateTime::now()
+            .try_to_rfc3339_string()
+            .map_err(anyhow::Error::from)?;
+        Ok(Self {
+            timestamp_value: now,
+        })
+    }
+}
+
+/// Firestore's arrays, of strings here. Empty ones come without any values.
+#[derive(Serialize, Deserialize)]
+pub struct ArrayValue {
+    #[serde(rename = "arrayValue")]
+    pub array_value: Values,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Values {
+    #[serde(default)]
+    pub values: Vec<StringValue>,
+}
+
+#[derive(Deserialize)]
+pub struct Document<T> {
+    /// The full path of the document, ending in its id.
+    name: String,
+    pub fields: T,
+    #[serde(rename = "createTime")]
+    pub create_time: String,
+}
+
+impl<T> Document<T> {
+    pub fn id(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+struct Documents<T> {
+    /// Left out when there are none.
+    #[serde(default = "Vec::new")]
+    documents: Vec<Document<T>>,
+}
+
+/// One of the results of a query, those without a document only report progress.
+#[derive(Deserialize)]
+struct QueryResult<T> {
+    document: Option<Document<T>>,
+}
+
+/// Errors for Firestore's responses other than the documents asked for.
+fn unexpected(res: &reqwest::Response, what: &str) -> ApiError {
+    ApiError::Upstream {
+        service: "firestore",
+        status: res.status(),
+        detail: what.to_string(),
+    }
+}
+
+/// The paste, with its fields read as `T`.
+pub async fn fetch<T: DeserializeOwned>(paste: &str) -> Result<Document<T>, ApiError> {
+    if !is_paste_id(paste) {
+        return Err(ApiError::PasteNotFound(paste.to_string()));
+    }
+    let res = CLINET
+        .get(format!("{}/{}", *PASTES_URL, paste))
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if res.status() == StatusCode::NOT_FOUND {
+        return Err(ApiError::PasteNotFound(paste.to_string()));
+    }
+    if !res.status().is_success() {
+        return Err(unexpected(&res, &format!("paste {}", paste)));
+    }
+    Ok(res.json().await.map_err(anyhow::Error::from)?)
+}
+
+/// Up to `limit` documents of a collection under the paste, oldest first, with only
+/// the given fields if there are any.
+pub async fn list<T: DeserializeOwned>(
+    paste: &str,
+    collection: &str,
+    limit: usize,
+    fields: &[&str],
+) -> Result<Vec<Document<T>>, ApiError> {
+    let mut query = vec![("pageSize", limit.to_string())];
+    query.extend(fields.iter().map(|it| ("mask.fieldPaths", it.to_string())));
+    let res = CLINET
+        .get(format!("{}/{}/{}", *PASTES_URL, paste, collection))
+        .query(&query)
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(unexpected(
+            &res,
+            &format!("the {} of {}", collection, paste),
+        ));
+    }
+    let res: Documents<T> = res.json().await.map_err(anyhow::Error::from)?;
+    let mut documents = res.documents;
+    // the timestamps all have the same precision, so they sort as strings
+    documents.sort_by(|a, b| a.create_time.cmp(&b.create_time));
+    Ok(documents)
+}
+
+/// A document of a collection under the paste, `None` if there's no such document.
+pub async fn get<T: DeserializeOwned>(
+    paste: &str,
+    collection: &str,
+    id: &str,
+) -> Result<Option<Document<T>>, ApiError> {
+    let res = CLINET
+        .get(format!("{}/{}/{}/{}", *PASTES_URL, paste, collection, id))
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if res.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !res.status().is_success() {
+        return Err(unexpected(
+            &res,
+            &format!("{} {} of {}", collection, id, paste),
+        ));
+    }
+    Ok(Some(res.json().await.map_err(anyhow::Error::from)?))
+}
+
+/// Adds a document to a collection under the paste, returning it as it was stored.
+pub async fn create<T: Serialize + DeserializeOwned>(
+    paste: &str,
+    collection: &str,
+    fields: &T,
+) -> Result<Document<T>, ApiError> {
+    let res = CLINET
+        .post(format!("{}/{}/{}", *PASTES_URL, paste, collection))
+        .json(&serde_json::json!({ "fields": fields }))
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(unexpected(
+            &res,
+            &format!("new {} of {}", collection, paste),
+        ));
+    }
+    Ok(res.json().await.map_err(anyhow::Error::from)?)
+}
+
+/// Stores a new paste, returning its id.
+pub async fn share<T: Serialize>(fields: &T) -> Result<String, ApiError> {
+    let res = CLINET
+        .post(&*PASTES_URL)
+        .json(&serde_json::json!({ "fields": fields }))
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(unexpected(&res, "a new paste"));
+    }
+    let document: Document<IgnoredAny> = res.json().await.map_err(anyhow::Error::from)?;
+    Ok(document.id().to_string())
+}
+
+/// Where a page of [`newest`] pastes ends, the next one starts after it.
+pub struct Cursor(serde_json::Value);
+
+/// Up to `limit` of the pastes shared through the backend, newest first, with only the
+/// given fields, and where the page ends if there may be more after it. Pastes shared
+/// straight to Firestore have no [`CREATED_AT`] to order them by and are left out.
+pub async fn newest<T: DeserializeOwned>(
+    fields: &[&str],
+    limit: usize,
+    after: Option<&Cursor>,
+) -> Result<(Vec<Document<T>>, Option<Cursor>), ApiError> {
+    // queries are run against the parent of the collection
+    let (parent, collection) = PASTES_URL
+        .rsplit_once('/')
+        .ok_or_else(|| ApiError::Unknown(anyhow!("invalid pastes url {}", *PASTES_URL)))?;
+    // the cursor is made of the fields ordered by, which have to be selected
+    let fields = fields
+        .iter()
+        .chain([&CREATED_AT])
+        .map(|it| serde_json::json!({ "fieldPath": it }))
+        .collect::<Vec<_>>();
+    let mut query = serde_json::json!({
+        "from": [{ "collectionId": collection }],
+        "select": { "fields": fields },
+        "orderBy": [
+            { "field": { "fieldPath": CREATED_AT }, "direction": "DESCENDING" },
+            { "field": { "fieldPath": "__name__" }, "direction": "DESCENDING" },
+        ],
+        "limit": limit,
+    });
+    if let Some(Cursor(values)) = after {
+        query["startAt"] = serde_json::json!({ "values": values, "before": false });
+    }
+    let res = CLINET
+        .post(format!("{}:runQuery", parent))
+        .json(&serde_json::json!({ "structuredQuery": query }))
+        .bearer_auth(token().await?)
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    if !res.status().is_success() {
+        return Err(unexpected(&res, "a query of the pastes"));
+    }
+    let results: Vec<QueryResult<serde_json::Value>> =
+        res.json().await.map_err(anyhow::Error::from)?;
+    let documents = results
+        .into_iter()
+        .filter_map(|it| it.document)
+        .collect::<Vec<_>>();
+    let cursor = match documents.last() {
+        Some(last) if documents.len() == limit => Some(Cursor(serde_json::json!([
+            last.fields[CREATED_AT],
+            { "referenceValue": last.name },
+        ]))),
+        _ => None,
+    };
+    let documents = documents
+        .into_iter()
+        .map(|it| {
+            Ok(Document {
+                name: it.name,
+                fields: serde_json::from_value(it.fields)?,
+                create_time: it.create_time,
+            })
+        })
+        .collect::<Result<_, serde_json::Error>>()
+        .map_err(anyhow::Error::from)?;
+    Ok((documents, cursor))
+}
@@ -0,0 +1,146 @@
+// Forwards the console and uncaught errors of the preview to the playground, which
+// loads it in a sandboxed iframe and can't look inside.
+(() => {
+    const post = (message) => parent.postMessage(JSON.stringify(message), "*");
+    const format = (arg) => {
+        if (typeof arg === "string") return arg;
+        if (arg instanceof Element) return arg.outerHTML;
+        if (arg instanceof Error) return arg.stack || String(arg);
+        try {
+            return JSON.stringify(arg) ?? String(arg);
+        } catch {
+            return String(arg);
+        }
+    };
+
+    for (const level of ["log", "info", "warn", "error", "debug"]) {
+        const original = console[level].bind(console);
+        console[level] = (...args) => {
+            original(...args);
+            post({ type: "console", level, message: args.map(format).join(" ") });
+        };
+    }
+
+    addEventListener("error", (e) => {
+        post({ type: "panic", message: e.message, stack: e.error?.stack ?? null });
+    });
+    addEventListener("unhandledrejection", (e) => {
+        post({ type: "panic", message: format(e.reason), stack: e.reason?.stack ?? null });
+    });
+    // reports the requests of the page, gloo's go through fetch
+    let requests = 0;
+    const started = (method, url) => {
+        const id = requests++;
+        const start = performance.now();
+        post({ type: "request", id, method: method.toUpperCase(), url: String(url) });
+        return (status, error) => {
+            const duration_ms = performance.now() - start;
+            post({ type: "response", id, status, error, duration_ms });
+        };
+    };
+    const fetch = globalThis.fetch;
+    globalThis.fetch = (input, init) => {
+        const method = init?.method ?? (input instanceof Request ? input.method : "GET");
+        const done = started(method, input instanceof Request ? input.url : input);
+        return fetch(input, init).then(
+            (response) => {
+                done(response.status, null);
+                return response;
+            },
+            (error) => {
+                done(null, format(error));
+                throw error;
+            },
+        );
+    };
+    const opened = new WeakMap();
+    const { open, send } = XMLHttpRequest.prototype;
+    XMLHttpRequest.prototype.open = function (method, url, ...rest) {
+        opened.set(this, { method: String(method), url });
+        return open.call(this, method, url, ...rest);
+    };
+    XMLHttpRequest.prototype.send = function (...args) {
+        const request = opened.get(this);
+        if (request) {
+            const done = started(request.method, request.url);
+            // a status of 0 is a request that never got a response
+            this.addEventListener("loadend", () => {
+                done(this.status || null, this.status ? null : "no response");
+            });
+        }
+        return send.apply(this, args);
+    };
+    // called by the benchmark the compiler builds in place of the snippet's `main`
+    globalThis.__playgroundBenchmark = (mount_ms, render_ms) => {
+        post({ type: "benchmark", mount_ms, render_ms: Array.from(render_ms) });
+    };
+    // the element tree of the page for the playground's inspector, the bridge's own
+    // scripts left out and cut off after `MAX_NODES` nodes
+    const MAX_NODES = 2000;
+    const inspect = () => {
+        let nodes = 0;
+        const walk = (element) => {
+            nodes++;
+            const children = [];
+            for (const child of element.childNodes) {
+                if (nodes >= MAX_NODES) break;
+                if (child instanceof Element && child.tagName !== "SCRIPT") {
+                    children.push(walk(child));
+                } else if (child instanceof Text && child.data.trim()) {
+                    nodes++;
+                    children.push({ text: child.data.trim() });
+                }
+            }
+            const attributes = Array.from(element.attributes, (it) => [it.name, it.value]);
+            // what's typed into form fields isn't in their attributes
+            if (element instanceof HTMLInputElement || element instanceof HTMLTextAreaElement) {
+                attributes.push(["(value)", element.value]);
+            }
+            return { tag: element.tagName.toLowerCase(), attributes, children };
+        };
+        const root = walk(document.body);
+        return { root, truncated: nodes >= MAX_NODES };
+    };
+    // renders the page into a PNG for exporting it, the playground can't read the
+    // sandboxed page's pixels itself
+    const HTML2CANVAS = "https://esm.sh/html2canvas@1.4.1";
+    const screenshot = async () => {
+        const { default: html2canvas } = await import(HTML2CANVAS);
+        const canvas = await html2canvas(document.body, { useCORS: true, logging: false });
+        return canvas.toDataURL("image/png");
+    };
+    // evaluates what's entered into the playground's console with the page's globals,
+    // and reports the element tree for its inspector and screenshots
+    addEventListener("message", async (e) => {
+        if (e.source !== parent || typeof e.data !== "string") return;
+        let request;
+        try {
+            request = JSON.parse(e.data);
+        } catch {
+            return;
+        }
+        if (request?.type === "inspect") {
+            post({ type: "tree", ...inspect() });
+            return;
+        }
+        if (request?.type === "screenshot") {
+            try {
+                post({ type: "screenshot", data: await screenshot(), error: null });
+            } catch (error) {
+                post({ type: "screenshot", data: null, error: format(error) });
+            }
+            return;
+        }
+        if (request?.type !== "evaluate") return;
+        try {
+            const value = await (0, eval)(request.code);
+            post({ type: "result", message: format(value), error: false });
+        } catch (error) {
+            post({ type: "result", message: format(error), error: true });
+        }
+    });
+    // lets the playground leave fullscreen while the preview has the focus
+    addEventListener("keydown", (e) => {
+        if (e.key === "Escape") post({ type: "escape" });
+    });
+})();
@@ -0,0 +1,44 @@
+//! The markup a snippet adds to the head of the preview's page. It's allowed what the
+//! page needs dressing up with, meta tags, style sheets and elements to mount on, and
+//! nothing that runs code.
+
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, UrlRelative};
+
+/// Of the body, the snippet mounts on them.
+const ELEMENTS: &[&str] = &["div", "main", "section", "header", "footer", "span"];
+
+/// The markup without anything besides the allowed elements and attributes. Links
+/// only go to other origins, the page is served by the backend.
+pub fn sanitize(html: &str) -> String {
+    let mut tags = HashSet::from(["meta", "link", "title"]);
+    tags.extend(ELEMENTS);
+    let tag_attributes = HashMap::from([
+        ("meta", HashSet::from(["name", "property", "content"])),
+        (
+            "link",
+            HashSet::from([
+                "href",
+                "media",
+                "crossorigin",
+                "integrity",
+                "referrerpolicy",
+            ]),
+        ),
+    ]);
+    let rels = HashSet::from(["stylesheet", "preconnect", "icon"]);
+    let tag_attribute_values = HashMap::from([("link", HashMap::from([("rel", rels)]))]);
+
+    Builder::empty()
+        .tags(tags)
+        .tag_attributes(tag_attributes)
+        .tag_attribute_values(tag_attribute_values)
+        .generic_attributes(HashSet::from(["id", "class", "style"]))
+        .generic_attribute_prefixes(HashSet::from(["data-"]))
+        .url_schemes(HashSet::from(["https", "http"]))
+        .url_relative(UrlRelative::Deny)
+        .link_rel(None)
+        .clean(html)
+        .to_string()
+}
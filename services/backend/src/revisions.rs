@@ -0,0 +1,118 @@
+//! Named versions of shared snippets, kept in a `revisions` collection under the paste.
+//! The paste itself stays as it was shared, links pick a revision with `revision`.
+
+use axum::extract::Path as UrlPath;
+use axum::Json;
+use common::errors::ApiError;
+use common::{NewRevision, Revision, RevisionSummary};
+use serde::{Deserialize, Serialize};
+
+use crate::pastes::{self, Document, StringValue};
+use crate::share;
+
+const COLLECTION: &str = "revisions";
+/// Per snippet, they're all listed at once.
+const MAX_REVISIONS: usize = 50;
+/// In characters.
+const MAX_NAME_LEN: usize = 64;
+/// In bytes, well below the size Firestore allows for a document.
+const MAX_CONTENT_LEN: usize = 512 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct RevisionFields {
+    name: StringValue,
+    content: StringValue,
+}
+
+/// What's listed, the code is only fetched when switching to the revision.
+#[derive(Deserialize)]
+struct SummaryFields {
+    name: StringValue,
+}
+
+/// Revisions get ids from Firestore, the same as pastes.
+fn is_revision_id(id: &str) -> bool {
+    pastes::is_paste_id(id)
+}
+
+async fn summaries(paste: &str) -> Result<Vec<RevisionSummary>, ApiError> {
+    let documents: Vec<Document<SummaryFields>> =
+        pastes::list(paste, COLLECTION, MAX_REVISIONS, &["name"]).await?;
+    Ok(documents
+        .into_iter()
+        .map(|it| RevisionSummary {
+            id: it.id().to_string(),
+            name: it.fields.name.string_value,
+            created_at: it.create_time,
+        })
+        .collect())
+}
+
+fn into_revision(document: Document<RevisionFields>) -> Revision {
+    Revision {
+        id: document.id().to_string(),
+        name: document.fields.name.string_value,
+        content: document.fields.content.string_value,
+        created_at: document.create_time,
+    }
+}
+
+/// The revisions of a shared snippet without their code, oldest first.
+pub async fn list(
+    UrlPath(paste): UrlPath<String>,
+) -> Result<Json<Vec<RevisionSummary>>, ApiError> {
+    if !pastes::is_paste_id(&paste) {
+        return Err(ApiError::PasteNotFound(paste));
+    }
+    Ok(Json(summaries(&paste).await?))
+}
+
+pub async fn get(
+    UrlPath((paste, revision)): UrlPath<(String, String)>,
+) -> Result<Json<Revision>, ApiError> {
+    if !pastes::is_paste_id(&paste) {
+        return Err(ApiError::PasteNotFound(paste));
+    }
+    if !is_revision_id(&revision) {
+        return Err(ApiError::RevisionNotFound(revision));
+    }
+    match pastes::get(&paste, COLLECTION, &revision).await? {
+        Some(document) => Ok(Json(into_revision(document))),
+        None => Err(ApiError::RevisionNotFound(revision)),
+    }
+}
+
+/// Saves the code as a new revision of a shared snippet, returning it as it was stored.
+pub async fn add(
+    UrlPath(paste): UrlPath<String>,
+    Json(revision): Json<NewRevision>,
+) -> Result<Json<Revision>, ApiError> {
+    let name = revision.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(ApiError::InvalidRevision(format!(
+            "the name must have 1 to {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    if revision.content.len() > MAX_CONTENT_LEN {
+        return Err(ApiError::InvalidRevision(format!(
+            "the code is over {} bytes",
+            MAX_CONTENT_LEN
+        )));
+    }
+    // only existing snippets get revisions, from whoever shared them
+    share::check_owner(&paste, &revision.owner_token).await?;
+    if summaries(&paste).await?.len() >= MAX_REVISIONS {
+        return Err(ApiError::InvalidRevision(format!(
+            "the snippet already has {} revisions",
+            MAX_REVISIONS
+        )));
+    }
+
+    let fields = RevisionFields {
+        name: StringValue::from(name),
+        content: StringValue::from(revision.content.as_str()),
+    };
+    let document = pastes::create(&paste, COLLECTION, &fields).await?;
+    Ok(Json(into_revision(document)))
+}
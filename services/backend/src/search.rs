@@ -0,0 +1,129 @@
+//! Finds shared snippets by their tags, titles and descriptions. Firestore can't match
+//! text, so the pastes are read from it newest first and matched here. Only the fields
+//! that are matched are read, the code is left in Firestore.
+
+use axum::extract::Query;
+use axum::Json;
+use common::errors::ApiError;
+use common::SnippetSummary;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tokio::sync::Semaphore;
+
+use crate::pastes::{self, ArrayValue, Cursor, Document, StringValue};
+
+/// Pastes read from Firestore at once.
+const PAGE: usize = 100;
+/// Searches look through this many of the newest pastes at most.
+const MAX_SCANNED: usize = 1000;
+const MAX_RESULTS: usize = 50;
+/// In characters.
+const MAX_QUERY_LEN: usize = 200;
+/// Searches running at once, each reads up to [`MAX_SCANNED`] pastes from Firestore.
+const MAX_SEARCHES: usize = 4;
+
+lazy_static! {
+    static ref SEARCHES: Semaphore = Semaphore::new(MAX_SEARCHES);
+}
+
+#[derive(Deserialize)]
+pub struct Search {
+    /// Text the title or description contains, in any case.
+    #[serde(default)]
+    q: String,
+    tag: Option<String>,
+}
+
+/// What's matched, pastes shared without a title, description or tags lack them.
+#[derive(Deserialize)]
+struct SearchFields {
+    title: Option<StringValue>,
+    description: Option<StringValue>,
+    tags: Option<ArrayValue>,
+}
+
+fn into_summary(document: Document<SearchFields>) -> SnippetSummary {
+    let id = document.id().to_string();
+    let fields = document.fields;
+    SnippetSummary {
+        id,
+        title: fields.title.map(|it| it.string_value),
+        description: fields.description.map(|it| it.string_value),
+        tags: fields
+            .tags
+            .map(|it| {
+                it.array_value
+                    .values
+                    .into_iter()
+                    .map(|it| it.string_value)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        created_at: document.create_time,
+    }
+}
+
+/// Shared snippets with the tag, if there is one, containing the text, newest first.
+/// Only the newest [`MAX_SCANNED`] pastes are looked through, and searches past
+/// [`MAX_SEARCHES`] running at once are turned away.
+pub async fn snippets(Query(search): Query<Search>) -> Result<Json<Vec<SnippetSummary>>, ApiError> {
+    let _permit = SEARCHES.try_acquire().map_err(|_| ApiError::SearchBusy)?;
+    let text = search.q.trim().to_lowercase();
+    if text.chars().count() > MAX_QUERY_LEN {
+        return Err(ApiError::InvalidSearch(format!(
+            "the text is over {} characters",
+            MAX_QUERY_LEN
+        )));
+    }
+    // the playground stores tags lowercase
+    let tag = search
+        .tag
+        .map(|it| it.trim().to_lowercase())
+        .filter(|it| !it.is_empty());
+    let contains = |value: Option<&StringValue>| {
+        value.is_some_and(|it| it.string_value.to_lowercase().contains(&text))
+    };
+    let matches = |document: &Document<SearchFields>| {
+        let fields = &document.fields;
+        let tagged = match &tag {
+            Some(tag) => fields.tags.as_ref().is_some_and(|it| {
+                it.array_value
+                    .values
+                    .iter()
+                    .any(|it| &it.string_value == tag)
+            }),
+            None => true,
+        };
+        tagged
+            && (text.is_empty()
+                || contains(fields.title.as_ref())
+                || contains(fields.description.as_ref()))
+    };
+
+    let mut snippets = Vec::new();
+    let mut cursor: Option<Cursor> = None;
+    let mut scanned = 0;
+    loop {
+        let (documents, next) = pastes::newest::<SearchFields>(
+            &["title", "description", "tags"],
+            PAGE,
+            cursor.as_ref(),
+        )
+        .await?;
+        scanned += documents.len();
+        snippets.extend(
+            documents
+                .into_iter()
+                .filter(|it| matches(it))
+                .map(into_summary),
+        );
+        match next {
+            Some(next) if snippets.len() < MAX_RESULTS && scanned < MAX_SCANNED => {
+                cursor = Some(next)
+            }
+            _ => break,
+        }
+    }
+    snippets.truncate(MAX_RESULTS);
+    Ok(Json(snippets))
+}
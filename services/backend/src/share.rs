@@ -0,0 +1,138 @@
+//! Sharing snippets, which are stored as pastes. Whoever shares one gets a token that
+//! only they have, the paste keeps its hash. There are no accounts, so the token is
+//! what tells the owner of a paste apart, only they can save revisions of it.
+
+use axum::Json;
+use common::errors::ApiError;
+use common::{NewSnippet, SharedSnippet};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::pastes::{self, ArrayValue, StringValue, TimestampValue, Values};
+
+/// In bytes, well below the size Firestore allows for a document.
+const MAX_CONTENT_LEN: usize = 512 * 1024;
+/// The same as the playground's share dialog keeps.
+const MAX_TAGS: usize = 5;
+/// In characters.
+const MAX_TAG_LEN: usize = 30;
+
+#[derive(Serialize)]
+struct NullValue {
+    #[serde(rename = "nullValue")]
+    null_value: (),
+}
+
+/// What the playground stores in pastes, the fields missing from a snippet are left
+/// out rather than empty.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NewPasteFields {
+    content: StringValue,
+    created_by: NullValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<StringValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<StringValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<ArrayValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forked_from: Option<StringValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<StringValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    head: Option<StringValue>,
+    owner_token_hash: StringValue,
+    /// See [`pastes::CREATED_AT`].
+    created_at: TimestampValue,
+}
+
+/// Pastes shared before the tokens, or straight to Firestore, have no hash.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OwnerFields {
+    owner_token_hash: Option<StringValue>,
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|it| format!("{:02x}", it)).collect()
+}
+
+fn hash(token: &str) -> String {
+    hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn string_value(value: Option<&str>) -> Option<StringValue> {
+    value
+        .map(str::trim)
+        .filter(|it| !it.is_empty())
+        .map(StringValue::from)
+}
+
+fn validate(snippet: &NewSnippet) -> Result<(), ApiError> {
+    if snippet.content.trim().is_empty() {
+        return Err(ApiError::InvalidSnippet("the code is empty".to_string()));
+    }
+    if snippet.content.len() > MAX_CONTENT_LEN {
+        return Err(ApiError::InvalidSnippet(format!(
+            "the code is over {} bytes",
+            MAX_CONTENT_LEN
+        )));
+    }
+    if snippet.tags.len() > MAX_TAGS
+        || snippet
+            .tags
+            .iter()
+            .any(|it| it.is_empty() || it.chars().count() > MAX_TAG_LEN)
+    {
+        return Err(ApiError::InvalidSnippet(format!(
+            "up to {} tags of 1 to {} characters are allowed",
+            MAX_TAGS, MAX_TAG_LEN
+        )));
+    }
+    if let Some(paste) = snippet.forked_from.as_deref() {
+        if !pastes::is_paste_id(paste) {
+            return Err(ApiError::PasteNotFound(paste.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Stores the snippet as a new paste, responding with its id and the owner's token.
+pub async fn create(Json(snippet): Json<NewSnippet>) -> Result<Json<SharedSnippet>, ApiError> {
+    validate(&snippet)?;
+    let owner_token = hex(&rand::random::<[u8; 32]>());
+    let tags = (!snippet.tags.is_empty()).then(|| ArrayValue {
+        array_value: Values {
+            values: snippet
+                .tags
+                .iter()
+                .map(|it| StringValue::from(it.as_str()))
+                .collect(),
+        },
+    });
+    let fields = NewPasteFields {
+        content: StringValue::from(snippet.content.as_str()),
+        created_by: NullValue { null_value: () },
+        title: string_value(snippet.title.as_deref()),
+        description: string_value(snippet.description.as_deref()),
+        tags,
+        forked_from: string_value(snippet.forked_from.as_deref()),
+        style: string_value(snippet.style.as_deref()),
+        head: string_value(snippet.head.as_deref()),
+        owner_token_hash: StringValue::from(hash(&owner_token).as_str()),
+        created_at: TimestampValue::now()?,
+    };
+    let id = pastes::share(&fields).await?;
+    Ok(Json(SharedSnippet { id, owner_token }))
+}
+
+/// Fails unless `token` is the one the paste was shared with, or if there's no such
+/// paste.
+pub async fn check_owner(paste: &str, token: &str) -> Result<(), ApiError> {
+    let document = pastes::fetch::<OwnerFields>(paste).await?;
+    match document.fields.owner_token_hash {
+        Some(it) if !token.is_empty() && it.string_value == hash(token) => Ok(()),
+        _ => Err(ApiError::NotOwner(paste.to_string())),
+    }
+}
@@ -0,0 +1,152 @@
+//! What links to shared snippets unfurl to in chat apps and forums. Their crawlers don't
+//! run the playground's wasm, so `/s/<id>` links get a page of meta tags from here,
+//! which sends browsers on to the playground.
+
+use axum::extract::{Path as UrlPath, RawQuery};
+use axum::response::Html;
+use axum::Json;
+use common::errors::ApiError;
+use common::SnippetMetadata;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::escape_html;
+use crate::pastes::{self, ArrayValue, Document, StringValue};
+
+lazy_static! {
+    /// Where the page sends browsers, the playground's own address.
+    static ref PLAYGROUND_URL: String =
+        std::env::var("PLAYGROUND_URL").unwrap_or_else(|_| "https://play.yew.rs".to_string());
+}
+
+/// For snippets shared without a description.
+const DEFAULT_DESCRIPTION: &str = "A snippet shared from the Yew Playground";
+
+/// Pastes shared without a title, description or tags lack them.
+#[derive(Deserialize)]
+struct MetadataFields {
+    content: StringValue,
+    title: Option<StringValue>,
+    description: Option<StringValue>,
+    tags: Option<ArrayValue>,
+}
+
+fn into_metadata(document: Document<MetadataFields>) -> SnippetMetadata {
+    let id = document.id().to_string();
+    let fields = document.fields;
+    let content = fields.content.string_value;
+    SnippetMetadata {
+        id,
+        title: fields.title.map(|it| it.string_value),
+        description: fields.description.map(|it| it.string_value),
+        tags: fields
+            .tags
+            .map(|it| {
+                it.array_value
+                    .values
+                    .into_iter()
+                    .map(|it| it.string_value)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        lines: content.lines().count(),
+        size: content.len(),
+        created_at: document.create_time,
+    }
+}
+
+async fn fetch(paste: &str) -> Result<SnippetMetadata, ApiError> {
+    Ok(into_metadata(pastes::fetch(paste).await?))
+}
+
+/// A shared snippet without its code, for bots to build previews of links from.
+pub async fn metadata(UrlPath(paste): UrlPath<String>) -> Result<Json<SnippetMetadata>, ApiError> {
+    Ok(Json(fetch(&paste).await?))
+}
+
+fn code_size(metadata: &SnippetMetadata) -> String {
+    format!(
+        "{} lines, {:.1} kB",
+        metadata.lines,
+        metadata.size as f64 / 1000.0
+    )
+}
+
+/// The page of `/s/<id>` links, its query holds the build options of the playground's
+/// own links and is passed on to them.
+pub async fn page(
+    UrlPath(paste): UrlPath<String>,
+    RawQuery(query): RawQuery,
+) -> Result<Html<String>, ApiError> {
+    let metadata = fetch(&paste).await?;
+    let mut url = format!("{}/?shared={}", *PLAYGROUND_URL, metadata.id);
+    if let Some(query) = query.filter(|it| !it.is_empty()) {
+        url.push('&');
+        url.push_str(&query);
+    }
+    let title = metadata
+        .title
+        .as_deref()
+        .map_or("Yew Playground".to_string(), |it| {
+            format!("{} - Yew Playground", it)
+        });
+    let description = format!(
+        "{} ({} of Rust)",
+        metadata
+            .description
+            .as_deref()
+            .unwrap_or(DEFAULT_DESCRIPTION)
+            .trim_end_matches('.'),
+        code_size(&metadata)
+    );
+    let tags = metadata
+        .tags
+        .iter()
+        .map(|it| format!("#{}", it))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let tags = if tags.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"
+    <meta name="twitter:label2" content="Tags">
+    <meta name="twitter:data2" content="{}">"#,
+            escape_html(&tags)
+        )
+    };
+
+    let (url, title, description) = (
+        escape_html(&url),
+        escape_html(&title),
+        escape_html(&description),
+    );
+    Ok(Html(format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>{title}</title>
+    <meta name="description" content="{description}">
+    <meta property="og:type" content="website">
+    <meta property="og:site_name" content="Yew Playground">
+    <meta property="og:title" content="{title}">
+    <meta property="og:description" content="{description}">
+    <meta property="og:url" content="{url}">
+    <meta name="twitter:card" content="summary">
+    <meta name="twitter:title" content="{title}">
+    <meta name="twitter:description" content="{description}">
+    <meta name="twitter:label1" content="Code">
+    <meta name="twitter:data1" content="{size}">{tags}
+    <meta name="theme-color" content="#009a5b">
+    <link rel="canonical" href="{url}">
+    <meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+    <a href="{url}">{title}</a>
+</body>
+</html>
+"##,
+        size = code_size(&metadata),
+    )))
+}
@@ -0,0 +1,414 @@
+//! Typed client of the backend's API. Requests go through a [`Transport`], reqwest's
+//! for native programs like the command-line client and gloo-net's for the frontend,
+//! so both share the endpoints and their types instead of each writing its own.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use url::Url;
+
+use crate::{
+    BuildOptions, ClippyResponse, CompletionResponse, CrateInfo, CrateSummary, ErrorResponse,
+    ExampleSummary, ExpandResponse, FormatResponse, NewRevision, NewSnippet, Revision,
+    RevisionSummary, RunReport, SharedSnippet, Snippet, SnippetMetadata, SnippetSummary,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    /// The body and its content type.
+    pub body: Option<(&'static str, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// Keyed by their names in lower case.
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends the client's requests. Failing to get a response is an error, responses with
+/// any status aren't.
+pub trait Transport {
+    fn send(&self, request: HttpRequest) -> impl Future<Output = Result<HttpResponse>>;
+}
+
+/// Sizes of a successful build's artifacts, in bytes, and how long it took.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildStats {
+    pub wasm_size: u64,
+    /// Estimated by the backend, what the module is served as with gzip.
+    pub wasm_gzip_size: u64,
+    pub js_size: u64,
+    pub build_ms: u64,
+}
+
+impl BuildStats {
+    /// Read from the headers of the page, which only carry them if the build succeeded.
+    fn from_headers(headers: &BTreeMap<String, String>) -> Option<Self> {
+        let size = |name| headers.get(name)?.parse().ok();
+        // the total of the `Server-Timing` header, as in `total;dur=1234`
+        let build_ms = headers
+            .get("server-timing")?
+            .split(',')
+            .find_map(|metric| {
+                let duration = metric.trim().strip_prefix("total;dur=")?;
+                duration.parse().ok()
+            })?;
+        Some(Self {
+            wasm_size: size("x-wasm-size")?,
+            wasm_gzip_size: size("x-wasm-gzip-size")?,
+            js_size: size("x-js-size")?,
+            build_ms,
+        })
+    }
+}
+
+pub struct RunOutput {
+    pub page: String,
+    /// `None` if the page reports a compile error.
+    pub stats: Option<BuildStats>,
+    /// ID under which the backend keeps the build around, if it does.
+    pub build_id: Option<String>,
+}
+
+fn query<P: Serialize + ?Sized>(params: &P) -> String {
+    serde_urlencoded::to_string(params).expect("parameters serialize")
+}
+
+/// Reads the JSON response of the endpoint under `path`.
+fn parse<R: DeserializeOwned>(path: &[&str], body: &str) -> Result<R> {
+    serde_json::from_str(body).with_context(|| format!("invalid response of /{}", path.join("/")))
+}
+
+/// Joins the query strings, leaving out empty ones.
+fn joined(queries: &[&str]) -> String {
+    queries
+        .iter()
+        .filter(|it| !it.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+pub struct PlaygroundClient<T> {
+    /// Of the API, e.g. `https://api.play.yew.rs/api`.
+    base_url: String,
+    transport: T,
+}
+
+impl<T: Transport> PlaygroundClient<T> {
+    pub fn new(base_url: impl Into<String>, transport: T) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            transport,
+        }
+    }
+
+    /// The URL of the endpoint under `path`, whose segments are percent-encoded.
+    fn url(&self, path: &[&str], query: &str) -> Result<String> {
+        let invalid = || anyhow!("invalid API url {}", self.base_url);
+        let mut url = Url::parse(&self.base_url).map_err(|_| invalid())?;
+        url.path_segments_mut()
+            .map_err(|()| invalid())?
+            .pop_if_empty()
+            .extend(path);
+        if !query.is_empty() {
+            url.set_query(Some(query));
+        }
+        Ok(url.into())
+    }
+
+    /// Sends the request, failing with the [`ErrorResponse`] of responses that aren't
+    /// successful, or their text if they aren't one.
+    async fn send(
+        &self,
+        method: Method,
+        url: String,
+        body: Option<(&'static str, String)>,
+    ) -> Result<HttpResponse> {
+        let resp = self
+            .transport
+            .send(HttpRequest { method, url, body })
+            .await?;
+        if !resp.ok() {
+            return Err(match serde_json::from_str::<ErrorResponse>(&resp.body) {
+                Ok(error) => error.into(),
+                Err(_) => anyhow!("{}", resp.body),
+            });
+        }
+        Ok(resp)
+    }
+
+    async fn get_text(&self, path: &[&str], query: &str) -> Result<String> {
+        let resp = self.send(Method::Get, self.url(path, query)?, None).await?;
+        Ok(resp.body)
+    }
+
+    async fn get_json<R: DeserializeOwned>(&self, path: &[&str], query: &str) -> Result<R> {
+        let body = self.get_text(path, query).await?;
+        parse(path, &body)
+    }
+
+    /// Posts `code` as plain text.
+    async fn post_code<R: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        query: &str,
+        code: &str,
+    ) -> Result<R> {
+        let body = Some(("text/plain", code.to_string()));
+        let resp = self
+            .send(Method::Post, self.url(path, query)?, body)
+            .await?;
+        parse(path, &resp.body)
+    }
+
+    async fn post_json<B: Serialize, R: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &B,
+    ) -> Result<R> {
+        let body = Some(("application/json", serde_json::to_string(body)?));
+        let resp = self.send(Method::Post, self.url(path, "")?, body).await?;
+        parse(path, &resp.body)
+    }
+
+    /// Whether the backend can reach the compiler. Fails if the backend itself can't be
+    /// reached.
+    pub async fn health(&self) -> Result<bool> {
+        let request = HttpRequest {
+            method: Method::Get,
+            url: self.url(&["health"], "")?,
+            body: None,
+        };
+        Ok(self.transport.send(request).await?.ok())
+    }
+
+    /// Builds the snippet and returns the page running it. A build that fails to
+    /// compile still returns a page, it reports the errors. Its stages are reported
+    /// under the `progress` id if there is one.
+    pub async fn run(
+        &self,
+        snippet: &Snippet,
+        options: &BuildOptions,
+        progress: Option<&str>,
+    ) -> Result<RunOutput> {
+        let progress = progress.map(|it| ("progress", it));
+        let url = self.url(
+            &["run"],
+            &joined(&[&options.query(), &query(progress.as_slice())]),
+        )?;
+        let body = Some(("application/json", serde_json::to_string(snippet)?));
+        let resp = self.send(Method::Post, url, body).await?;
+        Ok(RunOutput {
+            stats: BuildStats::from_headers(&resp.headers),
+            build_id: resp.headers.get("x-build-id").cloned(),
+            page: resp.body,
+        })
+    }
+
+    /// Builds the snippet like [`run`](Self::run) but returns just the outcome: the sizes
+    /// of the build if it compiled, cargo's output and diagnostics if it didn't.
+    pub async fn run_report(&self, snippet: &Snippet, options: &BuildOptions) -> Result<RunReport> {
+        let url = self.url(&["run.json"], &options.query())?;
+        let body = Some(("application/json", serde_json::to_string(snippet)?));
+        let resp = self.send(Method::Post, url, body).await?;
+        parse(&["run.json"], &resp.body)
+    }
+
+    /// The page running a kept build. It goes without the snippet's style and head,
+    /// which aren't kept along with the build.
+    pub async fn build_page(&self, build_id: &str) -> Result<String> {
+        self.get_text(&["builds", build_id], "").await
+    }
+
+    /// The JS wasm-bindgen generated for a kept build, which loads and drives its module.
+    pub async fn glue_js(&self, build_id: &str) -> Result<String> {
+        self.get_text(&["builds", build_id, "js"], "").await
+    }
+
+    /// Formats the code with rustfmt.
+    pub async fn format(&self, code: &str) -> Result<FormatResponse> {
+        self.post_code(&["format"], "", code).await
+    }
+
+    /// Lints the code with clippy, built with the given options.
+    pub async fn clippy(&self, code: &str, options: &BuildOptions) -> Result<ClippyResponse> {
+        self.post_code(&["clippy"], &options.query(), code).await
+    }
+
+    /// Expands the macros in the code, built with the given options.
+    pub async fn expand(&self, code: &str, options: &BuildOptions) -> Result<ExpandResponse> {
+        self.post_code(&["expand"], &options.query(), code).await
+    }
+
+    /// Asks rust-analyzer for completions at the 1-based `line` and `column`.
+    pub async fn complete(&self, code: &str, line: u32, column: u32) -> Result<CompletionResponse> {
+        let (line, column) = (line.to_string(), column.to_string());
+        let query = query(&[("line", &line), ("column", &column)]);
+        self.post_code(&["complete"], &query, code).await
+    }
+
+    /// The allowed crates matching `text`, all of them if it's empty.
+    pub async fn crates(&self, text: &str) -> Result<Vec<CrateSummary>> {
+        self.get_json(&["crates"], &query(&[("q", text)])).await
+    }
+
+    pub async fn crate_info(&self, name: &str) -> Result<CrateInfo> {
+        self.get_json(&["crates", name], "").await
+    }
+
+    pub async fn examples(&self) -> Result<Vec<ExampleSummary>> {
+        self.get_json(&["examples"], "").await
+    }
+
+    /// The code of the example.
+    pub async fn example(&self, id: &str) -> Result<String> {
+        self.get_text(&["examples", id], "").await
+    }
+
+    pub fn example_thumbnail_url(&self, id: &str) -> Result<String> {
+        self.url(&["examples", id, "thumbnail.svg"], "")
+    }
+
+    /// Shared snippets whose title or description contains `text`, with `tag` if
+    /// it's given, newest first.
+    pub async fn snippets(&self, text: &str, tag: Option<&str>) -> Result<Vec<SnippetSummary>> {
+        let mut params = vec![("q", text)];
+        if let Some(tag) = tag {
+            params.push(("tag", tag));
+        }
+        self.get_json(&["snippets"], &query(&params)).await
+    }
+
+    /// What links to the shared snippet unfurl to.
+    pub async fn snippet_metadata(&self, paste: &str) -> Result<SnippetMetadata> {
+        self.get_json(&["snippets", paste], "").await
+    }
+
+    /// Stores the snippet as a paste. Keep the owner token of the response to save
+    /// revisions of it.
+    pub async fn share(&self, snippet: &NewSnippet) -> Result<SharedSnippet> {
+        self.post_json(&["snippets"], snippet).await
+    }
+
+    /// The revisions of the paste with the given id, oldest first.
+    pub async fn revisions(&self, paste: &str) -> Result<Vec<RevisionSummary>> {
+        self.get_json(&["revisions", paste], "").await
+    }
+
+    pub async fn revision(&self, paste: &str, revision: &str) -> Result<Revision> {
+        self.get_json(&["revisions", paste, revision], "").await
+    }
+
+    pub async fn add_revision(&self, paste: &str, revision: &NewRevision) -> Result<Revision> {
+        self.post_json(&["revisions", paste], revision).await
+    }
+}
+
+/// Sends requests with reqwest, for native programs.
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = match request.method {
+            Method::Get => self.client.get(&request.url),
+            Method::Post => self.client.post(&request.url),
+        };
+        if let Some((content_type, body)) = request.body {
+            builder = builder
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(body);
+        }
+        let resp = builder
+            .send()
+            .await
+            .with_context(|| format!("failed to reach {}", request.url))?;
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        Ok(HttpResponse {
+            status: resp.status().as_u16(),
+            headers,
+            body: resp.text().await?,
+        })
+    }
+}
+
+/// Sends requests with the browser's `fetch`, for the frontend.
+#[cfg(feature = "gloo")]
+#[derive(Debug, Clone, Default)]
+pub struct GlooTransport {
+    signal: Option<web_sys::AbortSignal>,
+}
+
+#[cfg(feature = "gloo")]
+impl GlooTransport {
+    /// Aborting through `signal` cancels the requests, the backend stops working on
+    /// them once they're gone.
+    pub fn with_abort_signal(signal: web_sys::AbortSignal) -> Self {
+        Self {
+            signal: Some(signal),
+        }
+    }
+}
+
+#[cfg(feature = "gloo")]
+impl Transport for GlooTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        use gloo_net::http::Request;
+
+        let mut builder = match request.method {
+            Method::Get => Request::get(&request.url),
+            Method::Post => Request::post(&request.url),
+        }
+        .abort_signal(self.signal.as_ref());
+        if let Some((content_type, body)) = request.body {
+            builder = builder.header("content-type", content_type).body(body);
+        }
+        let resp = builder.send().await?;
+        let headers = resp
+            .headers()
+            .entries()
+            .map(|(name, value)| (name.to_lowercase(), value))
+            .collect();
+        Ok(HttpResponse {
+            status: resp.status(),
+            headers,
+            body: resp.text().await?,
+        })
+    }
+}
@@ -1,9 +1,46 @@
 use std::process::Output;
 
-use axum::body::Full;
-use axum::http::{header, HeaderValue, StatusCode};
+use axum::http::{HeaderValue, Request, StatusCode};
+use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
-use axum::{body, BoxError};
+use axum::{BoxError, Json};
+
+use crate::{ErrorResponse, REQUEST_ID_HEADER};
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The ID of the request being handled, see [`with_request_id`].
+pub fn request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// IDs requests come with are kept if they can't garble the logs.
+fn is_request_id(id: &str) -> bool {
+    (1..=64).contains(&id.len())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Middleware that gives requests an ID, the one they came with in the request ID header
+/// if any. Their errors report it, and responses carry it in the same header.
+pub async fn with_request_id<B>(req: Request<B>, next: Next<B>) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|it| it.to_str().ok())
+        .filter(|it| is_request_id(it))
+        .map(str::to_string)
+        .unwrap_or_else(|| bson::oid::ObjectId::new().to_hex());
+
+    let mut res = REQUEST_ID.scope(id.clone(), next.run(req)).await;
+    if let Ok(id) = HeaderValue::from_str(&id) {
+        res.headers_mut().insert(REQUEST_ID_HEADER, id);
+    }
+    res
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -13,45 +50,252 @@ pub enum ApiError {
     BuildFileNotFound(&'static str),
     #[error("request must have a body but none was found")]
     NoBody,
+    #[error("{0:?} is not JSON, BSON or CBOR")]
+    UnsupportedMediaType(String),
+    #[error("invalid body: {0}")]
+    InvalidBody(String),
     #[error("build failed with error {}\n{}", .0.status, String::from_utf8_lossy(&.0.stderr))]
     BuildFailed(Output),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
     #[error("failed to deserialize bson: {0}")]
     BsonDeserializeError(#[from] bson::de::Error),
+    /// The compiler responded in a different [`WIRE_VERSION`](crate::WIRE_VERSION) that
+    /// the backend couldn't read, `None` if it didn't say which.
+    #[error("the compiler responded in wire version {}, which version {} can't read: {1}", .0.map_or("0".to_string(), |it| it.to_string()), crate::WIRE_VERSION)]
+    IncompatibleWireVersion(Option<u32>, bson::de::Error),
+    /// A request of the other service in a different [`WIRE_VERSION`](crate::WIRE_VERSION)
+    /// that couldn't be read.
+    #[error(
+        "the request is in wire version {0}, which version {} can't read: {1}",
+        crate::WIRE_VERSION
+    )]
+    IncompatibleWireVersionRequest(u32, String),
+    /// A client's error another service found, relayed as it was.
+    #[error("{}", .1.message)]
+    Relayed(StatusCode, ErrorResponse),
+    /// Another service failed, with the status it responded with and the details.
+    #[error("{service} responded with {status}: {detail}")]
+    Upstream {
+        service: &'static str,
+        status: StatusCode,
+        detail: String,
+    },
+    #[error("the request took too long")]
+    Timeout,
+    #[error("custom dependencies are not allowed")]
+    CustomDependenciesDisabled,
+    #[error("{0} is not one of the crates snippets may depend on")]
+    CrateNotAllowed(String),
+    #[error("invalid dependency: {0}")]
+    InvalidDependency(String),
+    #[error("invalid snippet: {0}")]
+    InvalidSnippet(String),
+    #[error("{0} is not a pre-installed crate")]
+    UnknownFeature(String),
+    #[error("{0} is not a RUSTFLAGS preset")]
+    UnknownRustflagsPreset(String),
+    #[error("build {0} is not retained")]
+    BuildNotFound(String),
+    #[error("there is no example called {0}")]
+    ExampleNotFound(String),
+    #[error("Yew {0} is not available for offline builds")]
+    YewVersionUnavailable(&'static str),
+    #[error("{0} is not a valid progress id")]
+    InvalidProgressId(String),
+    #[error("no build with progress id {0} is running")]
+    ProgressNotFound(String),
+    #[error("{0} is not a valid room id")]
+    InvalidRoomId(String),
+    #[error("the room is full")]
+    RoomFull,
+    #[error("too many rooms are open, try again later")]
+    TooManyRooms,
+    #[error("there is no shared snippet called {0}")]
+    PasteNotFound(String),
+    #[error("there is no revision called {0}")]
+    RevisionNotFound(String),
+    #[error("only whoever shared {0} can save revisions of it")]
+    NotOwner(String),
+    #[error("invalid revision: {0}")]
+    InvalidRevision(String),
+    #[error("invalid benchmark: {0}")]
+    InvalidBenchmark(String),
+    #[error("invalid search: {0}")]
+    InvalidSearch(String),
+    #[error("too many searches are running, try again shortly")]
+    SearchBusy,
+    #[error("server-side rendering only runs while builds are sandboxed")]
+    SsrUnavailable,
+    /// Holds cargo-deny's report of the violations.
+    #[error("{0}")]
+    PolicyViolation(String),
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let status = match self {
+impl ApiError {
+    /// The error another service responded with, `body` is its [`ErrorResponse`] or,
+    /// from releases before those, its text.
+    pub fn upstream(service: &'static str, status: StatusCode, body: String) -> Self {
+        match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(error) if status.is_client_error() => ApiError::Relayed(status, error),
+            Ok(error) => ApiError::Upstream {
+                service,
+                status,
+                detail: error.message,
+            },
+            Err(_) => ApiError::Upstream {
+                service,
+                status,
+                detail: body,
+            },
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
             ApiError::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::BuildFileNotFound(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::NoBody => StatusCode::BAD_REQUEST,
+            ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::InvalidBody(_) => StatusCode::BAD_REQUEST,
             ApiError::BuildFailed(_) => StatusCode::BAD_REQUEST,
             ApiError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::BsonDeserializeError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::IncompatibleWireVersion(..)
+            | ApiError::IncompatibleWireVersionRequest(..) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Relayed(status, _) => *status,
+            ApiError::Upstream { .. } => StatusCode::BAD_GATEWAY,
+            ApiError::Timeout => StatusCode::REQUEST_TIMEOUT,
+            ApiError::CustomDependenciesDisabled => StatusCode::BAD_REQUEST,
+            ApiError::CrateNotAllowed(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidDependency(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidSnippet(_) => StatusCode::BAD_REQUEST,
+            ApiError::UnknownFeature(_) => StatusCode::BAD_REQUEST,
+            ApiError::UnknownRustflagsPreset(_) => StatusCode::BAD_REQUEST,
+            ApiError::BuildNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::ExampleNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::YewVersionUnavailable(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidProgressId(_) => StatusCode::BAD_REQUEST,
+            ApiError::ProgressNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::InvalidRoomId(_) => StatusCode::BAD_REQUEST,
+            ApiError::RoomFull => StatusCode::CONFLICT,
+            ApiError::TooManyRooms => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::PasteNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::RevisionNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::NotOwner(_) => StatusCode::FORBIDDEN,
+            ApiError::InvalidRevision(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidBenchmark(_) => StatusCode::BAD_REQUEST,
+            ApiError::InvalidSearch(_) => StatusCode::BAD_REQUEST,
+            ApiError::SearchBusy => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::SsrUnavailable => StatusCode::NOT_IMPLEMENTED,
+            ApiError::PolicyViolation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    /// The [`ErrorResponse::code`] of the error. Errors of the services themselves are
+    /// all `internal`, clients can't do anything about them.
+    pub fn code(&self) -> &str {
+        match self {
+            ApiError::IoError(_)
+            | ApiError::BuildFileNotFound(_)
+            | ApiError::Unknown(_)
+            | ApiError::BsonDeserializeError(_) => "internal",
+            ApiError::NoBody => "no_body",
+            ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::InvalidBody(_) => "invalid_body",
+            ApiError::BuildFailed(_) => "build_failed",
+            ApiError::IncompatibleWireVersion(..)
+            | ApiError::IncompatibleWireVersionRequest(..) => "incompatible_wire_version",
+            ApiError::Relayed(_, error) => &error.code,
+            ApiError::Upstream { .. } => "upstream",
+            ApiError::Timeout => "timeout",
+            ApiError::CustomDependenciesDisabled => "custom_dependencies_disabled",
+            ApiError::CrateNotAllowed(_) => "crate_not_allowed",
+            ApiError::InvalidDependency(_) => "invalid_dependency",
+            ApiError::InvalidSnippet(_) => "invalid_snippet",
+            ApiError::UnknownFeature(_) => "unknown_feature",
+            ApiError::UnknownRustflagsPreset(_) => "unknown_rustflags_preset",
+            ApiError::BuildNotFound(_) => "build_not_found",
+            ApiError::ExampleNotFound(_) => "example_not_found",
+            ApiError::YewVersionUnavailable(_) => "yew_version_unavailable",
+            ApiError::InvalidProgressId(_) => "invalid_progress_id",
+            ApiError::ProgressNotFound(_) => "progress_not_found",
+            ApiError::InvalidRoomId(_) => "invalid_room_id",
+            ApiError::RoomFull => "room_full",
+            ApiError::TooManyRooms => "too_many_rooms",
+            ApiError::PasteNotFound(_) => "paste_not_found",
+            ApiError::RevisionNotFound(_) => "revision_not_found",
+            ApiError::NotOwner(_) => "not_owner",
+            ApiError::InvalidRevision(_) => "invalid_revision",
+            ApiError::InvalidBenchmark(_) => "invalid_benchmark",
+            ApiError::InvalidSearch(_) => "invalid_search",
+            ApiError::SearchBusy => "search_busy",
+            ApiError::SsrUnavailable => "ssr_unavailable",
+            ApiError::PolicyViolation(_) => "policy_violation",
+        }
+    }
+
+    /// Whether the request may succeed later, once the services are upgraded or the
+    /// ones they depend on are back.
+    pub fn retryable(&self) -> bool {
+        match self {
+            ApiError::IncompatibleWireVersion(..)
+            | ApiError::IncompatibleWireVersionRequest(..)
+            | ApiError::TooManyRooms
+            | ApiError::SearchBusy
+            | ApiError::Timeout => true,
+            ApiError::Relayed(_, error) => error.retryable,
+            ApiError::Upstream { status, .. } => matches!(
+                *status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            _ => false,
+        }
+    }
+
+    /// What clients are told. Errors of the services themselves are only logged, their
+    /// details are of no use to clients and may give away how the services are set up.
+    pub fn message(&self) -> String {
+        match self {
+            ApiError::IoError(_)
+            | ApiError::BuildFileNotFound(_)
+            | ApiError::Unknown(_)
+            | ApiError::BsonDeserializeError(_) => "internal error".to_string(),
+            ApiError::IncompatibleWireVersion(..)
+            | ApiError::IncompatibleWireVersionRequest(..) => {
+                "the playground is being upgraded, try again shortly".to_string()
+            }
+            ApiError::Upstream { service, .. } => format!("{} failed to respond", service),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        let request_id = request_id();
+        if status.is_server_error() {
+            tracing::error!(?request_id, error = %self, "request failed");
+        }
+        let error = ErrorResponse {
+            code: self.code().to_string(),
+            message: self.message(),
+            retryable: self.retryable(),
+            request_id,
         };
-        Response::builder()
-            .status(status)
-            .header(
-                header::CONTENT_TYPE,
-                HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
-            )
-            .body(body::boxed(Full::from(self.to_string())))
-            .unwrap()
+        (status, Json(error)).into_response()
     }
 }
 
-pub async fn timeout_or_500(err: BoxError) -> (StatusCode, String) {
+/// For [`HandleErrorLayer`](axum::error_handling::HandleErrorLayer)s of timeouts.
+pub async fn timeout_or_500(err: BoxError) -> ApiError {
     if err.is::<tower::timeout::error::Elapsed>() {
-        (
-            StatusCode::REQUEST_TIMEOUT,
-            "Request took too long".to_string(),
-        )
+        ApiError::Timeout
     } else {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Unhandled internal error: {}", err),
-        )
+        ApiError::Unknown(anyhow::anyhow!("unhandled internal error: {}", err))
     }
 }
@@ -1,8 +1,16 @@
+pub mod client;
+#[cfg(feature = "server")]
 pub mod errors;
+#[cfg(feature = "server")]
 pub mod response;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+#[cfg(feature = "server")]
 pub fn init_tracing() {
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
@@ -21,6 +29,889 @@ pub enum Response {
         index_html: String,
         js: String,
         wasm: Vec<u8>,
+        #[serde(default)]
+        timings: BuildTimings,
+        /// ID under which the compiler retains the build, see `/artifacts/:id`.
+        #[serde(default)]
+        build_id: Option<String>,
+        #[serde(default)]
+        integrity: Integrity,
+        #[serde(default)]
+        manifest: Option<Box<BuildManifest>>,
+    },
+    CompileError(#[serde(serialize_with = "serialize_compile_error")] CompileError),
+}
+
+/// Writes compile errors for a backend that asked in [`WIRE_VERSION`] 1 or before as
+/// just cargo's output, which is all those read.
+fn serialize_compile_error<S: serde::Serializer>(
+    error: &CompileError,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[cfg(feature = "server")]
+    if response::requested_version() < 2 {
+        return serializer.serialize_str(&error.stderr);
+    }
+    error.serialize(serializer)
+}
+
+/// A build that didn't compile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "CompileErrorRepr")]
+pub struct CompileError {
+    /// Cargo's output, with its colors.
+    pub stderr: String,
+    /// Those of cargo's diagnostics that point into the snippet.
+    pub diagnostics: Vec<FileDiagnostic>,
+}
+
+/// Compilers before [`WIRE_VERSION`] 2 sent just cargo's output.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CompileErrorRepr {
+    Stderr(String),
+    Diagnosed {
+        stderr: String,
+        #[serde(default)]
+        diagnostics: Vec<FileDiagnostic>,
+    },
+}
+
+impl From<CompileErrorRepr> for CompileError {
+    fn from(repr: CompileErrorRepr) -> Self {
+        match repr {
+            CompileErrorRepr::Stderr(stderr) => CompileError {
+                stderr,
+                diagnostics: Vec::new(),
+            },
+            CompileErrorRepr::Diagnosed {
+                stderr,
+                diagnostics,
+            } => CompileError {
+                stderr,
+                diagnostics,
+            },
+        }
+    }
+}
+
+/// Removes ANSI escape sequences like the colors in cargo's output.
+pub fn strip_ansi(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            stripped.push(c);
+            continue;
+        }
+        // a control sequence ends with its first character in `@..=~`
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+/// The outcome of a build as `/run.json` responds with it, for programs rather than
+/// browsers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RunReport {
+    Ok {
+        #[serde(flatten)]
+        stats: client::BuildStats,
+        /// ID under which the backend keeps the build around, if it does.
+        build_id: Option<String>,
     },
+    Error(CompileError),
+}
+
+/// Everything a build was made with besides the snippet, so it can be reproduced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub toolchain: ToolchainVersions,
+    pub options: BuildOptions,
+    /// The `RUSTFLAGS` the selected presets expanded to.
+    pub rustflags: String,
+    /// SHA-256 of the workspace's `Cargo.lock`, hex encoded.
+    pub lockfile_sha256: String,
+    /// The packages `Cargo.lock` resolved to.
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Where the package came from, `None` for path dependencies.
+    pub source: Option<String>,
+}
+
+/// SHA-256 hashes of the build's artifacts, in the `sha256-<base64>` form of
+/// subresource integrity attributes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Integrity {
+    pub js: String,
+    pub wasm: String,
+}
+
+/// How long each phase of a build took, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildTimings {
+    pub dependency_resolution_ms: u64,
+    pub rustc_ms: u64,
+    pub wasm_bindgen_ms: u64,
+    pub wasm_opt_ms: u64,
+    pub total_ms: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpandResponse {
+    Expanded(String),
+    CompileError(String),
+}
+
+/// A row of `twiggy top`: an item and the bytes it takes up by itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeEntry {
+    pub name: String,
+    pub shallow_size: u64,
+    pub shallow_size_percent: f64,
+}
+
+/// A node of `twiggy dominators`: an item, the bytes that would be removed along
+/// with it, and the items it dominates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DominatorEntry {
+    pub name: String,
+    pub shallow_size: u64,
+    pub retained_size: u64,
+    pub retained_size_percent: f64,
+    #[serde(default)]
+    pub children: Vec<DominatorEntry>,
+}
+
+/// A custom section of a wasm module, such as `name` or `producers`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomSection {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Where the bytes of a built wasm module go.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizeReport {
+    pub wasm_size: u64,
+    /// Empty if the module was built with [`BuildOptions::strip`].
+    #[serde(default)]
+    pub custom_sections: Vec<CustomSection>,
+    pub top: Vec<SizeEntry>,
+    pub dominators: Vec<DominatorEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyzeResponse {
+    Report(SizeReport),
+    CompileError(String),
+}
+
+/// The HTML the snippet's `App` component renders to on the server.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SsrResponse {
+    Rendered(String),
     CompileError(String),
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Edition {
+    #[serde(rename = "2015")]
+    E2015,
+    #[serde(rename = "2018")]
+    E2018,
+    #[default]
+    #[serde(rename = "2021")]
+    E2021,
+    #[serde(rename = "2024")]
+    E2024,
+}
+
+impl Edition {
+    /// The default first, the way the playground lists them.
+    pub const ALL: [Edition; 4] = [
+        Edition::E2021,
+        Edition::E2024,
+        Edition::E2018,
+        Edition::E2015,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Edition::E2015 => "2015",
+            Edition::E2018 => "2018",
+            Edition::E2021 => "2021",
+            Edition::E2024 => "2024",
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Profile {
+    /// Fast to build.
+    Debug,
+    /// Realistic size and performance.
+    #[default]
+    Release,
+    /// Release with every setting tuned for the smallest wasm binary.
+    #[serde(rename = "min-size")]
+    MinSize,
+}
+
+impl Profile {
+    /// The default first, the way the playground lists them.
+    pub const ALL: [Profile; 3] = [Profile::Release, Profile::Debug, Profile::MinSize];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Profile::Debug => "debug",
+            Profile::Release => "release",
+            Profile::MinSize => "min-size",
+        }
+    }
+}
+
+/// Rust release channel to build with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Toolchain {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Toolchain {
+    /// The default first, the way the playground lists them.
+    pub const ALL: [Toolchain; 3] = [Toolchain::Stable, Toolchain::Beta, Toolchain::Nightly];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Toolchain::Stable => "stable",
+            Toolchain::Beta => "beta",
+            Toolchain::Nightly => "nightly",
+        }
+    }
+}
+
+/// Yew release the snippet is built against. The app template pins the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum YewVersion {
+    #[serde(rename = "0.20")]
+    V0_20,
+    #[default]
+    #[serde(rename = "0.21")]
+    V0_21,
+}
+
+impl YewVersion {
+    /// The default first, the way the playground lists them.
+    pub const ALL: [YewVersion; 2] = [YewVersion::V0_21, YewVersion::V0_20];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            YewVersion::V0_20 => "0.20",
+            YewVersion::V0_21 => "0.21",
+        }
+    }
+}
+
+/// Files making up a snippet, keyed by their path relative to the app's root.
+///
+/// Besides the app's own `src/`, a snippet may define extra crates as
+/// `crates/<name>/src/lib.rs` plus more sources. These are built as members of a cargo
+/// workspace and the app can depend on them by name. Their manifests are written by
+/// the compiler, the crates have no dependencies of their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub files: BTreeMap<String, String>,
+}
+
+impl Snippet {
+    pub const MAIN: &'static str = "src/main.rs";
+    /// CSS the preview's page is styled with, it isn't built.
+    pub const STYLE: &'static str = "src/style.css";
+    /// Markup added to the head of the preview's page, sanitized before it is.
+    pub const HEAD: &'static str = "src/head.html";
+
+    /// A snippet consisting only of `src/main.rs`.
+    pub fn single(code: String) -> Self {
+        Self {
+            files: BTreeMap::from([(Self::MAIN.to_string(), code)]),
+        }
+    }
+
+    pub fn main(&self) -> Option<&str> {
+        self.files.get(Self::MAIN).map(String::as_str)
+    }
+
+    pub fn style(&self) -> Option<&str> {
+        self.files.get(Self::STYLE).map(String::as_str)
+    }
+
+    pub fn head(&self) -> Option<&str> {
+        self.files.get(Self::HEAD).map(String::as_str)
+    }
+
+    /// Names of the extra crates defined by the snippet.
+    pub fn crates(&self) -> BTreeSet<&str> {
+        self.files
+            .keys()
+            .filter_map(|path| path.strip_prefix("crates/")?.strip_suffix("/src/lib.rs"))
+            .filter(|name| !name.contains('/'))
+            .collect()
+    }
+
+    /// Whether `name` can be the name of an extra crate: a letter followed by letters,
+    /// digits, `-` and `_`.
+    pub fn is_crate_name(name: &str) -> bool {
+        name.len() <= 64
+            && name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+}
+
+/// Crates that are compiled into the app template ahead of time. A snippet can
+/// opt into any of them through [`BuildOptions::features`].
+pub const PREINSTALLED_CRATES: &[&str] = &["yew-router", "yew-hooks", "stylist"];
+
+/// Crates snippets may add through [`BuildOptions::dependencies`], with what they're
+/// for. Anything else from crates.io is rejected even where custom dependencies are
+/// allowed.
+pub const ALLOWED_CRATES: &[(&str, &str)] = &[
+    ("serde", "Serialization framework"),
+    ("serde_json", "JSON support for serde"),
+    ("gloo", "Timers, storage, events and more for wasm"),
+    ("gloo-net", "HTTP requests, websockets and event sources"),
+    ("wasm-bindgen", "Interop between Rust and JavaScript"),
+    ("wasm-bindgen-futures", "Futures as promises"),
+    ("js-sys", "Bindings to JavaScript's builtins"),
+    ("web-sys", "Bindings to the web's APIs"),
+    ("implicit-clone", "Immutable types that are cheap to clone"),
+    ("rand", "Random number generation"),
+    ("getrandom", "Randomness from the OS or the browser"),
+    ("chrono", "Date and time"),
+    ("time", "Date and time"),
+    ("uuid", "Generating and parsing UUIDs"),
+    ("itertools", "Extra iterator adaptors"),
+    ("regex", "Regular expressions"),
+    ("once_cell", "Lazily initialized values"),
+    ("anyhow", "Flexible error type"),
+    ("thiserror", "Derive macro for error types"),
+    ("futures", "Async building blocks"),
+    ("log", "Logging facade"),
+    ("wasm-logger", "Logs to the browser console"),
+    ("pulldown-cmark", "Markdown parser"),
+    ("base64", "Base64 encoding"),
+    ("indexmap", "Hash map keeping insertion order"),
+];
+
+/// Named `RUSTFLAGS` presets a build can select through [`BuildOptions::rustflags`],
+/// along with the flags they stand for. Arbitrary flags are never accepted.
+pub const RUSTFLAGS_PRESETS: &[(&str, &str)] = &[
+    ("debug-assertions-off", "-C debug-assertions=off"),
+    ("codegen-units=1", "-C codegen-units=1"),
+    ("target-feature=+simd128", "-C target-feature=+simd128"),
+];
+
+/// Options controlling how the compiler builds a snippet. Sent as query parameters,
+/// the playground keeps them in its links the same way. Defaults are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildOptions {
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub profile: Profile,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub edition: Edition,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub toolchain: Toolchain,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub yew: YewVersion,
+    /// Extra `[dependencies]` entries, only honoured if the compiler allows custom dependencies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<String>,
+    /// Comma separated [`PREINSTALLED_CRATES`] to enable, e.g. `yew-router,stylist`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<String>,
+    /// Comma separated names of [`RUSTFLAGS_PRESETS`] to build with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustflags: Option<String>,
+    /// Strip the names and other custom sections from the built wasm. Keeping
+    /// them makes for a readable size report, stripping them for a smaller module.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub strip: bool,
+    /// Build a benchmark instead, which mounts the snippet's `App` and re-renders it
+    /// this many times, reporting the timings to the playground.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench: Option<u32>,
+}
+
+impl BuildOptions {
+    /// The options as a query string, without the `?`.
+    pub fn query(&self) -> String {
+        serde_urlencoded::to_string(self).expect("options serialize")
+    }
+
+    pub fn features(&self) -> impl Iterator<Item = &str> {
+        split_list(self.features.as_deref())
+    }
+
+    pub fn rustflags(&self) -> impl Iterator<Item = &str> {
+        split_list(self.rustflags.as_deref())
+    }
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn split_list(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|it| !it.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// 1-based location of a diagnostic in the snippet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    pub code: Option<String>,
+    pub span: Option<Span>,
+}
+
+/// A diagnostic along with the snippet file it points into, e.g. `src/main.rs`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDiagnostic {
+    pub path: String,
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatResponse {
+    Formatted(String),
+    ParseError {
+        rendered: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClippyResponse {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 1-based position of the cursor in the snippet's `src/main.rs`, with the column
+/// counted in UTF-16 code units like the editor does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionKind {
+    Function,
+    Field,
+    Variable,
+    Struct,
+    Enum,
+    EnumMember,
+    Trait,
+    Module,
+    Keyword,
+    Snippet,
+    TypeParameter,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: Option<String>,
+    /// Text replacing the word before the cursor when the item is picked.
+    pub insert_text: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletionResponse {
+    pub items: Vec<CompletionItem>,
+}
+
+/// A starter snippet listed in the playground's examples gallery. Its code and
+/// thumbnail are served separately.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExampleSummary {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+}
+
+/// One of the [`ALLOWED_CRATES`], as offered in the playground's dependency picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateSummary {
+    pub name: String,
+    pub description: String,
+}
+
+/// The recent releases of an allowed crate, newest first, as looked up on crates.io.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateInfo {
+    pub name: String,
+    pub versions: Vec<CrateVersion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateVersion {
+    pub num: String,
+    /// Its optional features, `default` aside.
+    pub features: Vec<String>,
+}
+
+/// A named version of a shared snippet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    /// RFC 3339.
+    pub created_at: String,
+}
+
+/// A [`Revision`] without its code, as they're listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevisionSummary {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+}
+
+/// A shared snippet found by searching, without its code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// RFC 3339.
+    pub created_at: String,
+}
+
+/// What links to a shared snippet unfurl to in chat apps and forums.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetMetadata {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// Of the snippet's `src/main.rs`.
+    pub lines: usize,
+    /// Of the snippet's `src/main.rs`, in bytes.
+    pub size: usize,
+    /// RFC 3339.
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewRevision {
+    pub name: String,
+    pub content: String,
+    /// The [`SharedSnippet::owner_token`] of the paste.
+    #[serde(default)]
+    pub owner_token: String,
+}
+
+/// A snippet to share, stored as a paste.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NewSnippet {
+    /// `src/main.rs`.
+    pub content: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// ID of the paste this one is a fork of.
+    #[serde(default)]
+    pub forked_from: Option<String>,
+    /// The style sheet of the preview.
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Markup of the head of the preview.
+    #[serde(default)]
+    pub head: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedSnippet {
+    pub id: String,
+    /// Only whoever shared the snippet gets it, saving revisions of the snippet takes
+    /// it. The paste keeps its hash, so it can't be had again.
+    pub owner_token: String,
+}
+
+/// Header the backend uses to tell the compiler how urgent a job is.
+pub const PRIORITY_HEADER: &str = "x-build-priority";
+
+/// Header carrying the id a client follows the progress of a build under.
+pub const PROGRESS_HEADER: &str = "x-build-progress";
+
+/// Header carrying the ID of a request, which its errors are reported and logged under.
+/// The backend passes its own on to the compiler.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// What the services respond with when a request fails.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// Identifies the kind of error, e.g. `paste_not_found`, unlike the message it
+    /// doesn't change.
+    pub code: String,
+    pub message: String,
+    /// Whether the same request may succeed if it's sent again later.
+    pub retryable: bool,
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.request_id {
+            Some(id) => write!(f, "{} (request {})", self.message, id),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ErrorResponse {}
+
+/// Version of the BSON the backend and the compiler exchange, raised with changes the
+/// other's previous release can't read, like a new variant of [`Response`]. Fields added
+/// to what's exchanged are `#[serde(default)]` instead, so the two can be deployed at
+/// different versions during rolling upgrades.
+///
+/// Version 2 has compile errors carry cargo's diagnostics along with its output.
+pub const WIRE_VERSION: u32 = 2;
+
+/// Header carrying the [`WIRE_VERSION`] of the requests and responses the backend and the
+/// compiler exchange. Releases from before it was introduced don't send it, which stands
+/// for version 0, the same BSON as version 1.
+pub const WIRE_VERSION_HEADER: &str = "x-wire-version";
+
+/// What a build is busy with, reported while it runs. Stages only move forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildStage {
+    /// Waiting for a build slot.
+    Queued,
+    CompilingDependencies,
+    CompilingSnippet,
+    Bindgen,
+    Optimizing,
+    /// The build finished, whether it succeeded or not.
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// A user is waiting on the result, e.g. the Run button.
+    Interactive,
+    /// Background work such as tests, analysis or gallery re-renders.
+    Batch,
+}
+
+impl Priority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Priority::Interactive => "interactive",
+            Priority::Batch => "batch",
+        }
+    }
+
+    pub fn from_header(value: &str) -> Option<Self> {
+        match value {
+            "interactive" => Some(Priority::Interactive),
+            "batch" => Some(Priority::Batch),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainVersions {
+    pub rustc: Option<String>,
+    pub cargo: Option<String>,
+    pub trunk: Option<String>,
+    pub wasm_bindgen: Option<String>,
+    pub wasm_opt: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceStatus {
+    pub key: String,
+    /// Seconds since the workspace was last used by a job.
+    pub idle_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerHealth {
+    pub toolchain: ToolchainVersions,
+    pub workspaces: Vec<WorkspaceStatus>,
+    pub queue_depth: usize,
+    pub busy: bool,
+    /// Disk used by workspaces as of the last janitor sweep, in bytes.
+    pub disk_usage: u64,
+    pub disk_quota: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What compilers before [`WIRE_VERSION`] 2 responded with, which backends have to
+    /// read while the two are being upgraded.
+    #[cfg(feature = "server")]
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum V1Response {
+        Output {
+            index_html: String,
+            js: String,
+            wasm: Vec<u8>,
+        },
+        CompileError(String),
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn reads_v1_output() {
+        let bytes = bson::to_vec(&V1Response::Output {
+            index_html: "<html></html>".to_string(),
+            js: "export default init;".to_string(),
+            wasm: vec![0, 97, 115, 109],
+        })
+        .unwrap();
+        let response: Response = bson::from_slice(&bytes).unwrap();
+        assert_eq!(
+            response,
+            Response::Output {
+                index_html: "<html></html>".to_string(),
+                js: "export default init;".to_string(),
+                wasm: vec![0, 97, 115, 109],
+                timings: BuildTimings::default(),
+                build_id: None,
+                integrity: Integrity::default(),
+                manifest: None,
+            }
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn reads_v1_compile_error() {
+        let stderr = "error[E0425]: cannot find value `x` in this scope";
+        let bytes = bson::to_vec(&V1Response::CompileError(stderr.to_string())).unwrap();
+        let response: Response = bson::from_slice(&bytes).unwrap();
+        assert_eq!(
+            response,
+            Response::CompileError(CompileError {
+                stderr: stderr.to_string(),
+                diagnostics: Vec::new(),
+            })
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn writes_v1_compile_error() {
+        let stderr = "error[E0425]: cannot find value `x` in this scope";
+        let response = Response::CompileError(CompileError {
+            stderr: stderr.to_string(),
+            diagnostics: Vec::new(),
+        });
+        let bytes = response::REQUESTED_VERSION
+            .sync_scope(1, || bson::to_vec(&response))
+            .unwrap();
+        let response: V1Response = bson::from_slice(&bytes).unwrap();
+        assert_eq!(response, V1Response::CompileError(stderr.to_string()));
+    }
+
+    /// Backends before the defaults were left out sent every option but the unset ones.
+    #[test]
+    fn reads_options_of_older_backends() {
+        let options: BuildOptions = serde_urlencoded::from_str(
+            "profile=release&edition=2021&toolchain=stable&yew=0.21&strip=false",
+        )
+        .unwrap();
+        assert_eq!(options, BuildOptions::default());
+
+        let options: BuildOptions = serde_urlencoded::from_str(
+            "profile=min-size&edition=2018&toolchain=nightly&yew=0.20&strip=true&features=stylist",
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            BuildOptions {
+                profile: Profile::MinSize,
+                edition: Edition::E2018,
+                toolchain: Toolchain::Nightly,
+                yew: YewVersion::V0_20,
+                features: Some("stylist".to_string()),
+                strip: true,
+                ..BuildOptions::default()
+            }
+        );
+    }
+
+    #[test]
+    fn reads_options_left_out() {
+        let options: BuildOptions = serde_urlencoded::from_str("").unwrap();
+        assert_eq!(options, BuildOptions::default());
+        assert_eq!(options.query(), "");
+    }
+}
@@ -1,35 +1,187 @@
-use axum::http::{header, HeaderValue, StatusCode};
-use axum::{
-    body::{self, Full},
-    response::{IntoResponse, Response},
-};
+use axum::async_trait;
+use axum::body::{self, Bytes, Full, HttpBody};
+use axum::extract::{FromRequest, RequestParts};
+use axum::http::{header, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::BoxError;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-pub struct Bson<T>(pub T);
-impl<T> IntoResponse for Bson<T>
+use crate::errors::ApiError;
+use crate::{WIRE_VERSION, WIRE_VERSION_HEADER};
+
+/// The formats [`Negotiate`] reads and writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// What the backend and the compiler exchange.
+    Bson,
+    /// For reading responses in the browser while debugging.
+    Json,
+    Cbor,
+}
+
+impl Format {
+    pub fn mime(self) -> &'static str {
+        match self {
+            Format::Bson => "application/bson",
+            Format::Json => "application/json",
+            Format::Cbor => "application/cbor",
+        }
+    }
+
+    /// The format of a media type, which may have parameters like `charset`.
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        let essence = mime.split(';').next().unwrap_or_default().trim();
+        [Format::Bson, Format::Json, Format::Cbor]
+            .into_iter()
+            .find(|it| essence.eq_ignore_ascii_case(it.mime()))
+    }
+
+    /// The format an `Accept` header asks for, the one it prefers if it names any.
+    /// Clients that accept anything get BSON, what the services always responded with,
+    /// and those asking for something else, like browsers for HTML, the readable JSON.
+    pub fn accepted(accept: Option<&str>) -> Self {
+        let mut preferred: Option<(f32, Format)> = None;
+        let mut anything = true;
+        for range in accept.unwrap_or("*/*").split(',') {
+            let mut params = range.split(';');
+            let mime = params.next().unwrap_or_default().trim();
+            let quality = params
+                .find_map(|it| it.trim().strip_prefix("q="))
+                .and_then(|it| it.parse().ok())
+                .unwrap_or(1.0);
+            anything &= matches!(mime, "*/*" | "application/*" | "");
+            match Format::from_mime(mime) {
+                Some(format) if quality > preferred.map_or(0.0, |(it, _)| it) => {
+                    preferred = Some((quality, format))
+                }
+                _ => {}
+            }
+        }
+        match preferred {
+            Some((_, format)) => format,
+            None if anything => Format::Bson,
+            None => Format::Json,
+        }
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> anyhow::Result<Vec<u8>> {
+        Ok(match self {
+            Format::Bson => bson::to_vec(value)?,
+            Format::Json => serde_json::to_vec(value)?,
+            Format::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::ser::into_writer(value, &mut bytes)?;
+                bytes
+            }
+        })
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> anyhow::Result<T> {
+        Ok(match self {
+            Format::Bson => bson::from_slice(bytes)?,
+            Format::Json => serde_json::from_slice(bytes)?,
+            Format::Cbor => ciborium::de::from_reader(bytes)?,
+        })
+    }
+}
+
+tokio::task_local! {
+    static ACCEPTED: Format;
+    pub(crate) static REQUESTED_VERSION: u32;
+}
+
+/// Middleware that has [`Negotiate`]d responses in the [`Format`] requests accept,
+/// and in the [`WIRE_VERSION`] they were sent in if it's an older one. Without it
+/// they're BSON of the current version.
+pub async fn negotiate<B>(req: Request<B>, next: Next<B>) -> Response {
+    let accept = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|it| it.to_str().ok());
+    let format = Format::accepted(accept);
+    // releases from before the header was introduced don't send it
+    let version = req
+        .headers()
+        .get(WIRE_VERSION_HEADER)
+        .and_then(|it| it.to_str().ok())
+        .and_then(|it| it.parse::<u32>().ok())
+        .unwrap_or(0);
+    let next = REQUESTED_VERSION.scope(version, next.run(req));
+    ACCEPTED.scope(format, next).await
+}
+
+/// The [`WIRE_VERSION`] responses are written in, the request's if it's an older one.
+pub fn requested_version() -> u32 {
+    REQUESTED_VERSION
+        .try_with(|it| *it)
+        .unwrap_or(WIRE_VERSION)
+        .min(WIRE_VERSION)
+}
+
+/// A body in any [`Format`]. Requests are read in the format of their `Content-Type`,
+/// and fail as incompatible if they were sent in another [`WIRE_VERSION`] that can't be
+/// read. Responses are written in the format the request accepts, tagged with the
+/// [`WIRE_VERSION`] they're written in.
+pub struct Negotiate<T>(pub T);
+
+impl<T> IntoResponse for Negotiate<T>
 where
     T: Serialize,
 {
     fn into_response(self) -> Response {
-        let bytes = match bson::to_vec(&self.0) {
+        let format = ACCEPTED.try_with(|it| *it).unwrap_or(Format::Bson);
+        let bytes = match format.encode(&self.0) {
             Ok(res) => res,
-            Err(err) => {
-                return Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .header(
-                        header::CONTENT_TYPE,
-                        HeaderValue::from_static(mime::TEXT_PLAIN_UTF_8.as_ref()),
-                    )
-                    .body(body::boxed(Full::from(err.to_string())))
-                    .unwrap();
-            }
+            Err(err) => return ApiError::Unknown(err).into_response(),
         };
 
         let mut res = Response::new(body::boxed(Full::from(bytes)));
         res.headers_mut().insert(
             header::CONTENT_TYPE,
-            HeaderValue::from_static("application/bson"),
+            HeaderValue::from_static(format.mime()),
         );
+        res.headers_mut()
+            .insert(WIRE_VERSION_HEADER, HeaderValue::from(requested_version()));
         res
     }
 }
+
+#[async_trait]
+impl<B, T> FromRequest<B> for Negotiate<T>
+where
+    B: HttpBody + Send,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    T: DeserializeOwned,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|it| it.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let format =
+            Format::from_mime(&content_type).ok_or(ApiError::UnsupportedMediaType(content_type))?;
+        let version = req
+            .headers()
+            .get(WIRE_VERSION_HEADER)
+            .and_then(|it| it.to_str().ok())
+            .and_then(|it| it.parse::<u32>().ok());
+        let bytes = Bytes::from_request(req)
+            .await
+            .map_err(|e| ApiError::InvalidBody(e.to_string()))?;
+        let value = format.decode(&bytes).map_err(|e| match version {
+            // only the services send it, what browsers send is just invalid
+            Some(version) if version != WIRE_VERSION => {
+                ApiError::IncompatibleWireVersionRequest(version, e.to_string())
+            }
+            _ => ApiError::InvalidBody(e.to_string()),
+        })?;
+        Ok(Negotiate(value))
+    }
+}